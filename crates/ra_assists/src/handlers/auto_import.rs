@@ -49,7 +49,13 @@ pub(crate) fn auto_import(ctx: AssistCtx) -> Option<Assist> {
     let mut group = ctx.add_assist_group(auto_import_assets.get_import_group_message());
     for import in proposed_imports {
         group.add_assist(AssistId("auto_import"), format!("Import `{}`", &import), range, |edit| {
-            insert_use_statement(&auto_import_assets.syntax_under_caret, &import, edit);
+            let sema = edit.ctx().sema;
+            insert_use_statement(
+                &auto_import_assets.syntax_under_caret,
+                &import,
+                sema,
+                edit.text_edit_builder(),
+            );
         });
     }
     group.finish()
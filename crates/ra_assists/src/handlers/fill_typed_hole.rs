@@ -0,0 +1,153 @@
+use ra_syntax::ast::{self, AstNode};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: fill_typed_hole
+//
+// Replaces a typed hole, like `_` or `todo!()`, with an expression of the expected type.
+//
+// ```
+// fn main() {
+//     let x: u32 = 92;
+//     let y: u32 = <|>_;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x: u32 = 92;
+//     let y: u32 = x;
+// }
+// ```
+pub(crate) fn fill_typed_hole(ctx: AssistCtx) -> Option<Assist> {
+    let hole = match ctx.find_node_at_offset::<ast::UnderscoreExpr>() {
+        Some(it) => ast::Expr::from(it),
+        None => ast::Expr::from(ctx.find_node_at_offset::<ast::MacroCall>()?),
+    };
+    // Only typed holes have an expected type recorded, so this also checks
+    // that the macro call is `todo!()` or `unimplemented!()`.
+    let goal = ctx.sema.expected_type_of_hole(&hole)?;
+    let scope = ctx.sema.scope(hole.syntax());
+    let module = scope.module()?;
+    let terms = scope.term_search(&goal);
+    if terms.is_empty() {
+        return None;
+    }
+
+    let range = hole.syntax().text_range();
+    let mut group = ctx.add_assist_group("Fill typed hole");
+    for term in terms {
+        let text = term.to_source(ctx.db, module);
+        group.add_assist(
+            AssistId("fill_typed_hole"),
+            format!("Replace with `{}`", text),
+            range,
+            |edit| edit.replace(range, text),
+        );
+    }
+    group.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    #[test]
+    fn fill_underscore_with_local() {
+        check_assist(
+            fill_typed_hole,
+            r"
+fn main() {
+    let s = String::new();
+    let n: u32 = 0;
+    let x: u32 = <|>_;
+}
+struct String;
+",
+            r"
+fn main() {
+    let s = String::new();
+    let n: u32 = 0;
+    let x: u32 = <|>n;
+}
+struct String;
+",
+        );
+    }
+
+    #[test]
+    fn fill_todo_with_constructor() {
+        check_assist(
+            fill_typed_hole,
+            r"
+macro_rules! todo { () => { panic() } }
+fn panic() -> ! { loop {} }
+struct Point { x: i32, y: i32 }
+fn make() -> Point {
+    let x: i32 = 1;
+    <|>todo!()
+}
+",
+            r"
+macro_rules! todo { () => { panic() } }
+fn panic() -> ! { loop {} }
+struct Point { x: i32, y: i32 }
+fn make() -> Point {
+    let x: i32 = 1;
+    <|>Point { x, y: x }
+}
+",
+        );
+    }
+
+    #[test]
+    fn fill_with_function_call() {
+        check_assist(
+            fill_typed_hole,
+            r"
+struct Wrapper(u8);
+fn wrap(b: u8) -> Wrapper { Wrapper(b) }
+fn main() {
+    let b: u8 = 0;
+    let w: Wrapper = <|>_;
+}
+",
+            r"
+struct Wrapper(u8);
+fn wrap(b: u8) -> Wrapper { Wrapper(b) }
+fn main() {
+    let b: u8 = 0;
+    let w: Wrapper = <|>Wrapper(b);
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_other_macros() {
+        check_assist_not_applicable(
+            fill_typed_hole,
+            r"
+macro_rules! foo { () => { panic() } }
+fn panic() -> ! { loop {} }
+fn main() {
+    let x: u32 = 1;
+    let y: u32 = <|>foo!();
+}
+",
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_candidates() {
+        check_assist_not_applicable(
+            fill_typed_hole,
+            r"
+fn main() {
+    let y: u32 = <|>_;
+}
+",
+        );
+    }
+}
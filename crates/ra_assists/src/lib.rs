@@ -131,6 +131,7 @@ mod handlers {
     mod change_visibility;
     mod early_return;
     mod fill_match_arms;
+    mod fill_typed_hole;
     mod flip_binexpr;
     mod flip_comma;
     mod flip_trait_bound;
@@ -168,6 +169,7 @@ mod handlers {
             change_visibility::change_visibility,
             early_return::convert_to_guarded_return,
             fill_match_arms::fill_match_arms,
+            fill_typed_hole::fill_typed_hole,
             flip_binexpr::flip_binexpr,
             flip_comma::flip_comma,
             flip_trait_bound::flip_trait_bound,
@@ -312,6 +312,25 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_fill_typed_hole() {
+    check_doc_test(
+        "fill_typed_hole",
+        r#####"
+fn main() {
+    let x: u32 = 92;
+    let y: u32 = <|>_;
+}
+"#####,
+        r#####"
+fn main() {
+    let x: u32 = 92;
+    let y: u32 = x;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_flip_binexpr() {
     check_doc_test(
@@ -49,6 +49,21 @@ impl CfgOptions {
         self.key_values.insert((key, value));
     }
 
+    /// Returns all atomic options, like `unix`.
+    pub fn atoms(&self) -> impl Iterator<Item = &SmolStr> + '_ {
+        self.atoms.iter()
+    }
+
+    /// Returns the keys of all key-value options, like `target_arch`.
+    pub fn keys(&self) -> impl Iterator<Item = &SmolStr> + '_ {
+        self.key_values.iter().map(|(k, _)| k)
+    }
+
+    /// Returns all values set for the given key, like the enabled `feature`s.
+    pub fn key_values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a SmolStr> + 'a {
+        self.key_values.iter().filter(move |(k, _)| k.as_str() == key).map(|(_, v)| v)
+    }
+
     /// Shortcut to set features
     pub fn insert_features(&mut self, iter: impl IntoIterator<Item = SmolStr>) {
         iter.into_iter().for_each(|feat| self.insert_key_value("feature".into(), feat));
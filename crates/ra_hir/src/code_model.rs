@@ -9,12 +9,13 @@ use hir_def::{
     builtin_type::BuiltinType,
     docs::Documentation,
     expr::{BindingAnnotation, Pat, PatId},
+    path::Path,
     per_ns::PerNs,
     resolver::{HasResolver, Resolver},
     type_ref::{Mutability, TypeRef},
     AdtId, AssocContainerId, ConstId, DefWithBodyId, EnumId, FunctionId, GenericDefId, HasModule,
-    ImplId, LocalEnumVariantId, LocalFieldId, LocalModuleId, Lookup, ModuleId, StaticId, StructId,
-    TraitId, TypeAliasId, TypeParamId, UnionId,
+    ImplId, LocalEnumVariantId, LocalFieldId, LocalModuleId, Lookup, ModuleDefId, ModuleId,
+    StaticId, StructId, TraitId, TypeAliasId, TypeParamId, UnionId,
 };
 use hir_expand::{
     diagnostics::DiagnosticSink,
@@ -22,21 +23,28 @@ use hir_expand::{
     MacroDefId, MacroDefKind,
 };
 use hir_ty::{
-    autoderef, display::HirFormatter, expr::ExprValidator, method_resolution, ApplicationTy,
-    Canonical, InEnvironment, Substs, TraitEnvironment, Ty, TyDefId, TypeCtor,
+    autoderef, coherence,
+    display::HirFormatter,
+    expr::ExprValidator,
+    layout::{FieldLayout, Layout, LayoutError},
+    method_resolution,
+    object_safety::{self, MethodViolationCode},
+    traits::{self, SolverTrace},
+    ApplicationTy, BoundVar, Canonical, DebruijnIndex, FnSig, GenericPredicate, InEnvironment,
+    Substs, TraitEnvironment, Ty, TyDefId, TypeCtor, TypeWalk,
 };
 use ra_db::{CrateId, CrateName, Edition, FileId};
 use ra_prof::profile;
 use ra_syntax::{
     ast::{self, AttrsOwner, NameOwner},
-    AstNode,
+    AstNode, TextSize,
 };
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
     db::{DefDatabase, HirDatabase},
     has_source::HasSource,
-    CallableDef, HirDisplay, InFile, Name,
+    source_analyzer, CallableDef, HirDisplay, InFile, Name, PathResolution,
 };
 
 /// hir::Crate describes a single crate. It's the main interface with which
@@ -66,7 +74,6 @@ impl Crate {
             .collect()
     }
 
-    // FIXME: add `transitive_reverse_dependencies`.
     pub fn reverse_dependencies(self, db: &dyn HirDatabase) -> Vec<Crate> {
         let crate_graph = db.crate_graph();
         crate_graph
@@ -78,6 +85,30 @@ impl Crate {
             .collect()
     }
 
+    /// This crate and every crate that depends on it, directly or not.
+    pub fn transitive_reverse_dependencies(self, db: &dyn HirDatabase) -> Vec<Crate> {
+        let crate_graph = db.crate_graph();
+        let mut reverse_deps: FxHashMap<CrateId, Vec<CrateId>> = FxHashMap::default();
+        for krate in crate_graph.iter() {
+            for dep in crate_graph[krate].dependencies.iter() {
+                reverse_deps.entry(dep.crate_id).or_default().push(krate);
+            }
+        }
+
+        let mut res = vec![self.id];
+        let mut seen: FxHashSet<CrateId> = res.iter().copied().collect();
+        let mut i = 0;
+        while i < res.len() {
+            for &krate in reverse_deps.get(&res[i]).into_iter().flatten() {
+                if seen.insert(krate) {
+                    res.push(krate);
+                }
+            }
+            i += 1;
+        }
+        res.into_iter().map(|id| Crate { id }).collect()
+    }
+
     pub fn root_module(self, db: &dyn HirDatabase) -> Option<Module> {
         let module_id = db.crate_def_map(self.id).root;
         Some(Module::new(self, module_id))
@@ -200,6 +231,13 @@ impl Module {
         Some(self.with_module_id(parent_id))
     }
 
+    /// Resolves `path` as if it was written in this module, like rustdoc does
+    /// for intra-doc links.
+    pub fn resolve_path(self, db: &dyn HirDatabase, path: &Path) -> Option<PathResolution> {
+        let resolver = self.id.resolver(db.upcast());
+        source_analyzer::resolve_hir_path(db, &resolver, path)
+    }
+
     pub fn path_to_root(self, db: &dyn HirDatabase) -> Vec<Module> {
         let mut res = vec![self];
         let mut curr = self;
@@ -260,11 +298,7 @@ impl Module {
         }
 
         for impl_def in self.impl_defs(db) {
-            for item in impl_def.items(db) {
-                if let AssocItem::Function(f) = item {
-                    f.diagnostics(db, sink);
-                }
-            }
+            impl_def.diagnostics(db, sink);
         }
     }
 
@@ -329,6 +363,24 @@ impl Field {
     pub fn parent_def(&self, _db: &dyn HirDatabase) -> VariantDef {
         self.parent
     }
+
+    /// The offset, size and alignment of this field in the layout of its
+    /// parent, which must not be generic.
+    pub fn layout(&self, db: &dyn HirDatabase) -> Result<FieldLayout, LayoutError> {
+        let (adt, variant_idx) = match self.parent {
+            VariantDef::Struct(it) => (Adt::from(it), 0),
+            VariantDef::Union(it) => (Adt::from(it), 0),
+            VariantDef::EnumVariant(it) => {
+                (Adt::from(it.parent), u32::from(it.id.into_raw()) as usize)
+            }
+        };
+        let layout = adt.layout(db)?;
+        layout
+            .variant(variant_idx)
+            .and_then(|it| it.fields.get(u32::from(self.id.into_raw()) as usize))
+            .copied()
+            .ok_or(LayoutError::NotConcrete)
+    }
 }
 
 impl HasVisibility for Field {
@@ -516,6 +568,14 @@ impl Adt {
             Adt::Enum(e) => e.name(db),
         }
     }
+
+    /// Computes the memory layout of this ADT. Fails for generic ADTs, since
+    /// their layout depends on the type arguments.
+    pub fn layout(self, db: &dyn HirDatabase) -> Result<Arc<Layout>, LayoutError> {
+        let id = AdtId::from(self);
+        let ty = Ty::apply(TypeCtor::Adt(id), Substs::type_params(db, id));
+        db.layout_of(id.module(db.upcast()).krate, ty)
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -614,6 +674,23 @@ impl Function {
         db.function_data(self.id).params.clone()
     }
 
+    /// The types of the parameters, including `self`.
+    pub fn param_types(self, db: &dyn HirDatabase) -> Vec<Type> {
+        let krate = self.module(db).id.krate;
+        let sig = self.signature(db);
+        sig.params().iter().map(|ty| Type::new(db, krate, self.id, ty.clone())).collect()
+    }
+
+    pub fn ret_type(self, db: &dyn HirDatabase) -> Type {
+        let krate = self.module(db).id.krate;
+        Type::new(db, krate, self.id, self.signature(db).ret().clone())
+    }
+
+    fn signature(self, db: &dyn HirDatabase) -> FnSig {
+        let substs = Substs::type_params(db, self.id);
+        db.callable_item_signature(self.id.into()).subst(&substs)
+    }
+
     pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink) {
         let _p = profile("Function::diagnostics");
         let infer = db.infer(self.id.into());
@@ -683,6 +760,26 @@ pub struct Trait {
 }
 
 impl Trait {
+    /// Finds the trait marked with `#[lang = "name"]` among the dependencies
+    /// of `krate`.
+    pub fn lang(db: &dyn HirDatabase, krate: Crate, name: &str) -> Option<Trait> {
+        db.lang_item(krate.id, name.into())?.as_trait().map(Trait::from)
+    }
+
+    /// All traits declared in `krate`, collected from the scopes of its modules.
+    pub fn all_in_crate(db: &dyn HirDatabase, krate: Crate) -> Vec<Trait> {
+        let def_map = db.crate_def_map(krate.id);
+        def_map
+            .modules
+            .iter()
+            .flat_map(|(_, module)| module.scope.declarations())
+            .filter_map(|it| match it {
+                ModuleDefId::TraitId(id) => Some(Trait { id }),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn module(self, db: &dyn HirDatabase) -> Module {
         Module { id: self.id.lookup(db.upcast()).container.module(db.upcast()) }
     }
@@ -698,6 +795,48 @@ impl Trait {
     pub fn is_auto(self, db: &dyn HirDatabase) -> bool {
         db.trait_data(self.id).auto
     }
+
+    /// The traits this trait directly extends, like `A` and `B` in
+    /// `trait T: A + B`.
+    pub fn direct_super_traits(self, db: &dyn HirDatabase) -> Vec<Trait> {
+        hir_ty::direct_super_traits(db.upcast(), self.id).into_iter().map(Trait::from).collect()
+    }
+
+    /// Returns the reasons why `dyn Trait` isn't a valid type, if any.
+    pub fn object_safety_violations(self, db: &dyn HirDatabase) -> Vec<ObjectSafetyViolation> {
+        db.object_safety(self.id)
+            .iter()
+            .map(|it| match *it {
+                object_safety::ObjectSafetyViolation::SizedSelf(id) => {
+                    ObjectSafetyViolation::SizedSelf(Trait { id })
+                }
+                object_safety::ObjectSafetyViolation::SupertraitSelf(id) => {
+                    ObjectSafetyViolation::SupertraitSelf(Trait { id })
+                }
+                object_safety::ObjectSafetyViolation::Method(id, code) => {
+                    ObjectSafetyViolation::Method(Function { id }, code)
+                }
+                object_safety::ObjectSafetyViolation::AssocConst(id) => {
+                    ObjectSafetyViolation::AssocConst(Const { id })
+                }
+            })
+            .collect()
+    }
+
+    pub fn is_object_safe(self, db: &dyn HirDatabase) -> bool {
+        db.object_safety(self.id).is_empty()
+    }
+}
+
+/// A reason why a trait is not object safe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectSafetyViolation {
+    /// The trait or one of its supertraits requires `Self: Sized`.
+    SizedSelf(Trait),
+    /// A supertrait bound mentions `Self`, like `trait Foo: PartialEq<Self>`.
+    SupertraitSelf(Trait),
+    Method(Function, MethodViolationCode),
+    AssocConst(Const),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -760,6 +899,18 @@ impl MacroDef {
         self.source(db).value.name().map(|it| it.as_name())
     }
 
+    /// For a `macro_rules!` macro, the index of the rule used to expand a call
+    /// with the given arguments, and the index of the top-level metavariable or
+    /// repetition of the rule's pattern the arguments at `offset` belong to.
+    pub fn matching_fragment(
+        self,
+        db: &dyn HirDatabase,
+        args: &ast::TokenTree,
+        offset: TextSize,
+    ) -> Option<(usize, Option<usize>)> {
+        db.macro_def(self.id)?.0.matching_fragment(args, offset)
+    }
+
     /// Indicate it is a proc-macro
     pub fn is_proc_macro(&self) -> bool {
         matches!(self.id.kind, MacroDefKind::CustomDerive(_))
@@ -823,6 +974,16 @@ impl AssocItem {
             AssocItem::TypeAlias(t) => t.module(db),
         }
     }
+    /// Checks whether `other` is an item of the same kind and name, like an
+    /// item of a trait and the item implementing it in an impl.
+    pub fn corresponds_to(self, db: &dyn HirDatabase, other: AssocItem) -> bool {
+        match (self, other) {
+            (AssocItem::Function(a), AssocItem::Function(b)) => a.name(db) == b.name(db),
+            (AssocItem::Const(a), AssocItem::Const(b)) => a.name(db) == b.name(db),
+            (AssocItem::TypeAlias(a), AssocItem::TypeAlias(b)) => a.name(db) == b.name(db),
+            _ => false,
+        }
+    }
     pub fn container(self, db: &dyn HirDatabase) -> AssocItemContainer {
         let container = match self {
             AssocItem::Function(it) => it.id.lookup(db.upcast()).container,
@@ -984,6 +1145,11 @@ impl ImplDef {
         db.impl_data(self.id).target_trait.clone()
     }
 
+    /// The trait this impl implements, if any and if it resolves.
+    pub fn target_trait_def(&self, db: &dyn HirDatabase) -> Option<Trait> {
+        db.impl_trait(self.id).map(|it| it.value.trait_.into())
+    }
+
     pub fn target_type(&self, db: &dyn HirDatabase) -> TypeRef {
         db.impl_data(self.id).target_type.clone()
     }
@@ -1036,12 +1202,22 @@ impl ImplDef {
 
         Some(item.with_value(attr))
     }
+
+    pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink) {
+        let _p = profile("ImplDef::diagnostics");
+        coherence::add_coherence_diagnostics(db, self.id, sink);
+        for item in self.items(db) {
+            if let AssocItem::Function(f) = item {
+                f.diagnostics(db, sink);
+            }
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Type {
-    krate: CrateId,
-    ty: InEnvironment<Ty>,
+    pub(crate) krate: CrateId,
+    pub(crate) ty: InEnvironment<Ty>,
 }
 
 impl Type {
@@ -1083,6 +1259,10 @@ impl Type {
         matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Bool, .. }))
     }
 
+    pub fn is_reference(&self) -> bool {
+        matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Ref(..), .. }))
+    }
+
     pub fn is_mutable_reference(&self) -> bool {
         matches!(
             self.ty.value,
@@ -1094,6 +1274,19 @@ impl Type {
         matches!(self.ty.value, Ty::Unknown)
     }
 
+    /// Whether `self` and `other` are the same type. Unlike `==`, this ignores
+    /// the environments the types come from.
+    pub fn is_same_type(&self, other: &Type) -> bool {
+        self.ty.value == other.ty.value
+    }
+
+    pub fn is_unit(&self) -> bool {
+        matches!(
+            self.ty.value,
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Tuple { cardinality: 0 }, .. })
+        )
+    }
+
     /// Checks that particular type `ty` implements `std::future::Future`.
     /// This function is used in `.await` syntax completion.
     pub fn impls_future(&self, db: &dyn HirDatabase) -> bool {
@@ -1116,6 +1309,23 @@ impl Type {
         )
     }
 
+    /// Checks that particular type `ty` implements `Copy`, so that passing it
+    /// by value doesn't move it. `None` if there is no `Copy` trait to check.
+    pub fn is_copy(&self, db: &dyn HirDatabase) -> Option<bool> {
+        let krate = self.krate;
+
+        let copy_trait = db.lang_item(krate, "copy".into()).and_then(|it| it.as_trait())?;
+
+        let canonical_ty = Canonical { value: self.ty.value.clone(), num_vars: 0 };
+        Some(method_resolution::implements_trait(
+            &canonical_ty,
+            db,
+            self.ty.environment.clone(),
+            krate,
+            copy_trait,
+        ))
+    }
+
     pub fn impls_trait(&self, db: &dyn HirDatabase, trait_: Trait, args: &[Type]) -> bool {
         let trait_ref = hir_ty::TraitRef {
             trait_: trait_.id,
@@ -1136,6 +1346,59 @@ impl Type {
         db.trait_solve(self.krate, goal).is_some()
     }
 
+    /// Runs the trait solver on `Self: trait_<args>`, recording what it does
+    /// along the way. Trait parameters not given in `args` are left for the
+    /// solver to find. This is only meant for debugging.
+    pub fn trace_impls_trait(
+        &self,
+        db: &dyn HirDatabase,
+        trait_: Trait,
+        args: &[Type],
+    ) -> SolverTrace {
+        let vars = (0..).map(|idx| Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, idx)));
+        let substs = Substs::build_for_def(db, trait_.id)
+            .push(self.ty.value.clone())
+            .fill(args.iter().map(|t| t.ty.value.clone()).chain(vars))
+            .build();
+        let num_vars = substs.len().saturating_sub(1 + args.len());
+        let trait_ref = hir_ty::TraitRef { trait_: trait_.id, substs };
+
+        let goal = Canonical {
+            value: hir_ty::InEnvironment::new(
+                self.ty.environment.clone(),
+                hir_ty::Obligation::Trait(trait_ref),
+            ),
+            num_vars,
+        };
+
+        traits::trace_trait_solve(db, self.krate, goal)
+    }
+
+    /// Runs the trait solver on the where clauses of the function item this
+    /// type refers to, with the generic arguments inferred for it. Returns
+    /// `None` if this isn't the type of a function item.
+    pub fn trace_callable_where_clauses(&self, db: &dyn HirDatabase) -> Option<Vec<SolverTrace>> {
+        let (def, substs) = self.ty.value.as_callable()?;
+        let def: GenericDefId = match def {
+            CallableDef::FunctionId(it) => it.into(),
+            CallableDef::StructId(it) => it.into(),
+            CallableDef::EnumVariantId(it) => it.parent.into(),
+        };
+        let traces = db
+            .generic_predicates(def)
+            .iter()
+            .filter_map(|pred| hir_ty::Obligation::from_predicate(pred.clone().subst(substs)))
+            .map(|obligation| {
+                let goal = Canonical {
+                    value: hir_ty::InEnvironment::new(self.ty.environment.clone(), obligation),
+                    num_vars: 0,
+                };
+                traits::trace_trait_solve(db, self.krate, goal)
+            })
+            .collect();
+        Some(traces)
+    }
+
     // FIXME: this method is broken, as it doesn't take closures into account.
     pub fn as_callable(&self) -> Option<CallableDef> {
         Some(self.ty.value.as_callable()?.0)
@@ -1152,6 +1415,17 @@ impl Type {
         )
     }
 
+    /// Returns this type with each `impl Trait` returned from a function
+    /// replaced by the concrete type it hides, or `None` if there is nothing
+    /// to reveal.
+    pub fn reveal_opaque_types(&self, db: &dyn HirDatabase) -> Option<Type> {
+        let revealed = self.ty.value.clone().reveal_opaque_types(db);
+        if revealed == self.ty.value {
+            return None;
+        }
+        Some(self.derived(revealed))
+    }
+
     pub fn contains_unknown(&self) -> bool {
         return go(&self.ty.value);
 
@@ -1295,6 +1569,33 @@ impl Type {
         Some(adt.into())
     }
 
+    /// The ADTs and traits this type refers to, like `Vec`, `Rc` and `Fn` for
+    /// `Vec<Rc<dyn Fn()>>`.
+    pub fn mentioned_defs(&self) -> Vec<ModuleDef> {
+        let mut res = Vec::new();
+        let mut push = |def: ModuleDef| {
+            if !res.contains(&def) {
+                res.push(def);
+            }
+        };
+        self.ty.value.walk(&mut |ty| {
+            let predicates = match ty {
+                Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(it), .. }) => {
+                    return push(ModuleDef::Adt((*it).into()))
+                }
+                Ty::Dyn(predicates) => predicates,
+                Ty::Opaque(it) => &it.predicates,
+                _ => return,
+            };
+            for predicate in predicates.iter() {
+                if let GenericPredicate::Implemented(trait_ref) = predicate {
+                    push(ModuleDef::Trait(trait_ref.trait_.into()));
+                }
+            }
+        });
+        res
+    }
+
     // FIXME: provide required accessors such that it becomes implementable from outside.
     pub fn is_equal_for_find_impls(&self, other: &Type) -> bool {
         match (&self.ty.value, &other.ty.value) {
@@ -14,12 +14,13 @@ pub use hir_expand::db::{
     MacroArgQuery, MacroDefQuery, MacroExpandQuery, ParseMacroQuery,
 };
 pub use hir_ty::db::{
-    AssociatedTyDataQuery, AssociatedTyValueQuery, CallableItemSignatureQuery, FieldTypesQuery,
-    GenericDefaultsQuery, GenericPredicatesForParamQuery, GenericPredicatesQuery, HirDatabase,
-    HirDatabaseStorage, ImplDatumQuery, ImplSelfTyQuery, ImplTraitQuery, ImplsForTraitQuery,
-    ImplsInCrateQuery, InferQueryQuery, InternAssocTyValueQuery, InternChalkImplQuery,
-    InternTypeCtorQuery, InternTypeParamIdQuery, StructDatumQuery, TraitDatumQuery,
-    TraitSolveQuery, TyQuery, ValueTyQuery,
+    AssociatedTyDataQuery, AssociatedTyValueQuery, CallableItemSignatureQuery,
+    CoherenceErrorsQuery, FieldTypesQuery, GenericDefaultsQuery, GenericPredicatesForParamQuery,
+    GenericPredicatesQuery, HirDatabase, HirDatabaseStorage, ImplDatumQuery, ImplSelfTyQuery,
+    ImplTraitQuery, ImplsForTraitQuery, ImplsInCrateQuery, InferQueryQuery,
+    InternAssocTyValueQuery, InternChalkImplQuery, InternTypeCtorQuery, InternTypeParamIdQuery,
+    LayoutOfQuery, ObjectSafetyQuery, StructDatumQuery, TraitDatumQuery, TraitSolveQuery,
+    TraitSolverConfigQuery, TyQuery, ValueTyQuery,
 };
 
 #[test]
@@ -48,16 +48,18 @@ mod from_id;
 mod code_model;
 
 mod has_source;
+mod term_search;
 
 pub use crate::{
     code_model::{
         Adt, AsAssocItem, AssocItem, AssocItemContainer, AttrDef, Const, Crate, CrateDependency,
         DefWithBody, Docs, Enum, EnumVariant, Field, FieldSource, Function, GenericDef, HasAttrs,
-        HasVisibility, ImplDef, Local, MacroDef, Module, ModuleDef, ScopeDef, Static, Struct,
-        Trait, Type, TypeAlias, TypeParam, Union, VariantDef, Visibility,
+        HasVisibility, ImplDef, Local, MacroDef, Module, ModuleDef, ObjectSafetyViolation,
+        ScopeDef, Static, Struct, Trait, Type, TypeAlias, TypeParam, Union, VariantDef, Visibility,
     },
     has_source::HasSource,
     semantics::{original_range, PathResolution, Semantics, SemanticsScope},
+    term_search::Term,
 };
 
 pub use hir_def::{
@@ -73,4 +75,10 @@ pub use hir_expand::{
     hygiene::Hygiene, name::Name, HirFileId, InFile, MacroCallId, MacroCallLoc, MacroDefId,
     MacroFile, Origin,
 };
-pub use hir_ty::{display::HirDisplay, CallableDef};
+pub use hir_ty::{
+    display::HirDisplay,
+    layout::LayoutError,
+    object_safety::MethodViolationCode,
+    traits::{SolverTrace, TracedCandidate, TracedGoal, TraitSolverConfig},
+    BindingMode, CallableDef,
+};
@@ -24,8 +24,9 @@ use crate::{
     diagnostics::Diagnostic,
     semantics::source_to_def::{ChildContainer, SourceToDefCache, SourceToDefCtx},
     source_analyzer::{resolve_hir_path, SourceAnalyzer},
-    AssocItem, Field, Function, HirFileId, ImplDef, InFile, Local, MacroDef, Module, ModuleDef,
-    Name, Origin, Path, ScopeDef, Trait, Type, TypeAlias, TypeParam,
+    term_search::{self, Term},
+    AssocItem, BindingMode, Field, Function, HirFileId, ImplDef, InFile, Local, MacroDef, Module,
+    ModuleDef, Name, Origin, Path, ScopeDef, Trait, Type, TypeAlias, TypeParam,
 };
 use resolver::TypeNs;
 
@@ -220,6 +221,19 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.analyze(pat.syntax()).type_of_pat(self.db, &pat)
     }
 
+    /// Returns how `pat` binds its value, taking default binding modes into
+    /// account: `x` in `let &(x, _) = ..` binds by value, in `let (x, _) = &..`
+    /// by reference.
+    pub fn binding_mode_of_pat(&self, pat: &ast::BindPat) -> Option<BindingMode> {
+        self.analyze(pat.syntax()).binding_mode_of_pat(&pat)
+    }
+
+    /// Returns the type the context expects of a typed hole like `_` or
+    /// `todo!()`.
+    pub fn expected_type_of_hole(&self, expr: &ast::Expr) -> Option<Type> {
+        self.analyze(expr.syntax()).expected_type_of_hole(self.db, &expr)
+    }
+
     pub fn resolve_method_call(&self, call: &ast::MethodCallExpr) -> Option<Function> {
         self.analyze(call.syntax()).resolve_method_call(self.db, call)
     }
@@ -451,6 +465,11 @@ impl<'a, DB: HirDatabase> SemanticsScope<'a, DB> {
     pub fn resolve_hir_path(&self, path: &Path) -> Option<PathResolution> {
         resolve_hir_path(self.db, &self.resolver, path)
     }
+
+    /// Finds terms of type `goal` which can be built from what is in scope.
+    pub fn term_search(&self, goal: &Type) -> Vec<Term> {
+        term_search::term_search(self, &self.resolver, goal)
+    }
 }
 
 // FIXME: Change `HasSource` trait to work with `Semantics` and remove this?
@@ -19,7 +19,7 @@ use hir_def::{
 use hir_expand::{hygiene::Hygiene, name::AsName, HirFileId, InFile};
 use hir_ty::{
     expr::{record_literal_missing_fields, record_pattern_missing_fields},
-    InferenceResult, Substs, Ty,
+    BindingMode, InferenceResult, Substs, Ty,
 };
 use ra_syntax::{
     ast::{self, AstNode},
@@ -121,12 +121,27 @@ impl SourceAnalyzer {
         Type::new_with_resolver(db, &self.resolver, ty)
     }
 
+    pub(crate) fn expected_type_of_hole(
+        &self,
+        db: &dyn HirDatabase,
+        expr: &ast::Expr,
+    ) -> Option<Type> {
+        let expr_id = self.expr_id(db, expr)?;
+        let ty = self.infer.as_ref()?.expected_type_of_hole(expr_id)?.clone();
+        Type::new_with_resolver(db, &self.resolver, ty)
+    }
+
     pub(crate) fn type_of_pat(&self, db: &dyn HirDatabase, pat: &ast::Pat) -> Option<Type> {
         let pat_id = self.pat_id(pat)?;
         let ty = self.infer.as_ref()?[pat_id].clone();
         Type::new_with_resolver(db, &self.resolver, ty)
     }
 
+    pub(crate) fn binding_mode_of_pat(&self, pat: &ast::BindPat) -> Option<BindingMode> {
+        let pat_id = self.pat_id(&pat.clone().into())?;
+        self.infer.as_ref()?.binding_mode_of_pat(pat_id)
+    }
+
     pub(crate) fn resolve_method_call(
         &self,
         db: &dyn HirDatabase,
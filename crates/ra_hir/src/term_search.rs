@@ -0,0 +1,270 @@
+//! Type-directed term search: finds expressions of a given type which can be
+//! built from what is in scope. This is used to suggest fillings for typed
+//! holes, like `_` or `todo!()`.
+//!
+//! The search is deliberately shallow. It considers locals, fields of locals,
+//! constants and statics, and `Default::default()`. It also considers
+//! constructors and function calls, as long as each argument is one of the
+//! former.
+
+use hir_def::{
+    adt::StructKind, path::path, resolver::Resolver, AdtId, AssocContainerId, AssocItemId,
+    EnumVariantId, Lookup, VariantId,
+};
+use hir_ty::{
+    method_resolution::{self, LookupMode},
+    unify, ApplicationTy, Canonical, Substs, Ty, TypeCtor, TypeWalk,
+};
+use ra_prof::profile;
+
+use crate::{
+    db::HirDatabase, Adt, AsAssocItem, AssocItemContainer, Const, Field, Function, HasVisibility,
+    Local, Module, ModuleDef, Name, ScopeDef, SemanticsScope, Static, Struct, Trait, Type,
+    VariantDef,
+};
+
+/// We stop looking once we found this many terms.
+const MAX_TERMS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Local(Local),
+    Field(Box<Term>, Field),
+    Const(Const),
+    Static(Static),
+    /// A struct or enum variant literal, with a term for each field.
+    Constructor(VariantDef, Vec<Term>),
+    /// A call of a free or associated function.
+    Call(Function, Vec<Term>),
+    /// `Default::default()`.
+    Default,
+}
+
+impl Term {
+    /// Renders the term as an expression which is valid in `module`.
+    pub fn to_source(&self, db: &dyn HirDatabase, module: Module) -> String {
+        match self {
+            Term::Local(it) => it.name(db).map_or_else(|| "_".to_string(), |it| it.to_string()),
+            Term::Field(receiver, field) => {
+                format!("{}.{}", receiver.to_source(db, module), field.name(db))
+            }
+            Term::Const(it) => path_to(db, module, (*it).into(), it.name(db)),
+            Term::Static(it) => path_to(db, module, (*it).into(), it.name(db)),
+            Term::Constructor(variant, args) => {
+                let def = match variant {
+                    VariantDef::Struct(it) => Adt::from(*it).into(),
+                    VariantDef::Union(it) => Adt::from(*it).into(),
+                    VariantDef::EnumVariant(it) => (*it).into(),
+                };
+                let path = path_to(db, module, def, Some(variant.name(db)));
+                match variant.variant_data(db).kind() {
+                    StructKind::Unit => path,
+                    StructKind::Tuple => format!("{}({})", path, render_args(db, module, args)),
+                    StructKind::Record => {
+                        let fields = variant
+                            .fields(db)
+                            .into_iter()
+                            .zip(args)
+                            .map(|(field, arg)| {
+                                let name = field.name(db).to_string();
+                                let arg = arg.to_source(db, module);
+                                if name == arg {
+                                    name
+                                } else {
+                                    format!("{}: {}", name, arg)
+                                }
+                            })
+                            .collect::<Vec<_>>();
+                        format!("{} {{ {} }}", path, fields.join(", "))
+                    }
+                }
+            }
+            Term::Call(func, args) => {
+                let callee = match func.as_assoc_item(db).map(|it| it.container(db)) {
+                    Some(AssocItemContainer::ImplDef(impl_def)) => {
+                        match impl_def.target_ty(db).as_adt() {
+                            Some(adt) => format!(
+                                "{}::{}",
+                                path_to(db, module, adt.into(), Some(adt.name(db))),
+                                func.name(db)
+                            ),
+                            None => func.name(db).to_string(),
+                        }
+                    }
+                    _ => path_to(db, module, (*func).into(), Some(func.name(db))),
+                };
+                format!("{}({})", callee, render_args(db, module, args))
+            }
+            Term::Default => "Default::default()".to_string(),
+        }
+    }
+}
+
+/// Falls back to the plain `name` if `def` isn't reachable from `module`.
+fn path_to(db: &dyn HirDatabase, module: Module, def: ModuleDef, name: Option<Name>) -> String {
+    match module.find_use_path(db.upcast(), def) {
+        Some(path) => path.to_string(),
+        None => name.map_or_else(|| "_".to_string(), |it| it.to_string()),
+    }
+}
+
+fn render_args(db: &dyn HirDatabase, module: Module, args: &[Term]) -> String {
+    args.iter().map(|arg| arg.to_source(db, module)).collect::<Vec<_>>().join(", ")
+}
+
+pub(crate) fn term_search<DB: HirDatabase>(
+    scope: &SemanticsScope<DB>,
+    resolver: &Resolver,
+    goal: &Type,
+) -> Vec<Term> {
+    let _p = profile("term_search");
+    let db: &dyn HirDatabase = scope.db;
+    let module = match scope.module() {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    if goal.contains_unknown() {
+        return Vec::new();
+    }
+    let goal_ty = &goal.ty.value;
+
+    let mut leaves: Vec<(Term, Ty)> = Vec::new();
+    let mut functions = Vec::new();
+    scope.process_all_names(&mut |_, def| match def {
+        ScopeDef::Local(it) => leaves.push((Term::Local(it), it.ty(db).ty.value)),
+        ScopeDef::ModuleDef(ModuleDef::Const(it)) => {
+            leaves.push((Term::Const(it), db.value_ty(it.id.into()).value))
+        }
+        ScopeDef::ModuleDef(ModuleDef::Static(it)) => {
+            leaves.push((Term::Static(it), db.value_ty(it.id.into()).value))
+        }
+        ScopeDef::ModuleDef(ModuleDef::Function(it)) if !it.has_self_param(db) => {
+            functions.push(it)
+        }
+        _ => {}
+    });
+    let fields = leaves
+        .iter()
+        .filter(|(term, _)| matches!(term, Term::Local(_)))
+        .flat_map(|(term, ty)| {
+            visible_fields(db, module, ty)
+                .into_iter()
+                .map(move |(field, ty)| (Term::Field(Box::new(term.clone()), field), ty))
+        })
+        .collect::<Vec<_>>();
+    leaves.extend(fields);
+    let leaf_of_type =
+        |ty: &Ty| leaves.iter().find(|(_, it)| it == ty).map(|(term, _)| term.clone());
+
+    let mut res: Vec<Term> =
+        leaves.iter().filter(|(_, ty)| ty == goal_ty).map(|(term, _)| term.clone()).collect();
+
+    if let Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(adt), parameters }) = goal_ty {
+        for variant in adt_variants(db, *adt) {
+            let variant_def = VariantDef::from(variant);
+            if !variant_def.fields(db).iter().all(|it| it.is_visible_from(db, module)) {
+                continue;
+            }
+            let args = db
+                .field_types(variant)
+                .iter()
+                .map(|(_, ty)| leaf_of_type(&ty.clone().subst(parameters)))
+                .collect::<Option<Vec<_>>>();
+            if let Some(args) = args {
+                res.push(Term::Constructor(variant_def, args));
+            }
+        }
+    }
+
+    // Associated functions of the goal type, like `Vec::new`.
+    let canonical_goal = Canonical { num_vars: 0, value: goal_ty.clone() };
+    method_resolution::iterate_method_candidates(
+        &canonical_goal,
+        db,
+        goal.ty.environment.clone(),
+        goal.krate,
+        &scope.traits_in_scope(),
+        None,
+        LookupMode::Path,
+        |_, item| {
+            if let AssocItemId::FunctionId(it) = item {
+                let func = Function::from(it);
+                let in_impl =
+                    matches!(it.lookup(db.upcast()).container, AssocContainerId::ImplId(_));
+                if in_impl && !func.has_self_param(db) && !functions.contains(&func) {
+                    functions.push(func);
+                }
+            }
+            None::<()>
+        },
+    );
+    for func in functions {
+        let sig = db.callable_item_signature(func.id.into());
+        let ret = Canonical { num_vars: sig.num_binders, value: sig.value.ret().clone() };
+        let substs = match unify(&ret, &canonical_goal) {
+            Some(it) => it,
+            None => continue,
+        };
+        // Type parameters which don't occur in the return type can't be
+        // inferred from it.
+        if substs_contain_bound_vars(&substs) {
+            continue;
+        }
+        let sig = sig.subst(&substs);
+        let args = sig.params().iter().map(|ty| leaf_of_type(ty)).collect::<Option<Vec<_>>>();
+        if let Some(args) = args {
+            res.push(Term::Call(func, args));
+        }
+    }
+
+    if let Some(default_trait) =
+        resolver.resolve_known_trait(db.upcast(), &path![std::default::Default])
+    {
+        if goal.impls_trait(db, Trait { id: default_trait }, &[]) {
+            res.push(Term::Default);
+        }
+    }
+
+    let mut deduped = Vec::new();
+    for term in res {
+        if !deduped.contains(&term) {
+            deduped.push(term);
+        }
+    }
+    deduped.truncate(MAX_TERMS);
+    deduped
+}
+
+fn visible_fields(db: &dyn HirDatabase, module: Module, ty: &Ty) -> Vec<(Field, Ty)> {
+    let (struct_id, parameters) = match ty {
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(AdtId::StructId(it)), parameters }) => {
+            (*it, parameters)
+        }
+        _ => return Vec::new(),
+    };
+    let parent = VariantDef::Struct(Struct { id: struct_id });
+    db.field_types(struct_id.into())
+        .iter()
+        .map(|(id, ty)| (Field { parent, id }, ty.clone().subst(parameters)))
+        .filter(|(field, _)| field.is_visible_from(db, module))
+        .collect()
+}
+
+fn adt_variants(db: &dyn HirDatabase, adt: AdtId) -> Vec<VariantId> {
+    match adt {
+        AdtId::StructId(it) => vec![it.into()],
+        AdtId::EnumId(it) => db
+            .enum_data(it)
+            .variants
+            .iter()
+            .map(|(local_id, _)| EnumVariantId { parent: it, local_id }.into())
+            .collect(),
+        AdtId::UnionId(_) => Vec::new(),
+    }
+}
+
+fn substs_contain_bound_vars(substs: &Substs) -> bool {
+    let mut res = false;
+    substs.walk(&mut |ty| res |= matches!(ty, Ty::Bound(_)));
+    res
+}
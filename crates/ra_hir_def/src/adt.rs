@@ -10,17 +10,20 @@ use hir_expand::{
 use ra_arena::{map::ArenaMap, Arena};
 use ra_prof::profile;
 use ra_syntax::ast::{self, NameOwner, TypeAscriptionOwner, VisibilityOwner};
+use tt::{Leaf, Subtree, TokenTree};
 
 use crate::{
+    attr::Attrs,
     body::{CfgExpander, LowerCtx},
+    builtin_type::BuiltinInt,
     db::DefDatabase,
     src::HasChildSource,
     src::HasSource,
     trace::Trace,
     type_ref::TypeRef,
     visibility::RawVisibility,
-    EnumId, HasModule, LocalEnumVariantId, LocalFieldId, Lookup, ModuleId, StructId, UnionId,
-    VariantId,
+    AdtId, EnumId, HasModule, LocalEnumVariantId, LocalFieldId, Lookup, ModuleId, StructId,
+    UnionId, VariantId,
 };
 
 /// Note that we use `StructData` for unions as well!
@@ -28,12 +31,14 @@ use crate::{
 pub struct StructData {
     pub name: Name,
     pub variant_data: Arc<VariantData>,
+    pub repr: ReprOptions,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EnumData {
     pub name: Name,
     pub variants: Arena<EnumVariantData>,
+    pub repr: ReprOptions,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -49,6 +54,18 @@ pub enum VariantData {
     Unit,
 }
 
+/// Layout-relevant options from `#[repr(...)]` attributes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReprOptions {
+    pub c: bool,
+    pub transparent: bool,
+    /// `Some(1)` for `#[repr(packed)]`, `Some(n)` for `#[repr(packed(n))]`.
+    pub packed: Option<u64>,
+    pub align: Option<u64>,
+    /// The discriminant type of an enum, like `u8` in `#[repr(u8)]`.
+    pub int: Option<BuiltinInt>,
+}
+
 /// A single field of an enum variant or struct
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FieldData {
@@ -65,7 +82,8 @@ impl StructData {
         let variant_data =
             VariantData::new(db, src.map(|s| s.kind()), id.lookup(db).container.module(db));
         let variant_data = Arc::new(variant_data);
-        Arc::new(StructData { name, variant_data })
+        let repr = ReprOptions::from_attrs(&db.attrs(AdtId::from(id).into()));
+        Arc::new(StructData { name, variant_data, repr })
     }
     pub(crate) fn union_data_query(db: &dyn DefDatabase, id: UnionId) -> Arc<StructData> {
         let src = id.lookup(db).source(db);
@@ -80,7 +98,8 @@ impl StructData {
             id.lookup(db).container.module(db),
         );
         let variant_data = Arc::new(variant_data);
-        Arc::new(StructData { name, variant_data })
+        let repr = ReprOptions::from_attrs(&db.attrs(AdtId::from(id).into()));
+        Arc::new(StructData { name, variant_data, repr })
     }
 }
 
@@ -91,7 +110,8 @@ impl EnumData {
         let name = src.value.name().map_or_else(Name::missing, |n| n.as_name());
         let mut trace = Trace::new_for_arena();
         lower_enum(db, &mut trace, &src, e.lookup(db).container.module(db));
-        Arc::new(EnumData { name, variants: trace.into_arena() })
+        let repr = ReprOptions::from_attrs(&db.attrs(AdtId::from(e).into()));
+        Arc::new(EnumData { name, variants: trace.into_arena(), repr })
     }
 
     pub fn variant(&self, name: &Name) -> Option<LocalEnumVariantId> {
@@ -135,6 +155,47 @@ fn lower_enum(
     }
 }
 
+impl ReprOptions {
+    fn from_attrs(attrs: &Attrs) -> ReprOptions {
+        let mut res = ReprOptions::default();
+        for tt in attrs.by_key("repr").tt_values() {
+            let mut it = tt.token_trees.iter().peekable();
+            while let Some(tt) = it.next() {
+                let name = match tt {
+                    TokenTree::Leaf(Leaf::Ident(ident)) => ident.text.clone(),
+                    _ => continue,
+                };
+                let arg = match it.peek() {
+                    Some(TokenTree::Subtree(subtree)) => {
+                        it.next();
+                        int_arg(subtree)
+                    }
+                    _ => None,
+                };
+                match name.as_str() {
+                    "C" => res.c = true,
+                    "transparent" => res.transparent = true,
+                    "packed" => res.packed = Some(arg.unwrap_or(1)),
+                    "align" => res.align = arg,
+                    _ => {
+                        if let Some(int) = BuiltinInt::from_suffix(&name) {
+                            res.int = Some(int);
+                        }
+                    }
+                }
+            }
+        }
+        return res;
+
+        fn int_arg(subtree: &Subtree) -> Option<u64> {
+            match subtree.token_trees.first()? {
+                TokenTree::Leaf(Leaf::Literal(lit)) => lit.text.parse().ok(),
+                _ => None,
+            }
+        }
+    }
+}
+
 impl VariantData {
     fn new(db: &dyn DefDatabase, flavor: InFile<ast::StructKind>, module_id: ModuleId) -> Self {
         let mut expander = CfgExpander::new(db, flavor.file_id, module_id.krate);
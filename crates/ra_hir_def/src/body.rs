@@ -13,7 +13,7 @@ use ra_cfg::CfgOptions;
 use ra_db::CrateId;
 use ra_prof::profile;
 use ra_syntax::{ast, AstNode, AstPtr};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 pub(crate) use lower::LowerCtx;
 
@@ -183,6 +183,9 @@ pub struct Body {
     pub params: Vec<PatId>,
     /// The `ExprId` of the actual body expression.
     pub body_expr: ExprId,
+    /// The typed holes: `_` in expression position (which is lowered to
+    /// `Expr::Missing`) and the expansions of `todo!()` and `unimplemented!()`.
+    pub typed_holes: FxHashSet<ExprId>,
     pub item_scope: ItemScope,
 }
 
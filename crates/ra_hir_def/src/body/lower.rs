@@ -73,6 +73,7 @@ pub(super) fn lower(
             pats: Arena::default(),
             params: Vec::new(),
             body_expr: dummy_expr_id(),
+            typed_holes: Default::default(),
             item_scope: Default::default(),
         },
     }
@@ -134,7 +135,7 @@ impl ExprCollector<'_> {
         self.make_expr(expr, Err(SyntheticSyntax))
     }
     fn empty_block(&mut self) -> ExprId {
-        self.alloc_expr_desugared(Expr::Block { statements: Vec::new(), tail: None })
+        self.alloc_expr_desugared(Expr::Block { statements: Vec::new(), tail: None, label: None })
     }
     fn missing_expr(&mut self) -> ExprId {
         self.alloc_expr_desugared(Expr::Missing)
@@ -207,17 +208,28 @@ impl ExprCollector<'_> {
                     let body = self.collect_block_opt(e.block_expr());
                     self.alloc_expr(Expr::TryBlock { body }, syntax_ptr)
                 }
-                // FIXME: we need to record these effects somewhere...
-                ast::Effect::Async(_) | ast::Effect::Label(_) | ast::Effect::Unsafe(_) => {
-                    self.collect_block_opt(e.block_expr())
+                ast::Effect::Async(_) => {
+                    let body = self.collect_block_opt(e.block_expr());
+                    self.alloc_expr(Expr::Async { body }, syntax_ptr)
                 }
+                ast::Effect::Label(label) => match e.block_expr() {
+                    Some(block) => {
+                        let label = label.lifetime_token().map(|lt| Name::new_lifetime(&lt));
+                        self.collect_labeled_block(block, label)
+                    }
+                    None => self.missing_expr(),
+                },
+                // FIXME: we need to record these effects somewhere...
+                ast::Effect::Unsafe(_) => self.collect_block_opt(e.block_expr()),
             },
             ast::Expr::BlockExpr(e) => self.collect_block(e),
             ast::Expr::LoopExpr(e) => {
+                let label = self.collect_label(e.label());
                 let body = self.collect_block_opt(e.loop_body());
-                self.alloc_expr(Expr::Loop { body }, syntax_ptr)
+                self.alloc_expr(Expr::Loop { body, label }, syntax_ptr)
             }
             ast::Expr::WhileExpr(e) => {
+                let label = self.collect_label(e.label());
                 let body = self.collect_block_opt(e.loop_body());
 
                 let condition = match e.condition() {
@@ -230,25 +242,30 @@ impl ExprCollector<'_> {
                             let pat = self.collect_pat(pat);
                             let match_expr = self.collect_expr_opt(condition.expr());
                             let placeholder_pat = self.missing_pat();
-                            let break_ = self.alloc_expr_desugared(Expr::Break { expr: None });
+                            let break_ = self.alloc_expr_desugared(Expr::Break {
+                                expr: None,
+                                label: label.clone(),
+                            });
                             let arms = vec![
                                 MatchArm { pat, expr: body, guard: None },
                                 MatchArm { pat: placeholder_pat, expr: break_, guard: None },
                             ];
                             let match_expr =
                                 self.alloc_expr_desugared(Expr::Match { expr: match_expr, arms });
-                            return self.alloc_expr(Expr::Loop { body: match_expr }, syntax_ptr);
+                            return self
+                                .alloc_expr(Expr::Loop { body: match_expr, label }, syntax_ptr);
                         }
                     },
                 };
 
-                self.alloc_expr(Expr::While { condition, body }, syntax_ptr)
+                self.alloc_expr(Expr::While { condition, body, label }, syntax_ptr)
             }
             ast::Expr::ForExpr(e) => {
+                let label = self.collect_label(e.label());
                 let iterable = self.collect_expr_opt(e.iterable());
                 let pat = self.collect_pat_opt(e.pat());
                 let body = self.collect_block_opt(e.loop_body());
-                self.alloc_expr(Expr::For { iterable, pat, body }, syntax_ptr)
+                self.alloc_expr(Expr::For { iterable, pat, body, label }, syntax_ptr)
             }
             ast::Expr::CallExpr(e) => {
                 let callee = self.collect_expr_opt(e.expr());
@@ -301,13 +318,14 @@ impl ExprCollector<'_> {
                     .unwrap_or(Expr::Missing);
                 self.alloc_expr(path, syntax_ptr)
             }
-            ast::Expr::ContinueExpr(_e) => {
-                // FIXME: labels
-                self.alloc_expr(Expr::Continue, syntax_ptr)
+            ast::Expr::ContinueExpr(e) => {
+                let label = e.lifetime_token().map(|lt| Name::new_lifetime(&lt));
+                self.alloc_expr(Expr::Continue { label }, syntax_ptr)
             }
             ast::Expr::BreakExpr(e) => {
+                let label = e.lifetime_token().map(|lt| Name::new_lifetime(&lt));
                 let expr = e.expr().map(|e| self.collect_expr(e));
-                self.alloc_expr(Expr::Break { expr }, syntax_ptr)
+                self.alloc_expr(Expr::Break { expr, label }, syntax_ptr)
             }
             ast::Expr::ParenExpr(e) => {
                 let inner = self.collect_expr_opt(e.expr());
@@ -320,6 +338,10 @@ impl ExprCollector<'_> {
                 let expr = e.expr().map(|e| self.collect_expr(e));
                 self.alloc_expr(Expr::Return { expr }, syntax_ptr)
             }
+            ast::Expr::YieldExpr(e) => {
+                let expr = e.expr().map(|e| self.collect_expr(e));
+                self.alloc_expr(Expr::Yield { expr }, syntax_ptr)
+            }
             ast::Expr::RecordLit(e) => {
                 let path = e.path().and_then(|path| self.expander.parse_path(path));
                 let mut field_ptrs = Vec::new();
@@ -472,6 +494,7 @@ impl ExprCollector<'_> {
                     self.alloc_expr(Expr::Missing, syntax_ptr)
                 } else {
                     let macro_call = self.expander.to_source(AstPtr::new(&e));
+                    let is_typed_hole = is_typed_hole_macro(&e);
                     match self.expander.enter_expand(self.db, Some(&self.body.item_scope), e) {
                         Some((mark, expansion)) => {
                             self.source_map
@@ -479,6 +502,9 @@ impl ExprCollector<'_> {
                                 .insert(macro_call, self.expander.current_file_id);
                             let id = self.collect_expr(expansion);
                             self.expander.exit(self.db, mark);
+                            if is_typed_hole {
+                                self.body.typed_holes.insert(id);
+                            }
                             id
                         }
                         None => self.alloc_expr(Expr::Missing, syntax_ptr),
@@ -486,6 +512,14 @@ impl ExprCollector<'_> {
                 }
             }
 
+            // `_` is a typed hole, which is an error as far as the compiler is
+            // concerned, but the IDE uses the expected type to suggest a term
+            ast::Expr::UnderscoreExpr(_) => {
+                let id = self.alloc_expr(Expr::Missing, syntax_ptr);
+                self.body.typed_holes.insert(id);
+                id
+            }
+
             // FIXME implement HIR for these:
             ast::Expr::Label(_e) => self.alloc_expr(Expr::Missing, syntax_ptr),
         }
@@ -500,6 +534,10 @@ impl ExprCollector<'_> {
     }
 
     fn collect_block(&mut self, block: ast::BlockExpr) -> ExprId {
+        self.collect_labeled_block(block, None)
+    }
+
+    fn collect_labeled_block(&mut self, block: ast::BlockExpr, label: Option<Name>) -> ExprId {
         let syntax_node_ptr = AstPtr::new(&block.clone().into());
         self.collect_block_items(&block);
         let statements = block
@@ -515,7 +553,11 @@ impl ExprCollector<'_> {
             })
             .collect();
         let tail = block.expr().map(|e| self.collect_expr(e));
-        self.alloc_expr(Expr::Block { statements, tail }, syntax_node_ptr)
+        self.alloc_expr(Expr::Block { statements, tail, label }, syntax_node_ptr)
+    }
+
+    fn collect_label(&self, label: Option<ast::Label>) -> Option<Name> {
+        label.and_then(|it| it.lifetime_token()).map(|lt| Name::new_lifetime(&lt))
     }
 
     fn collect_block_items(&mut self, block: &ast::BlockExpr) {
@@ -742,6 +784,16 @@ impl ExprCollector<'_> {
     }
 }
 
+/// `todo!()` and `unimplemented!()` stand for code yet to be written, so like
+/// `_` they are typed holes the IDE can suggest a term for.
+fn is_typed_hole_macro(call: &ast::MacroCall) -> bool {
+    let name_ref = call.path().and_then(|it| it.segment()).and_then(|it| it.name_ref());
+    match name_ref {
+        Some(it) => matches!(it.text().as_str(), "todo" | "unimplemented"),
+        None => false,
+    }
+}
+
 impl From<ast::BinOp> for BinaryOp {
     fn from(ast_op: ast::BinOp) -> Self {
         match ast_op {
@@ -34,6 +34,9 @@ pub struct FunctionData {
     /// True if the first param is `self`. This is relevant to decide whether this
     /// can be called as a method.
     pub has_self_param: bool,
+    /// True for `async fn`s, whose `ret_type` has been desugared to
+    /// `impl Future<Output = T>`.
+    pub is_async: bool,
     pub visibility: RawVisibility,
 }
 
@@ -77,7 +80,8 @@ impl FunctionData {
             TypeRef::unit()
         };
 
-        let ret_type = if src.value.async_token().is_some() {
+        let is_async = src.value.async_token().is_some();
+        let ret_type = if is_async {
             let future_impl = desugar_future_path(ret_type);
             let ty_bound = TypeBound::Path(future_impl);
             TypeRef::ImplTrait(vec![ty_bound])
@@ -89,7 +93,8 @@ impl FunctionData {
         let visibility =
             RawVisibility::from_ast_with_default(db, vis_default, src.map(|s| s.visibility()));
 
-        let sig = FunctionData { name, params, ret_type, has_self_param, visibility, attrs };
+        let sig =
+            FunctionData { name, params, ret_type, has_self_param, is_async, visibility, attrs };
         Arc::new(sig)
     }
 }
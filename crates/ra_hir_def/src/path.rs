@@ -319,7 +319,9 @@ macro_rules! __known_path {
     (std::ops::RangeToInclusive) => {};
     (std::ops::RangeInclusive) => {};
     (std::future::Future) => {};
+    (std::future::IntoFuture) => {};
     (std::ops::Try) => {};
+    (std::default::Default) => {};
     ($path:path) => {
         compile_error!("Please register your known path in the path module")
     };
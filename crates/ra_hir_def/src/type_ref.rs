@@ -44,7 +44,8 @@ pub enum TypeRef {
     Path(Path),
     RawPtr(Box<TypeRef>, Mutability),
     Reference(Box<TypeRef>, Mutability),
-    Array(Box<TypeRef> /*, Expr*/),
+    /// An array type, with its length if that's a plain integer literal.
+    Array(Box<TypeRef>, Option<u64>),
     Slice(Box<TypeRef>),
     /// A fn pointer. Last element of the vector is the return type.
     Fn(Vec<TypeRef>),
@@ -85,7 +86,8 @@ impl TypeRef {
                 TypeRef::RawPtr(Box::new(inner_ty), mutability)
             }
             ast::TypeRef::ArrayType(inner) => {
-                TypeRef::Array(Box::new(TypeRef::from_ast_opt(&ctx, inner.type_ref())))
+                let len = inner.expr().and_then(|it| array_len_from_ast(&it));
+                TypeRef::Array(Box::new(TypeRef::from_ast_opt(&ctx, inner.type_ref())), len)
             }
             ast::TypeRef::SliceType(inner) => {
                 TypeRef::Slice(Box::new(TypeRef::from_ast_opt(&ctx, inner.type_ref())))
@@ -145,7 +147,7 @@ impl TypeRef {
                 TypeRef::Fn(types) | TypeRef::Tuple(types) => types.iter().for_each(|t| go(t, f)),
                 TypeRef::RawPtr(type_ref, _)
                 | TypeRef::Reference(type_ref, _)
-                | TypeRef::Array(type_ref)
+                | TypeRef::Array(type_ref, _)
                 | TypeRef::Slice(type_ref) => go(&type_ref, f),
                 TypeRef::ImplTrait(bounds) | TypeRef::DynTrait(bounds) => {
                     for bound in bounds {
@@ -187,6 +189,30 @@ impl TypeRef {
     }
 }
 
+/// Evaluates the length of an array type, like the `4` in `[u8; 4]`. Only
+/// integer literals are supported for now.
+fn array_len_from_ast(expr: &ast::Expr) -> Option<u64> {
+    let lit = match expr {
+        ast::Expr::Literal(it) => it,
+        _ => return None,
+    };
+    let suffix = match lit.kind() {
+        ast::LiteralKind::IntNumber { suffix } => suffix,
+        _ => return None,
+    };
+    let token = lit.token();
+    let text = token.text().trim_end_matches(suffix.as_deref().unwrap_or("")).replace('_', "");
+    if text.starts_with("0x") {
+        u64::from_str_radix(&text[2..], 16).ok()
+    } else if text.starts_with("0o") {
+        u64::from_str_radix(&text[2..], 8).ok()
+    } else if text.starts_with("0b") {
+        u64::from_str_radix(&text[2..], 2).ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
 pub(crate) fn type_bounds_from_ast(
     lower_ctx: &LowerCtx,
     type_bounds_opt: Option<ast::TypeBoundList>,
@@ -2,11 +2,11 @@
 
 use std::sync::Arc;
 
-use mbe::{ExpandResult, MacroRules};
+use mbe::{ExpandResult, MacroRules, TokenTextRange};
 use ra_db::{salsa, SourceDatabase};
 use ra_parser::FragmentKind;
 use ra_prof::profile;
-use ra_syntax::{algo::diff, AstNode, Parse, SyntaxKind::*, SyntaxNode};
+use ra_syntax::{algo::diff, ast, AstNode, Parse, SyntaxKind::*, SyntaxNode, TextSize};
 
 use crate::{
     ast_id_map::AstIdMap, BuiltinDeriveExpander, BuiltinFnLikeExpander, EagerCallLoc, EagerMacroId,
@@ -38,6 +38,41 @@ impl TokenExpander {
         }
     }
 
+    /// For a `macro_rules!` macro, the index of the rule used to expand a call
+    /// with the given arguments, and the index of the top-level metavariable or
+    /// repetition of the rule's pattern the arguments at `offset` belong to.
+    pub fn matching_fragment(
+        &self,
+        args: &ast::TokenTree,
+        offset: TextSize,
+    ) -> Option<(usize, Option<usize>)> {
+        let rules = match self {
+            TokenExpander::MacroRules(it) => it,
+            _ => return None,
+        };
+        let (tt, token_map) = mbe::ast_to_token_tree(args)?;
+        let (rule, starts) = rules.matching_fragments(&tt)?;
+        let offset = offset.checked_sub(args.syntax().text_range().start())?;
+        let start_of = |tt: &tt::TokenTree| {
+            let id = match tt {
+                tt::TokenTree::Leaf(tt::Leaf::Ident(it)) => it.id,
+                tt::TokenTree::Leaf(tt::Leaf::Punct(it)) => it.id,
+                tt::TokenTree::Leaf(tt::Leaf::Literal(it)) => it.id,
+                tt::TokenTree::Subtree(it) => it.delimiter?.id,
+            };
+            match token_map.range_by_token(id)? {
+                TokenTextRange::Token(it) => Some(it.start()),
+                TokenTextRange::Delimiter(open, _) => Some(open.start()),
+            }
+        };
+        let before = tt
+            .token_trees
+            .iter()
+            .take_while(|tt| start_of(tt).map_or(false, |start| start < offset))
+            .count();
+        Some((rule, starts.iter().rposition(|&start| start <= before)))
+    }
+
     pub fn map_id_down(&self, id: tt::TokenId) -> tt::TokenId {
         match self {
             TokenExpander::MacroRules(it) => it.map_id_down(id),
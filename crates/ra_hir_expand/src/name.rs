@@ -52,6 +52,11 @@ impl Name {
         }
     }
 
+    /// Creates a name for a label like `'outer` from its lifetime token.
+    pub fn new_lifetime(lt: &ra_syntax::SyntaxToken) -> Name {
+        Name::new_text(lt.text().clone())
+    }
+
     pub fn missing() -> Name {
         Name::new_text("[missing name]".into())
     }
@@ -157,14 +162,18 @@ pub mod known {
         future,
         result,
         boxed,
+        default,
         // Components of known path (type name)
         IntoIterator,
         Item,
         Try,
         Ok,
         Future,
+        IntoFuture,
         Result,
         Output,
+        Yield,
+        Return,
         Target,
         Box,
         RangeFrom,
@@ -0,0 +1,242 @@
+//! Coherence checking: finds trait impls which overlap with another impl of the
+//! same trait, and impls which break the orphan rules.
+//!
+//! Two impls overlap if their trait refs unify and none of their combined
+//! where clauses is known to be unsatisfiable for the unified types; the latter
+//! is checked with Chalk. This is more lenient than rustc, which also accounts
+//! for impls upstream crates might add in the future.
+
+use std::sync::Arc;
+
+use hir_def::{src::HasSource, AdtId, AttrDefId, HasModule, ImplId, Lookup, TraitId};
+use hir_expand::{diagnostics::DiagnosticSink, InFile};
+use ra_db::CrateId;
+use ra_prof::profile;
+use ra_syntax::AstPtr;
+use rustc_hash::FxHashMap;
+
+use crate::{
+    db::HirDatabase,
+    diagnostics::{ConflictingImpls, OrphanImpl},
+    infer::unify,
+    method_resolution::TyFingerprint,
+    traits::{InEnvironment, Obligation, TraitEnvironment},
+    ApplicationTy, Canonical, DebruijnIndex, GenericPredicate, Substs, TraitRef, Ty, TypeCtor,
+    TypeWalk,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoherenceError {
+    /// The impl overlaps with another impl of the same trait, which is either
+    /// in a dependency or comes earlier in the same crate.
+    Overlap { impl_: ImplId, conflicting: ImplId },
+    /// The impl implements a trait from another crate, but none of the types
+    /// it is implemented for are local.
+    Orphan { impl_: ImplId, trait_: TraitId },
+}
+
+impl CoherenceError {
+    pub fn impl_id(&self) -> ImplId {
+        match self {
+            CoherenceError::Overlap { impl_, .. } | CoherenceError::Orphan { impl_, .. } => *impl_,
+        }
+    }
+
+    fn add_to(&self, db: &dyn HirDatabase, sink: &mut DiagnosticSink) {
+        let impl_source = |impl_id: ImplId| {
+            let src = impl_id.lookup(db.upcast()).source(db.upcast());
+            InFile::new(src.file_id, AstPtr::new(&src.value))
+        };
+        let trait_name = |trait_: TraitId| db.trait_data(trait_).name.clone();
+        match self {
+            CoherenceError::Overlap { impl_, conflicting } => {
+                let src = impl_source(*impl_);
+                let trait_ = match db.impl_trait(*impl_) {
+                    Some(it) => it.value.trait_,
+                    None => return,
+                };
+                sink.push(ConflictingImpls {
+                    file: src.file_id,
+                    impl_: src.value,
+                    trait_name: trait_name(trait_),
+                    conflicting: impl_source(*conflicting),
+                })
+            }
+            CoherenceError::Orphan { impl_, trait_ } => {
+                let src = impl_source(*impl_);
+                let trait_src = trait_.lookup(db.upcast()).source(db.upcast());
+                sink.push(OrphanImpl {
+                    file: src.file_id,
+                    impl_: src.value,
+                    trait_name: trait_name(*trait_),
+                    trait_: InFile::new(trait_src.file_id, AstPtr::new(&trait_src.value)),
+                })
+            }
+        }
+    }
+}
+
+pub(crate) fn coherence_errors_query(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+) -> Arc<[CoherenceError]> {
+    let _p = profile("coherence_errors_query");
+    let crate_def_map = db.crate_def_map(krate);
+    let local_impls: Vec<ImplId> =
+        crate_def_map.modules.iter().flat_map(|(_, module)| module.scope.impls()).collect();
+    let local_positions: FxHashMap<ImplId, usize> =
+        local_impls.iter().enumerate().map(|(idx, &impl_id)| (impl_id, idx)).collect();
+
+    let mut res = Vec::new();
+    for (idx, &impl_id) in local_impls.iter().enumerate() {
+        let trait_ref = match db.impl_trait(impl_id) {
+            Some(it) => it.value,
+            None => continue,
+        };
+        if is_orphan(db, krate, &trait_ref) {
+            res.push(CoherenceError::Orphan { impl_: impl_id, trait_: trait_ref.trait_ });
+            continue;
+        }
+
+        let self_ty_fp = TyFingerprint::for_impl(&db.impl_self_ty(impl_id).value);
+        // Only report each overlap once, on the later impl, and point to the
+        // first impl it conflicts with: impls from dependencies come first,
+        // then the local ones in declaration order.
+        let mut candidates: Vec<ImplId> = db
+            .impls_for_trait(krate, trait_ref.trait_, self_ty_fp)
+            .iter()
+            .copied()
+            .filter(|other| local_positions.get(other).map_or(true, |&pos| pos < idx))
+            .collect();
+        candidates.sort_by_key(|other| local_positions.get(other).copied());
+        let conflicting =
+            candidates.into_iter().find(|&other| impls_overlap(db, krate, impl_id, other));
+        if let Some(conflicting) = conflicting {
+            res.push(CoherenceError::Overlap { impl_: impl_id, conflicting });
+        }
+    }
+    res.into()
+}
+
+pub fn add_coherence_diagnostics(db: &dyn HirDatabase, impl_id: ImplId, sink: &mut DiagnosticSink) {
+    let krate = impl_id.lookup(db.upcast()).container.module(db.upcast()).krate;
+    for error in db.coherence_errors(krate).iter().filter(|it| it.impl_id() == impl_id) {
+        error.add_to(db, sink);
+    }
+}
+
+fn impls_overlap(db: &dyn HirDatabase, krate: CrateId, a: ImplId, b: ImplId) -> bool {
+    let (a_trait_ref, b_trait_ref) = match (db.impl_trait(a), db.impl_trait(b)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return false,
+    };
+    // Give the type parameters of both impls distinct variables, and check
+    // whether there is an assignment making both trait refs equal.
+    let a_vars =
+        Substs::build_for_def(db, a).fill_with_bound_vars(DebruijnIndex::INNERMOST, 0).build();
+    let b_vars = Substs::build_for_def(db, b)
+        .fill_with_bound_vars(DebruijnIndex::INNERMOST, a_vars.len())
+        .build();
+    let num_vars = a_vars.len() + b_vars.len();
+    let a_trait_ref = a_trait_ref.subst(&a_vars);
+    let b_trait_ref = b_trait_ref.subst(&b_vars);
+    if contains_unknown(&a_trait_ref) || contains_unknown(&b_trait_ref) {
+        return false;
+    }
+    let as_tuple = |trait_ref: TraitRef| {
+        let cardinality = trait_ref.substs.len() as u16;
+        Canonical { num_vars, value: Ty::apply(TypeCtor::Tuple { cardinality }, trait_ref.substs) }
+    };
+    let unified = match unify(&as_tuple(a_trait_ref), &as_tuple(b_trait_ref)) {
+        Some(it) => it,
+        None => return false,
+    };
+
+    // The impls don't overlap if the where clauses can't hold for the types
+    // that make the trait refs equal.
+    let env = Arc::new(TraitEnvironment { predicates: Vec::new() });
+    let predicates = db
+        .generic_predicates(a.into())
+        .iter()
+        .map(|pred| pred.clone().subst(&a_vars))
+        .chain(db.generic_predicates(b.into()).iter().map(|pred| pred.clone().subst(&b_vars)))
+        .collect::<Vec<GenericPredicate>>();
+    for pred in predicates {
+        let obligation = match Obligation::from_predicate(pred.subst_bound_vars(&unified)) {
+            Some(it) => it,
+            None => continue,
+        };
+        let goal = Canonical { num_vars, value: InEnvironment::new(env.clone(), obligation) };
+        if db.trait_solve(krate, goal).is_none() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Checks the orphan rules from RFC 2451: an impl of a foreign trait needs a
+/// local type among the trait's parameters, and no type parameter of the impl
+/// may appear uncovered before it.
+fn is_orphan(db: &dyn HirDatabase, krate: CrateId, trait_ref: &TraitRef) -> bool {
+    if trait_krate(db, trait_ref.trait_) == krate {
+        return false;
+    }
+    for ty in trait_ref.substs.iter() {
+        if is_local(db, krate, ty) {
+            return false;
+        }
+        if is_uncovered_param(db, ty) {
+            return true;
+        }
+    }
+    true
+}
+
+fn is_local(db: &dyn HirDatabase, krate: CrateId, ty: &Ty) -> bool {
+    match ty {
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(adt), parameters }) => {
+            adt.module(db.upcast()).krate == krate
+                || (is_fundamental(db, *adt)
+                    && parameters.first().map_or(false, |it| is_local(db, krate, it)))
+        }
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Ref(_), parameters }) => {
+            is_local(db, krate, parameters.as_single())
+        }
+        Ty::Dyn(predicates) => match predicates.first() {
+            Some(GenericPredicate::Implemented(tr)) => trait_krate(db, tr.trait_) == krate,
+            _ => false,
+        },
+        // Don't complain about impls we can't fully resolve.
+        Ty::Unknown => true,
+        _ => false,
+    }
+}
+
+fn is_uncovered_param(db: &dyn HirDatabase, ty: &Ty) -> bool {
+    match ty {
+        Ty::Bound(_) | Ty::Placeholder(_) => true,
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(adt), parameters }) => {
+            is_fundamental(db, *adt)
+                && parameters.first().map_or(false, |it| is_uncovered_param(db, it))
+        }
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Ref(_), parameters }) => {
+            is_uncovered_param(db, parameters.as_single())
+        }
+        _ => false,
+    }
+}
+
+/// `#[fundamental]` types like `Box` are transparent to the orphan rules.
+fn is_fundamental(db: &dyn HirDatabase, adt: AdtId) -> bool {
+    db.attrs(AttrDefId::AdtId(adt)).by_key("fundamental").exists()
+}
+
+fn trait_krate(db: &dyn HirDatabase, trait_: TraitId) -> CrateId {
+    trait_.lookup(db.upcast()).container.module(db.upcast()).krate
+}
+
+fn contains_unknown(trait_ref: &TraitRef) -> bool {
+    let mut res = false;
+    trait_ref.walk(&mut |ty| res |= *ty == Ty::Unknown);
+    res
+}
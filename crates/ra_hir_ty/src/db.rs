@@ -11,8 +11,11 @@ use ra_db::{impl_intern_key, salsa, CrateId, Upcast};
 use ra_prof::profile;
 
 use crate::{
+    coherence::CoherenceError,
+    layout::{Layout, LayoutError},
     method_resolution::{CrateImplDefs, TyFingerprint},
-    traits::{chalk, AssocTyValue, Impl},
+    object_safety::ObjectSafetyViolation,
+    traits::{chalk, AssocTyValue, Impl, TraitSolverConfig},
     Binders, CallableDef, GenericPredicate, InferenceResult, PolyFnSig, Substs, TraitRef, Ty,
     TyDefId, TypeCtor, ValueTyDefId,
 };
@@ -61,6 +64,15 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::lower::generic_defaults_query)]
     fn generic_defaults(&self, def: GenericDefId) -> Substs;
 
+    #[salsa::invoke(crate::layout::layout_of_query)]
+    fn layout_of(&self, krate: CrateId, ty: Ty) -> Result<Arc<Layout>, LayoutError>;
+
+    #[salsa::invoke(crate::coherence::coherence_errors_query)]
+    fn coherence_errors(&self, krate: CrateId) -> Arc<[CoherenceError]>;
+
+    #[salsa::invoke(crate::object_safety::object_safety_query)]
+    fn object_safety(&self, trait_: TraitId) -> Arc<[ObjectSafetyViolation]>;
+
     #[salsa::invoke(crate::method_resolution::CrateImplDefs::impls_in_crate_query)]
     fn impls_in_crate(&self, krate: CrateId) -> Arc<CrateImplDefs>;
 
@@ -101,6 +113,10 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
         id: chalk::AssociatedTyValueId,
     ) -> Arc<chalk::AssociatedTyValue>;
 
+    /// Limits for the trait solver.
+    #[salsa::input]
+    fn trait_solver_config(&self) -> TraitSolverConfig;
+
     #[salsa::invoke(crate::traits::trait_solve_query)]
     fn trait_solve(
         &self,
@@ -131,3 +131,111 @@ impl AstDiagnostic for MissingOkInTailExpr {
         ast::Expr::cast(node).unwrap()
     }
 }
+
+#[derive(Debug)]
+pub struct UnreachableCode {
+    pub file: HirFileId,
+    pub block: AstPtr<ast::BlockExpr>,
+    /// Index of the first statement of `block` that can't be reached. If this
+    /// is past the last statement, only the tail expression is unreachable.
+    pub first_stmt: usize,
+}
+
+impl Diagnostic for UnreachableCode {
+    fn message(&self) -> String {
+        "unreachable code".to_string()
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.block.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for UnreachableCode {
+    type AST = ast::BlockExpr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.file).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::BlockExpr::cast(node).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct UnresolvedMethodCall {
+    pub file: HirFileId,
+    pub call: AstPtr<ast::MethodCallExpr>,
+    pub method_name: Name,
+    /// The receiver type, already rendered for display.
+    pub receiver: String,
+}
+
+impl Diagnostic for UnresolvedMethodCall {
+    fn message(&self) -> String {
+        format!("no method named `{}` found for type `{}`", self.method_name, self.receiver)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.call.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for UnresolvedMethodCall {
+    type AST = ast::MethodCallExpr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.file).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::MethodCallExpr::cast(node).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct ConflictingImpls {
+    pub file: HirFileId,
+    pub impl_: AstPtr<ast::ImplDef>,
+    pub trait_name: Name,
+    /// The impl this one overlaps with, which may be in another crate.
+    pub conflicting: InFile<AstPtr<ast::ImplDef>>,
+}
+
+impl Diagnostic for ConflictingImpls {
+    fn message(&self) -> String {
+        format!("conflicting implementations of trait `{}`", self.trait_name)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.impl_.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct OrphanImpl {
+    pub file: HirFileId,
+    pub impl_: AstPtr<ast::ImplDef>,
+    pub trait_name: Name,
+    /// The foreign trait being implemented.
+    pub trait_: InFile<AstPtr<ast::TraitDef>>,
+}
+
+impl Diagnostic for OrphanImpl {
+    fn message(&self) -> String {
+        format!(
+            "only traits defined in the current crate can be implemented for arbitrary types, \
+             but `{}` is defined in another crate",
+            self.trait_name
+        )
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.impl_.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
@@ -263,6 +263,21 @@ impl HirDisplay for ApplicationTy {
                     write!(f, "{{closure}}")?;
                 }
             }
+            TypeCtor::AsyncBlock { .. } => {
+                write!(f, "impl Future<Output = {}>", self.parameters[0].display(f.db))?;
+            }
+            TypeCtor::Generator { .. } => {
+                write!(f, "impl Generator<")?;
+                if self.parameters[0] != Ty::unit() {
+                    write!(f, "{}, ", self.parameters[0].display(f.db))?;
+                }
+                write!(
+                    f,
+                    "Yield = {}, Return = {}>",
+                    self.parameters[1].display(f.db),
+                    self.parameters[2].display(f.db)
+                )?;
+            }
         }
         Ok(())
     }
@@ -274,14 +289,21 @@ impl HirDisplay for ProjectionTy {
             return write!(f, "{}", TYPE_HINT_TRUNCATION);
         }
 
-        let trait_ = f.db.trait_data(self.trait_(f.db));
-        write!(f, "<{} as {}", self.parameters[0].display(f.db), trait_.name)?;
-        if self.parameters.len() > 1 {
+        let trait_ref = self.trait_ref(f.db);
+        let trait_ = f.db.trait_data(trait_ref.trait_);
+        write!(f, "<{} as {}", trait_ref.substs[0].display(f.db), trait_.name)?;
+        if trait_ref.substs.len() > 1 {
             write!(f, "<")?;
-            f.write_joined(&self.parameters[1..], ", ")?;
+            f.write_joined(&trait_ref.substs[1..], ", ")?;
             write!(f, ">")?;
         }
         write!(f, ">::{}", f.db.type_alias_data(self.associated_ty).name)?;
+        let own_parameters = self.own_parameters(f.db);
+        if !own_parameters.is_empty() {
+            write!(f, "<")?;
+            f.write_joined(own_parameters, ", ")?;
+            write!(f, ">")?;
+        }
         Ok(())
     }
 }
@@ -314,14 +336,14 @@ impl HirDisplay for Ty {
                 }
             }
             Ty::Bound(idx) => write!(f, "?{}.{}", idx.debruijn.depth(), idx.index)?,
-            Ty::Dyn(predicates) | Ty::Opaque(predicates) => {
-                match self {
-                    Ty::Dyn(_) => write!(f, "dyn ")?,
-                    Ty::Opaque(_) => write!(f, "impl ")?,
-                    _ => unreachable!(),
-                };
+            Ty::Dyn(predicates) => {
+                write!(f, "dyn ")?;
                 write_bounds_like_dyn_trait(predicates, f)?;
             }
+            Ty::Opaque(o_ty) => {
+                write!(f, "impl ")?;
+                write_bounds_like_dyn_trait(&o_ty.predicates, f)?;
+            }
             Ty::Unknown => write!(f, "{{unknown}}")?,
             Ty::Infer(..) => write!(f, "_")?,
         }
@@ -30,7 +30,10 @@ use hir_def::{
     type_ref::{Mutability, TypeRef},
     AdtId, AssocItemId, DefWithBodyId, FieldId, FunctionId, TraitId, TypeAliasId, VariantId,
 };
-use hir_expand::{diagnostics::DiagnosticSink, name::name};
+use hir_expand::{
+    diagnostics::DiagnosticSink,
+    name::{name, Name},
+};
 use ra_arena::map::ArenaMap;
 use ra_prof::profile;
 use ra_syntax::SmolStr;
@@ -45,7 +48,7 @@ use crate::{
     db::HirDatabase, infer::diagnostics::InferenceDiagnostic, lower::ImplTraitLoweringMode,
 };
 
-pub(crate) use unify::unify;
+pub use unify::unify;
 
 macro_rules! ty_app {
     ($ctor:pat, $param:pat) => {
@@ -90,7 +93,7 @@ impl_froms!(ExprOrPatId: ExprId, PatId);
 /// Binding modes inferred for patterns.
 /// https://doc.rust-lang.org/reference/patterns.html#binding-modes
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-enum BindingMode {
+pub enum BindingMode {
     Move,
     Ref(Mutability),
 }
@@ -136,6 +139,16 @@ pub struct InferenceResult {
     pub type_of_expr: ArenaMap<ExprId, Ty>,
     pub type_of_pat: ArenaMap<PatId, Ty>,
     pub(super) type_mismatches: ArenaMap<ExprId, TypeMismatch>,
+    pat_type_mismatches: ArenaMap<PatId, TypeMismatch>,
+    /// For each binding, records whether it binds by value or by reference,
+    /// taking default binding modes into account.
+    pat_binding_modes: ArenaMap<PatId, BindingMode>,
+    /// For each typed hole, i.e. `_` in expression position or a call of
+    /// `todo!()`, records the type the context expects of it.
+    hole_types: ArenaMap<ExprId, Ty>,
+    /// For each `impl Trait` in the return type of the function, records the
+    /// concrete type it hides, in the order of `OpaqueTyId::idx`.
+    hidden_types: Vec<Ty>,
 }
 
 impl InferenceResult {
@@ -166,6 +179,18 @@ impl InferenceResult {
     pub fn type_mismatch_for_expr(&self, expr: ExprId) -> Option<&TypeMismatch> {
         self.type_mismatches.get(expr)
     }
+    pub fn type_mismatch_for_pat(&self, pat: PatId) -> Option<&TypeMismatch> {
+        self.pat_type_mismatches.get(pat)
+    }
+    pub fn binding_mode_of_pat(&self, pat: PatId) -> Option<BindingMode> {
+        self.pat_binding_modes.get(pat).copied()
+    }
+    pub fn expected_type_of_hole(&self, expr: ExprId) -> Option<&Ty> {
+        self.hole_types.get(expr)
+    }
+    pub fn hidden_type(&self, idx: usize) -> Option<&Ty> {
+        self.hidden_types.get(idx)
+    }
     pub fn add_diagnostics(
         &self,
         db: &dyn HirDatabase,
@@ -210,6 +235,35 @@ struct InferenceContext<'a> {
     /// closures, but currently this is the only field that will change there,
     /// so it doesn't make sense.
     return_ty: Ty,
+    /// The yield and resume types of the generator we're currently within, if
+    /// any.
+    yield_ty: Option<(Ty, Ty)>,
+    /// Whether the code currently being inferred can't be reached, because
+    /// control flow diverged before it (e.g. after a `return`).
+    diverges: bool,
+    /// The loops and labeled blocks we're currently within, innermost last.
+    breakables: Vec<BreakableContext>,
+}
+
+#[derive(Clone, Debug)]
+struct BreakableContext {
+    /// Whether any `break` targets this loop or block.
+    may_break: bool,
+    /// The type of the values this loop or block is broken with.
+    break_ty: Ty,
+    label: Option<Name>,
+    /// Labeled blocks can only be targeted by `break`s with their label.
+    is_loop: bool,
+}
+
+fn find_breakable<'c>(
+    ctxs: &'c mut [BreakableContext],
+    label: Option<&Name>,
+) -> Option<&'c mut BreakableContext> {
+    match label {
+        Some(label) => ctxs.iter_mut().rev().find(|ctx| ctx.label.as_ref() == Some(label)),
+        None => ctxs.iter_mut().rev().find(|ctx| ctx.is_loop),
+    }
 }
 
 impl<'a> InferenceContext<'a> {
@@ -219,6 +273,9 @@ impl<'a> InferenceContext<'a> {
             table: unify::InferenceTable::new(),
             obligations: Vec::default(),
             return_ty: Ty::Unknown, // set in collect_fn_signature
+            yield_ty: None,
+            diverges: false,
+            breakables: Vec::new(),
             trait_env: TraitEnvironment::lower(db, &resolver),
             db,
             owner,
@@ -238,6 +295,14 @@ impl<'a> InferenceContext<'a> {
             let resolved = self.table.resolve_ty_completely(mem::replace(ty, Ty::Unknown));
             *ty = resolved;
         }
+        for ty in result.hole_types.values_mut() {
+            let resolved = self.table.resolve_ty_completely(mem::replace(ty, Ty::Unknown));
+            *ty = resolved;
+        }
+        for ty in result.hidden_types.iter_mut() {
+            let resolved = self.table.resolve_ty_completely(mem::replace(ty, Ty::Unknown));
+            *ty = resolved;
+        }
         result
     }
 
@@ -366,8 +431,8 @@ impl<'a> InferenceContext<'a> {
                 // this is a workaround while Chalk assoc type projection doesn't always work yet,
                 // but once that is fixed I don't think we should keep this
                 // (we'll probably change how associated types are resolved anyway)
-                if let Ty::Opaque(ref predicates) = inner_ty {
-                    for p in predicates.iter() {
+                if let Ty::Opaque(ref o_ty) = inner_ty {
+                    for p in o_ty.predicates.iter() {
                         if let GenericPredicate::Projection(projection) = p {
                             if projection.projection_ty.associated_ty == res_assoc_ty {
                                 if let ty_app!(_, params) = &projection.ty {
@@ -488,8 +553,51 @@ impl<'a> InferenceContext<'a> {
 
             self.infer_pat(*pat, &ty, BindingMode::default());
         }
-        let return_ty = self.make_ty_with_mode(&data.ret_type, ImplTraitLoweringMode::Disallowed); // FIXME implement RPIT
-        self.return_ty = return_ty;
+        let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver)
+            .with_impl_trait_mode(ImplTraitLoweringMode::Opaque);
+        let return_ty = Ty::from_hir(&ctx, &data.ret_type);
+        // Inside the function, each `impl Trait` in the return type stands for
+        // a concrete type which we infer from the body.
+        let mut hidden_types = Vec::new();
+        let mut last_opaque = None;
+        let return_ty = return_ty.fold(&mut |ty| match ty {
+            Ty::Opaque(opaque) => {
+                let var = self.table.new_type_var();
+                hidden_types.push(var.clone());
+                last_opaque = Some(opaque);
+                var
+            }
+            ty => ty,
+        });
+        // The body of an `async fn` evaluates to the `Output` of its desugared
+        // `impl Future` return type, which is hidden by the body's own future
+        // type. That opaque type is the outermost one, so it comes last.
+        let return_ty = match (data.is_async, last_opaque) {
+            (true, Some(opaque)) => {
+                let future_output = self.resolve_future_future_output();
+                let output = opaque.predicates.iter().find_map(|pred| match pred {
+                    GenericPredicate::Projection(proj)
+                        if Some(proj.projection_ty.associated_ty) == future_output =>
+                    {
+                        Some(proj.ty.clone())
+                    }
+                    _ => None,
+                });
+                let output = output.unwrap_or(Ty::Unknown);
+                let body_future = Ty::apply_one(
+                    TypeCtor::AsyncBlock { def: self.owner, expr: self.body.body_expr },
+                    output.clone(),
+                );
+                if let Some(hidden) = hidden_types.last_mut() {
+                    *hidden = body_future;
+                }
+                output
+            }
+            _ => return_ty,
+        };
+        self.result.hidden_types = hidden_types;
+        let return_ty = self.insert_type_vars(return_ty);
+        self.return_ty = self.normalize_associated_types_in(return_ty);
     }
 
     fn infer_body(&mut self) {
@@ -529,6 +637,12 @@ impl<'a> InferenceContext<'a> {
         self.db.trait_data(trait_).associated_type_by_name(&name![Output])
     }
 
+    fn resolve_into_future_output(&self) -> Option<TypeAliasId> {
+        let path = path![std::future::IntoFuture];
+        let trait_ = self.resolver.resolve_known_trait(self.db.upcast(), &path)?;
+        self.db.trait_data(trait_).associated_type_by_name(&name![Output])
+    }
+
     fn resolve_boxed_box(&self) -> Option<AdtId> {
         let struct_ = self.resolve_lang_item("owned_box")?.as_struct()?;
         Some(struct_.into())
@@ -589,6 +703,8 @@ pub enum InferTy {
     TypeVar(unify::TypeVarId),
     IntVar(unify::TypeVarId),
     FloatVar(unify::TypeVarId),
+    /// A type variable for the type of a diverging expression, which falls
+    /// back to `()` if nothing else constrains it.
     MaybeNeverTypeVar(unify::TypeVarId),
 }
 
@@ -607,7 +723,7 @@ impl InferTy {
             InferTy::TypeVar(..) => Ty::Unknown,
             InferTy::IntVar(..) => Ty::simple(TypeCtor::Int(Uncertain::Known(IntTy::i32()))),
             InferTy::FloatVar(..) => Ty::simple(TypeCtor::Float(Uncertain::Known(FloatTy::f64()))),
-            InferTy::MaybeNeverTypeVar(..) => Ty::simple(TypeCtor::Never),
+            InferTy::MaybeNeverTypeVar(..) => Ty::unit(),
         }
     }
 }
@@ -668,13 +784,21 @@ impl Expectation {
 
 mod diagnostics {
     use hir_def::{expr::ExprId, FunctionId};
-    use hir_expand::diagnostics::DiagnosticSink;
-
-    use crate::{db::HirDatabase, diagnostics::NoSuchField};
+    use hir_expand::{diagnostics::DiagnosticSink, name::Name};
+    use ra_syntax::ast;
+
+    use crate::{
+        db::HirDatabase,
+        diagnostics::{NoSuchField, UnreachableCode, UnresolvedMethodCall},
+        display::HirDisplay,
+        Ty,
+    };
 
     #[derive(Debug, PartialEq, Eq, Clone)]
     pub(super) enum InferenceDiagnostic {
         NoSuchField { expr: ExprId, field: usize },
+        UnreachableCode { block: ExprId, first_stmt: usize },
+        UnresolvedMethodCall { expr: ExprId, method_name: Name, receiver: Ty },
     }
 
     impl InferenceDiagnostic {
@@ -690,6 +814,35 @@ mod diagnostics {
                     let field = source_map.field_syntax(*expr, *field);
                     sink.push(NoSuchField { file: field.file_id, field: field.value })
                 }
+                InferenceDiagnostic::UnreachableCode { block, first_stmt } => {
+                    let (_, source_map) = db.body_with_source_map(owner.into());
+                    let block = match source_map.expr_syntax(*block) {
+                        Ok(it) => it,
+                        Err(_) => return,
+                    };
+                    if let Some(ptr) = block.value.cast::<ast::BlockExpr>() {
+                        sink.push(UnreachableCode {
+                            file: block.file_id,
+                            block: ptr,
+                            first_stmt: *first_stmt,
+                        })
+                    }
+                }
+                InferenceDiagnostic::UnresolvedMethodCall { expr, method_name, receiver } => {
+                    let (_, source_map) = db.body_with_source_map(owner.into());
+                    let call = match source_map.expr_syntax(*expr) {
+                        Ok(it) => it,
+                        Err(_) => return,
+                    };
+                    if let Some(ptr) = call.value.cast::<ast::MethodCallExpr>() {
+                        sink.push(UnresolvedMethodCall {
+                            file: call.file_id,
+                            call: ptr,
+                            method_name: method_name.clone(),
+                            receiver: receiver.display(db).to_string(),
+                        })
+                    }
+                }
             }
         }
     }
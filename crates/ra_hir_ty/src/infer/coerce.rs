@@ -40,7 +40,7 @@ impl<'a> InferenceContext<'a> {
 
     fn coerce_inner(&mut self, mut from_ty: Ty, to_ty: &Ty) -> bool {
         match (&from_ty, to_ty) {
-            // Never type will make type variable to fallback to Never Type instead of Unknown.
+            // Never type will make type variable to fallback to `()` instead of Unknown.
             (ty_app!(TypeCtor::Never), Ty::Infer(InferTy::TypeVar(tv))) => {
                 let var = self.table.new_maybe_never_type_var();
                 self.table.var_unification_table.union_value(*tv, TypeVarValue::Known(var));
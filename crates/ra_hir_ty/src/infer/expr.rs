@@ -1,9 +1,10 @@
 //! Type inference for expressions.
 
 use std::iter::{repeat, repeat_with};
-use std::sync::Arc;
+use std::{mem, sync::Arc};
 
 use hir_def::{
+    body::Body,
     builtin_type::Signedness,
     expr::{Array, BinaryOp, Expr, ExprId, Literal, Statement, UnaryOp},
     path::{GenericArg, GenericArgs},
@@ -18,16 +19,21 @@ use crate::{
     traits::InEnvironment,
     utils::{generics, variant_data, Generics},
     ApplicationTy, Binders, CallableDef, InferTy, IntTy, Mutability, Obligation, Substs, TraitRef,
-    Ty, TypeCtor, Uncertain,
+    Ty, TypeCtor, TypeWalk, Uncertain,
 };
 
-use super::{BindingMode, Expectation, InferenceContext, InferenceDiagnostic, TypeMismatch};
+use super::{
+    find_breakable, BindingMode, BreakableContext, Expectation, InferenceContext,
+    InferenceDiagnostic, TypeMismatch,
+};
 
 impl<'a> InferenceContext<'a> {
     pub(super) fn infer_expr(&mut self, tgt_expr: ExprId, expected: &Expectation) -> Ty {
+        // Don't report type mismatches in unreachable code.
+        let unreachable = self.diverges;
         let ty = self.infer_expr_inner(tgt_expr, expected);
         let could_unify = self.unify(&ty, &expected.ty);
-        if !could_unify {
+        if !could_unify && !unreachable {
             self.result.type_mismatches.insert(
                 tgt_expr,
                 TypeMismatch { expected: expected.ty.clone(), actual: ty.clone() },
@@ -39,11 +45,15 @@ impl<'a> InferenceContext<'a> {
     /// Infer type of expression with possibly implicit coerce to the expected type.
     /// Return the type after possible coercion.
     pub(super) fn infer_expr_coerce(&mut self, expr: ExprId, expected: &Expectation) -> Ty {
+        let unreachable = self.diverges;
         let ty = self.infer_expr_inner(expr, &expected);
         let ty = if !self.coerce(&ty, &expected.coercion_target()) {
-            self.result
-                .type_mismatches
-                .insert(expr, TypeMismatch { expected: expected.ty.clone(), actual: ty.clone() });
+            if !unreachable {
+                self.result.type_mismatches.insert(
+                    expr,
+                    TypeMismatch { expected: expected.ty.clone(), actual: ty.clone() },
+                );
+            }
             // Return actual type when type mismatch.
             // This is needed for diagnostic when return type mismatch.
             ty
@@ -58,48 +68,108 @@ impl<'a> InferenceContext<'a> {
 
     fn infer_expr_inner(&mut self, tgt_expr: ExprId, expected: &Expectation) -> Ty {
         let body = Arc::clone(&self.body); // avoid borrow checker problem
+        let diverges_on_entry = self.diverges;
         let ty = match &body[tgt_expr] {
             Expr::Missing => Ty::Unknown,
             Expr::If { condition, then_branch, else_branch } => {
                 // if let is desugared to match, so this is always simple if
                 self.infer_expr(*condition, &Expectation::has_type(Ty::simple(TypeCtor::Bool)));
+                let condition_diverges = self.diverges;
 
                 let then_ty = self.infer_expr_inner(*then_branch, &expected);
+                let then_diverges = mem::replace(&mut self.diverges, condition_diverges);
                 let else_ty = match else_branch {
                     Some(else_branch) => self.infer_expr_inner(*else_branch, &expected),
                     None => Ty::unit(),
                 };
+                self.diverges = condition_diverges || (then_diverges && self.diverges);
 
                 self.coerce_merge_branch(&then_ty, &else_ty)
             }
-            Expr::Block { statements, tail } => self.infer_block(statements, *tail, expected),
+            Expr::Block { statements, tail, label: None } => {
+                self.infer_block(tgt_expr, statements, *tail, expected)
+            }
+            Expr::Block { statements, tail, label: Some(label) } => {
+                let break_ty = self.table.new_type_var();
+                self.breakables.push(BreakableContext {
+                    may_break: false,
+                    break_ty: break_ty.clone(),
+                    label: Some(label.clone()),
+                    is_loop: false,
+                });
+                let ty = self.infer_block(
+                    tgt_expr,
+                    statements,
+                    *tail,
+                    &Expectation::has_type(break_ty.clone()),
+                );
+                let ctxt = self.breakables.pop().expect("breakable stack broken");
+                if ctxt.may_break {
+                    self.diverges = diverges_on_entry;
+                    break_ty
+                } else {
+                    ty
+                }
+            }
             Expr::TryBlock { body } => {
                 let _inner = self.infer_expr(*body, expected);
                 // FIXME should be std::result::Result<{inner}, _>
                 Ty::Unknown
             }
-            Expr::Loop { body } => {
+            Expr::Loop { body, label } => {
+                let break_ty = self.table.new_type_var();
+                self.breakables.push(BreakableContext {
+                    may_break: false,
+                    break_ty: break_ty.clone(),
+                    label: label.clone(),
+                    is_loop: true,
+                });
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
-                // FIXME handle break with value
-                Ty::simple(TypeCtor::Never)
+                let ctxt = self.breakables.pop().expect("breakable stack broken");
+                if ctxt.may_break {
+                    self.diverges = diverges_on_entry;
+                    break_ty
+                } else {
+                    Ty::simple(TypeCtor::Never)
+                }
             }
-            Expr::While { condition, body } => {
+            Expr::While { condition, body, label } => {
+                self.breakables.push(BreakableContext {
+                    may_break: false,
+                    break_ty: Ty::Unknown,
+                    label: label.clone(),
+                    is_loop: true,
+                });
                 // while let is desugared to a match loop, so this is always simple while
                 self.infer_expr(*condition, &Expectation::has_type(Ty::simple(TypeCtor::Bool)));
+                let condition_diverges = self.diverges;
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
+                self.breakables.pop().expect("breakable stack broken");
+                // the body might not run at all
+                self.diverges = condition_diverges;
                 Ty::unit()
             }
-            Expr::For { iterable, body, pat } => {
+            Expr::For { iterable, body, pat, label } => {
                 let iterable_ty = self.infer_expr(*iterable, &Expectation::none());
+                let iterable_diverges = self.diverges;
 
                 let pat_ty =
                     self.resolve_associated_type(iterable_ty, self.resolve_into_iter_item());
 
                 self.infer_pat(*pat, &pat_ty, BindingMode::default());
+                self.breakables.push(BreakableContext {
+                    may_break: false,
+                    break_ty: Ty::Unknown,
+                    label: label.clone(),
+                    is_loop: true,
+                });
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
+                self.breakables.pop().expect("breakable stack broken");
+                // the body might not run at all
+                self.diverges = iterable_diverges;
                 Ty::unit()
             }
-            Expr::Lambda { body, args, ret_type, arg_types } => {
+            Expr::Lambda { body: body_expr, args, ret_type, arg_types } => {
                 assert_eq!(args.len(), arg_types.len());
 
                 let mut sig_tys = Vec::new();
@@ -124,22 +194,63 @@ impl<'a> InferenceContext<'a> {
                     TypeCtor::FnPtr { num_args: sig_tys.len() as u16 - 1 },
                     Substs(sig_tys.into()),
                 );
-                let closure_ty =
-                    Ty::apply_one(TypeCtor::Closure { def: self.owner, expr: tgt_expr }, sig_ty);
+                // A closure containing `yield` is a generator; its argument,
+                // if any, is the resume type.
+                let (closure_ty, yield_ty) = if contains_yield(&body, *body_expr) {
+                    let resume_ty =
+                        sig_ty.callable_sig(self.db).and_then(|sig| sig.params().first().cloned());
+                    let resume_ty = resume_ty.unwrap_or_else(Ty::unit);
+                    let yield_ty = self.table.new_type_var();
+                    let generator_ty = Ty::apply(
+                        TypeCtor::Generator { def: self.owner, expr: tgt_expr },
+                        Substs(vec![resume_ty.clone(), yield_ty.clone(), ret_ty.clone()].into()),
+                    );
+                    (generator_ty, Some((yield_ty, resume_ty)))
+                } else {
+                    let closure_ty = Ty::apply_one(
+                        TypeCtor::Closure { def: self.owner, expr: tgt_expr },
+                        sig_ty,
+                    );
+                    (closure_ty, None)
+                };
 
                 // Eagerly try to relate the closure type with the expected
                 // type, otherwise we often won't have enough information to
                 // infer the body.
                 self.coerce(&closure_ty, &expected.ty);
 
-                let prev_ret_ty = std::mem::replace(&mut self.return_ty, ret_ty.clone());
+                let prev_ret_ty = mem::replace(&mut self.return_ty, ret_ty.clone());
+                let prev_yield_ty = mem::replace(&mut self.yield_ty, yield_ty);
+                let prev_diverges = mem::replace(&mut self.diverges, false);
+                let prev_breakables = mem::take(&mut self.breakables);
 
-                self.infer_expr_coerce(*body, &Expectation::has_type(ret_ty));
+                self.infer_expr_coerce(*body_expr, &Expectation::has_type(ret_ty));
 
                 self.return_ty = prev_ret_ty;
+                self.yield_ty = prev_yield_ty;
+                self.diverges = prev_diverges;
+                self.breakables = prev_breakables;
 
                 closure_ty
             }
+            Expr::Async { body: body_expr } => {
+                // `return` and `?` inside the block refer to the block, not to
+                // the enclosing function.
+                let ret_ty = self.table.new_type_var();
+                let prev_ret_ty = mem::replace(&mut self.return_ty, ret_ty.clone());
+                let prev_yield_ty = self.yield_ty.take();
+                let prev_diverges = mem::replace(&mut self.diverges, false);
+                let prev_breakables = mem::take(&mut self.breakables);
+
+                self.infer_expr_coerce(*body_expr, &Expectation::has_type(ret_ty.clone()));
+
+                self.return_ty = prev_ret_ty;
+                self.yield_ty = prev_yield_ty;
+                self.diverges = prev_diverges;
+                self.breakables = prev_breakables;
+
+                Ty::apply_one(TypeCtor::AsyncBlock { def: self.owner, expr: tgt_expr }, ret_ty)
+            }
             Expr::Call { callee, args } => {
                 let callee_ty = self.infer_expr(*callee, &Expectation::none());
                 let (param_tys, ret_ty) = match callee_ty.callable_sig(self.db) {
@@ -158,6 +269,8 @@ impl<'a> InferenceContext<'a> {
                 .infer_method_call(tgt_expr, *receiver, &args, &method_name, generic_args.as_ref()),
             Expr::Match { expr, arms } => {
                 let input_ty = self.infer_expr(*expr, &Expectation::none());
+                let input_diverges = self.diverges;
+                let mut all_arms_diverge = true;
 
                 let mut result_ty = if arms.is_empty() {
                     Ty::simple(TypeCtor::Never)
@@ -166,6 +279,7 @@ impl<'a> InferenceContext<'a> {
                 };
 
                 for arm in arms {
+                    self.diverges = input_diverges;
                     let _pat_ty = self.infer_pat(arm.pat, &input_ty, BindingMode::default());
                     if let Some(guard_expr) = arm.guard {
                         self.infer_expr(
@@ -176,7 +290,9 @@ impl<'a> InferenceContext<'a> {
 
                     let arm_ty = self.infer_expr_inner(arm.expr, &expected);
                     result_ty = self.coerce_merge_branch(&result_ty, &arm_ty);
+                    all_arms_diverge &= self.diverges;
                 }
+                self.diverges = input_diverges || all_arms_diverge;
 
                 result_ty
             }
@@ -185,11 +301,20 @@ impl<'a> InferenceContext<'a> {
                 let resolver = resolver_for_expr(self.db.upcast(), self.owner, tgt_expr);
                 self.infer_path(&resolver, p, tgt_expr.into()).unwrap_or(Ty::Unknown)
             }
-            Expr::Continue => Ty::simple(TypeCtor::Never),
-            Expr::Break { expr } => {
+            Expr::Continue { .. } => Ty::simple(TypeCtor::Never),
+            Expr::Break { expr, label } => {
+                let break_ty = match find_breakable(&mut self.breakables, label.as_ref()) {
+                    Some(ctxt) => {
+                        ctxt.may_break = true;
+                        ctxt.break_ty.clone()
+                    }
+                    None => Ty::Unknown,
+                };
                 if let Some(expr) = expr {
-                    // FIXME handle break with value
-                    self.infer_expr(*expr, &Expectation::none());
+                    self.infer_expr_coerce(*expr, &Expectation::has_type(break_ty));
+                } else {
+                    let unit = Ty::unit();
+                    self.coerce(&unit, &break_ty);
                 }
                 Ty::simple(TypeCtor::Never)
             }
@@ -202,6 +327,24 @@ impl<'a> InferenceContext<'a> {
                 }
                 Ty::simple(TypeCtor::Never)
             }
+            Expr::Yield { expr } => match self.yield_ty.clone() {
+                Some((yield_ty, resume_ty)) => {
+                    if let Some(expr) = expr {
+                        self.infer_expr_coerce(*expr, &Expectation::has_type(yield_ty));
+                    } else {
+                        let unit = Ty::unit();
+                        self.coerce(&unit, &yield_ty);
+                    }
+                    resume_ty
+                }
+                None => {
+                    // `yield` outside of a generator
+                    if let Some(expr) = expr {
+                        self.infer_expr(*expr, &Expectation::none());
+                    }
+                    Ty::Unknown
+                }
+            },
             Expr::RecordLit { path, fields, spread } => {
                 let (ty, def_id) = self.resolve_variant(path.as_ref());
                 if let Some(variant) = def_id {
@@ -274,7 +417,24 @@ impl<'a> InferenceContext<'a> {
             }
             Expr::Await { expr } => {
                 let inner_ty = self.infer_expr_inner(*expr, &Expectation::none());
-                self.resolve_associated_type(inner_ty, self.resolve_future_future_output())
+                let inner_ty = self.resolve_ty_shallow(&inner_ty).into_owned();
+                match &inner_ty {
+                    // The output of an `async` block is its type parameter.
+                    Ty::Apply(ApplicationTy { ctor: TypeCtor::AsyncBlock { .. }, parameters }) => {
+                        parameters[0].clone()
+                    }
+                    _ => {
+                        // `.await` goes through `IntoFuture` where it exists,
+                        // except for `impl Future`s whose output we can read
+                        // off directly.
+                        let output = match inner_ty {
+                            Ty::Opaque(_) => None,
+                            _ => self.resolve_into_future_output(),
+                        };
+                        let output = output.or_else(|| self.resolve_future_future_output());
+                        self.resolve_associated_type(inner_ty, output)
+                    }
+                }
             }
             Expr::Try { expr } => {
                 let inner_ty = self.infer_expr_inner(*expr, &Expectation::none());
@@ -373,10 +533,15 @@ impl<'a> InferenceContext<'a> {
                         _ => Expectation::none(),
                     };
                     let lhs_ty = self.infer_expr(*lhs, &lhs_expectation);
+                    let lhs_diverges = self.diverges;
                     // FIXME: find implementation of trait corresponding to operation
                     // symbol and resolve associated `Output` type
                     let rhs_expectation = op::binary_op_rhs_expectation(*op, lhs_ty.clone());
                     let rhs_ty = self.infer_expr(*rhs, &Expectation::has_type(rhs_expectation));
+                    if let BinaryOp::LogicOp(_) = op {
+                        // the right-hand side might not be evaluated
+                        self.diverges = lhs_diverges;
+                    }
 
                     // FIXME: similar as above, return ty is often associated trait type
                     op::binary_op_return_ty(*op, lhs_ty, rhs_ty)
@@ -509,21 +674,38 @@ impl<'a> InferenceContext<'a> {
                 Literal::Float(_v, ty) => Ty::simple(TypeCtor::Float((*ty).into())),
             },
         };
+        if body.typed_holes.contains(&tgt_expr) {
+            self.result.hole_types.insert(tgt_expr, expected.ty.clone());
+        }
         // use a new type variable if we got Ty::Unknown here
         let ty = self.insert_type_vars_shallow(ty);
         let ty = self.resolve_ty_as_possible(ty);
+        if let ty_app!(TypeCtor::Never) = ty {
+            self.diverges = true;
+        }
         self.write_expr_ty(tgt_expr, ty.clone());
         ty
     }
 
     fn infer_block(
         &mut self,
+        block: ExprId,
         statements: &[Statement],
         tail: Option<ExprId>,
         expected: &Expectation,
     ) -> Ty {
-        let mut diverges = false;
-        for stmt in statements {
+        let diverges_on_entry = self.diverges;
+        // If the whole block is unreachable, that's reported for the enclosing
+        // block already.
+        let mut unreachable_reported = diverges_on_entry;
+        for (idx, stmt) in statements.iter().enumerate() {
+            if self.diverges && !unreachable_reported {
+                self.push_diagnostic(InferenceDiagnostic::UnreachableCode {
+                    block,
+                    first_stmt: idx,
+                });
+                unreachable_reported = true;
+            }
             match stmt {
                 Statement::Let { pat, type_ref, initializer } => {
                     let decl_ty =
@@ -536,7 +718,12 @@ impl<'a> InferenceContext<'a> {
                         let actual_ty =
                             self.infer_expr_coerce(*expr, &Expectation::has_type(decl_ty.clone()));
                         if decl_ty == Ty::Unknown {
-                            ty = actual_ty;
+                            ty = match actual_ty {
+                                // The binding can still be used as any type,
+                                // e.g. in `let x = return;`.
+                                ty_app!(TypeCtor::Never) => self.table.new_maybe_never_type_var(),
+                                _ => actual_ty,
+                            };
                         }
                     }
 
@@ -544,20 +731,26 @@ impl<'a> InferenceContext<'a> {
                     self.infer_pat(*pat, &ty, BindingMode::default());
                 }
                 Statement::Expr(expr) => {
-                    if let ty_app!(TypeCtor::Never) = self.infer_expr(*expr, &Expectation::none()) {
-                        diverges = true;
-                    }
+                    self.infer_expr(*expr, &Expectation::none());
                 }
             }
         }
+        let statements_diverge = self.diverges && !diverges_on_entry;
 
         let ty = if let Some(expr) = tail {
+            if self.diverges && !unreachable_reported {
+                let first_stmt = statements.len();
+                self.push_diagnostic(InferenceDiagnostic::UnreachableCode { block, first_stmt });
+            }
             self.infer_expr_coerce(expr, expected)
+        } else if statements_diverge {
+            // a block without tail whose statements diverge has type `!`, not `()`
+            Ty::simple(TypeCtor::Never)
         } else {
             self.coerce(&Ty::unit(), expected.coercion_target());
             Ty::unit()
         };
-        if diverges {
+        if statements_diverge {
             Ty::simple(TypeCtor::Never)
         } else {
             ty
@@ -593,7 +786,42 @@ impl<'a> InferenceContext<'a> {
                 self.write_method_resolution(tgt_expr, func);
                 (ty, self.db.value_ty(func.into()), Some(generics(self.db.upcast(), func.into())))
             }
-            None => (receiver_ty, Binders::new(0, Ty::Unknown), None),
+            None => {
+                // Only report the call if we know what the receiver is;
+                // otherwise the method may well exist and we just failed to
+                // infer the receiver type. We don't fully handle associated
+                // types and `impl Trait` yet, so receivers involving them
+                // count as unknown too, as do receivers for which the trait
+                // solver gave up.
+                let receiver = self.resolve_ty_as_possible(receiver_ty.clone());
+                let mut known = true;
+                receiver.walk(&mut |ty| match ty {
+                    Ty::Unknown | Ty::Infer(_) | Ty::Projection(_) | Ty::Opaque(_) => known = false,
+                    Ty::Apply(ApplicationTy { ctor: TypeCtor::AssociatedType(_), .. }) => {
+                        known = false
+                    }
+                    _ => {}
+                });
+                let known = known
+                    && !self.resolver.krate().map_or(false, |krate| {
+                        method_resolution::method_lookup_runs_out_of_fuel(
+                            &canonicalized_receiver.value,
+                            self.db,
+                            self.trait_env.clone(),
+                            krate,
+                            &traits_in_scope,
+                            method_name,
+                        )
+                    });
+                if known {
+                    self.push_diagnostic(InferenceDiagnostic::UnresolvedMethodCall {
+                        expr: tgt_expr,
+                        method_name: method_name.clone(),
+                        receiver,
+                    });
+                }
+                (receiver_ty, Binders::new(0, Ty::Unknown), None)
+            }
         };
         let substs = self.substs_for_method_call(def_generics, generic_args, &derefed_receiver_ty);
         let method_ty = method_ty.subst(&substs);
@@ -715,3 +943,17 @@ impl<'a> InferenceContext<'a> {
         }
     }
 }
+
+/// Whether the closure body `expr` contains a `yield`, making the closure a
+/// generator. `yield`s inside nested closures don't count.
+fn contains_yield(body: &Body, expr: ExprId) -> bool {
+    match &body[expr] {
+        Expr::Yield { .. } => true,
+        Expr::Lambda { .. } => false,
+        e => {
+            let mut found = false;
+            e.walk_child_exprs(|child| found |= contains_yield(body, child));
+            found
+        }
+    }
+}
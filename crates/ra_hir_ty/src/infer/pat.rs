@@ -4,15 +4,17 @@ use std::iter::repeat;
 use std::sync::Arc;
 
 use hir_def::{
-    expr::{BindingAnnotation, Pat, PatId, RecordFieldPat},
+    body::Body,
+    expr::{BindingAnnotation, Expr, Literal, Pat, PatId, RecordFieldPat},
     path::Path,
+    resolver::ValueNs,
     type_ref::Mutability,
     FieldId,
 };
 use hir_expand::name::Name;
 use test_utils::tested_by;
 
-use super::{BindingMode, Expectation, InferenceContext};
+use super::{BindingMode, Expectation, InferenceContext, TypeMismatch};
 use crate::{utils::variant_data, Substs, Ty, TypeCtor};
 
 impl<'a> InferenceContext<'a> {
@@ -23,6 +25,7 @@ impl<'a> InferenceContext<'a> {
         expected: &Ty,
         default_bm: BindingMode,
         id: PatId,
+        ellipsis: Option<usize>,
     ) -> Ty {
         let (ty, def) = self.resolve_variant(path);
         let var_data = def.map(|it| variant_data(self.db.upcast(), it));
@@ -34,8 +37,15 @@ impl<'a> InferenceContext<'a> {
         let substs = ty.substs().unwrap_or_else(Substs::empty);
 
         let field_tys = def.map(|it| self.db.field_types(it)).unwrap_or_default();
+        // Subpatterns after `..` match the last fields.
+        let n_skipped =
+            var_data.as_ref().map_or(0, |d| d.fields().len().saturating_sub(subpats.len()));
 
         for (i, &subpat) in subpats.iter().enumerate() {
+            let i = match ellipsis {
+                Some(idx) if i >= idx => i + n_skipped,
+                _ => i,
+            };
             let expected_ty = var_data
                 .as_ref()
                 .and_then(|d| d.field(&Name::new_tuple_field(i)))
@@ -90,18 +100,7 @@ impl<'a> InferenceContext<'a> {
     ) -> Ty {
         let body = Arc::clone(&self.body); // avoid borrow checker problem
 
-        let is_non_ref_pat = match &body[pat] {
-            Pat::Tuple { .. }
-            | Pat::Or(..)
-            | Pat::TupleStruct { .. }
-            | Pat::Record { .. }
-            | Pat::Range { .. }
-            | Pat::Slice { .. } => true,
-            // FIXME: Path/Lit might actually evaluate to ref, but inference is unimplemented.
-            Pat::Path(..) | Pat::Lit(..) => true,
-            Pat::Wild | Pat::Bind { .. } | Pat::Ref { .. } | Pat::Missing => false,
-        };
-        if is_non_ref_pat {
+        if self.is_non_ref_pat(&body, pat) {
             while let Some((inner, mutability)) = expected.as_reference() {
                 expected = inner;
                 default_bm = match default_bm {
@@ -122,20 +121,34 @@ impl<'a> InferenceContext<'a> {
         let expected = expected;
 
         let ty = match &body[pat] {
-            Pat::Tuple { ref args, .. } => {
+            Pat::Tuple { ref args, ellipsis } => {
                 let expectations = match expected.as_tuple() {
                     Some(parameters) => &*parameters.0,
                     _ => &[],
                 };
-                let expectations_iter = expectations.iter().chain(repeat(&Ty::Unknown));
+                let (pre, post) = match ellipsis {
+                    Some(idx) => args.split_at(*idx),
+                    None => (&args[..], &[][..]),
+                };
+                // The elements covered by `..` just get their expected types.
+                let n_uncovered = expectations.len().saturating_sub(args.len());
+                let mut expectations_iter = expectations.iter().chain(repeat(&Ty::Unknown));
 
-                let inner_tys = args
-                    .iter()
-                    .zip(expectations_iter)
-                    .map(|(&pat, ty)| self.infer_pat(pat, ty, default_bm))
-                    .collect();
+                let mut inner_tys = Vec::with_capacity(n_uncovered + args.len());
+                for &pat in pre {
+                    let ty = expectations_iter.next().unwrap();
+                    inner_tys.push(self.infer_pat(pat, ty, default_bm));
+                }
+                inner_tys.extend(expectations_iter.by_ref().take(n_uncovered).cloned());
+                for &pat in post {
+                    let ty = expectations_iter.next().unwrap();
+                    inner_tys.push(self.infer_pat(pat, ty, default_bm));
+                }
 
-                Ty::apply(TypeCtor::Tuple { cardinality: args.len() as u16 }, Substs(inner_tys))
+                Ty::apply(
+                    TypeCtor::Tuple { cardinality: inner_tys.len() as u16 },
+                    Substs(inner_tys.into()),
+                )
             }
             Pat::Or(ref pats) => {
                 if let Some((first_pat, rest)) = pats.split_first() {
@@ -149,21 +162,22 @@ impl<'a> InferenceContext<'a> {
                 }
             }
             Pat::Ref { pat, mutability } => {
+                // A mismatched mutability is recorded as a type mismatch below.
                 let expectation = match expected.as_reference() {
-                    Some((inner_ty, exp_mut)) => {
-                        if *mutability != exp_mut {
-                            // FIXME: emit type error?
-                        }
-                        inner_ty
-                    }
+                    Some((inner_ty, _exp_mut)) => inner_ty,
                     _ => &Ty::Unknown,
                 };
                 let subty = self.infer_pat(*pat, expectation, default_bm);
                 Ty::apply_one(TypeCtor::Ref(*mutability), subty)
             }
-            Pat::TupleStruct { path: p, args: subpats, .. } => {
-                self.infer_tuple_struct_pat(p.as_ref(), subpats, expected, default_bm, pat)
-            }
+            Pat::TupleStruct { path: p, args: subpats, ellipsis } => self.infer_tuple_struct_pat(
+                p.as_ref(),
+                subpats,
+                expected,
+                default_bm,
+                pat,
+                *ellipsis,
+            ),
             Pat::Record { path: p, args: fields, ellipsis: _ } => {
                 self.infer_record_pat(p.as_ref(), fields, expected, default_bm, pat)
             }
@@ -193,9 +207,10 @@ impl<'a> InferenceContext<'a> {
                 };
                 let bound_ty = self.resolve_ty_as_possible(bound_ty);
                 self.write_pat_ty(pat, bound_ty);
+                self.result.pat_binding_modes.insert(pat, mode);
                 return inner_ty;
             }
-            Pat::Slice { prefix, slice: _slice, suffix } => {
+            Pat::Slice { prefix, slice, suffix } => {
                 let (container_ty, elem_ty) = match &expected {
                     ty_app!(TypeCtor::Array, st) => (TypeCtor::Array, st.as_single().clone()),
                     ty_app!(TypeCtor::Slice, st) => (TypeCtor::Slice, st.as_single().clone()),
@@ -206,7 +221,14 @@ impl<'a> InferenceContext<'a> {
                     self.infer_pat(*pat_id, &elem_ty, default_bm);
                 }
 
-                Ty::apply_one(container_ty, elem_ty)
+                let pat_ty = Ty::apply_one(container_ty, elem_ty);
+                // The rest of a slice is a slice again, and the rest of an
+                // array an (unsized) array, so that `rest @ ..` gets a type.
+                if let Some(slice_pat) = slice {
+                    self.infer_pat(*slice_pat, &pat_ty, default_bm);
+                }
+
+                pat_ty
             }
             Pat::Wild => expected.clone(),
             Pat::Range { start, end } => {
@@ -214,16 +236,44 @@ impl<'a> InferenceContext<'a> {
                 let end_ty = self.infer_expr(*end, &Expectation::has_type(start_ty));
                 end_ty
             }
-            Pat::Lit(expr) => self.infer_expr(*expr, &Expectation::has_type(expected.clone())),
+            // A mismatch is recorded for the pattern below, not the literal.
+            Pat::Lit(expr) => self.infer_expr(*expr, &Expectation::none()),
             Pat::Missing => Ty::Unknown,
         };
         // use a new type variable if we got Ty::Unknown here
         let ty = self.insert_type_vars_shallow(ty);
         if !self.unify(&ty, expected) {
-            // FIXME record mismatch, we need to change the type of self.type_mismatches for that
+            let expected = self.resolve_ty_as_possible(expected.clone());
+            let actual = self.resolve_ty_as_possible(ty.clone());
+            self.result.pat_type_mismatches.insert(pat, TypeMismatch { expected, actual });
         }
         let ty = self.resolve_ty_as_possible(ty);
         self.write_pat_ty(pat, ty.clone());
         ty
     }
+
+    /// Whether `pat` is matched through references in the expected type,
+    /// adjusting the default binding mode on the way ("match ergonomics").
+    fn is_non_ref_pat(&self, body: &Body, pat: PatId) -> bool {
+        match &body[pat] {
+            Pat::Tuple { .. }
+            | Pat::TupleStruct { .. }
+            | Pat::Record { .. }
+            | Pat::Range { .. }
+            | Pat::Slice { .. } => true,
+            Pat::Or(pats) => pats.iter().all(|&pat| self.is_non_ref_pat(body, pat)),
+            // Constants can have reference types, so they are matched against
+            // the expected type as is.
+            Pat::Path(path) => !matches!(
+                self.resolver.resolve_path_in_value_ns_fully(self.db.upcast(), path.mod_path()),
+                Some(ValueNs::ConstId(_)) | Some(ValueNs::StaticId(_))
+            ),
+            // Same for string literals, which are references themselves.
+            Pat::Lit(expr) => !matches!(
+                body[*expr],
+                Expr::Literal(Literal::String(..)) | Expr::Literal(Literal::ByteString(..))
+            ),
+            Pat::Wild | Pat::Bind { .. } | Pat::Ref { .. } | Pat::Missing => false,
+        }
+    }
 }
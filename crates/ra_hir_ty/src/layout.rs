@@ -0,0 +1,536 @@
+//! Computes the memory layout of types: their size, alignment, field offsets
+//! and niches.
+//!
+//! This mirrors what rustc does, but is necessarily an approximation: rustc's
+//! field reordering for the default representation has a couple of heuristics
+//! we don't replicate. The layouts of `#[repr(C)]`, `#[repr(packed)]`,
+//! `#[repr(transparent)]` and `#[repr(<int>)]` types are fully specified, and
+//! for those we should agree with rustc.
+//!
+//! The size of pointers is read from the `target_pointer_width` cfg of the
+//! crate, which we get from `rustc --print cfg` for the configured target.
+
+use std::{cmp, sync::Arc};
+
+use hir_def::{
+    adt::{ReprOptions, VariantData},
+    path::GenericArg,
+    type_ref::TypeRef,
+    AdtId, AttrDefId, EnumVariantId, VariantId,
+};
+use ra_db::CrateId;
+use ra_prof::profile;
+use tt::{Leaf, TokenTree};
+
+use crate::{
+    db::HirDatabase,
+    primitive::{FloatBitness, IntBitness, Uncertain},
+    ApplicationTy, DebruijnIndex, Substs, Ty, TypeCtor,
+};
+
+/// Target-specific information needed to compute layouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetDataLayout {
+    /// The size and alignment of pointers, in bytes.
+    pub pointer_size: u64,
+}
+
+impl TargetDataLayout {
+    pub fn for_crate(db: &dyn HirDatabase, krate: CrateId) -> TargetDataLayout {
+        let pointer_width = db.crate_graph()[krate]
+            .cfg_options
+            .key_values("target_pointer_width")
+            .find_map(|it| it.parse::<u64>().ok())
+            .unwrap_or(64);
+        TargetDataLayout { pointer_size: pointer_width / 8 }
+    }
+
+    fn int_size(&self, bitness: IntBitness) -> u64 {
+        match bitness {
+            IntBitness::Xsize => self.pointer_size,
+            IntBitness::X8 => 1,
+            IntBitness::X16 => 2,
+            IntBitness::X32 => 4,
+            IntBitness::X64 => 8,
+            IntBitness::X128 => 16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub size: u64,
+    pub align: u64,
+    /// The fields in declaration order. For enums, these are empty; see the
+    /// layouts in `variants` instead.
+    pub fields: Vec<FieldLayout>,
+    pub variants: Variants,
+    /// The niche with the most invalid values in this type, which enclosing
+    /// enums can use to store their discriminant.
+    pub largest_niche: Option<Niche>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldLayout {
+    pub offset: u64,
+    pub size: u64,
+    pub align: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Variants {
+    /// Structs, unions, tuples, scalars and enums with a single variant.
+    Single,
+    /// An enum storing its discriminant in a tag at offset zero.
+    Tagged { tag_size: u64, variants: Vec<Layout> },
+    /// An enum storing its discriminant in the niche of its only variant with
+    /// data, like `Option<&T>`.
+    NicheFilling { dataful_variant: usize, niche: Niche, variants: Vec<Layout> },
+}
+
+/// A scalar somewhere in a type which has invalid values, like the `0` of a
+/// reference or the values `2..=255` of a `bool`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Niche {
+    pub offset: u64,
+    pub size: u64,
+    /// The number of invalid values not yet used by some enum.
+    pub available: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The type contains type parameters, inference variables or things we
+    /// couldn't resolve.
+    NotConcrete,
+    Unsized,
+    /// We don't know the length of an array, e.g. because it's not written
+    /// as a literal in the type.
+    UnknownArrayLength,
+    /// The type contains itself without indirection.
+    Recursive,
+    /// The size of the type doesn't fit into the address space.
+    SizeOverflow,
+}
+
+impl Layout {
+    /// The layout of the given variant; types with a single variant are their
+    /// own variant zero.
+    pub fn variant(&self, idx: usize) -> Option<&Layout> {
+        match &self.variants {
+            Variants::Single if idx == 0 => Some(self),
+            Variants::Single => None,
+            Variants::Tagged { variants, .. } | Variants::NicheFilling { variants, .. } => {
+                variants.get(idx)
+            }
+        }
+    }
+
+    fn scalar(size: u64, niche: Option<u128>) -> Layout {
+        Layout {
+            size,
+            align: size,
+            fields: Vec::new(),
+            variants: Variants::Single,
+            largest_niche: niche.map(|available| Niche { offset: 0, size, available }),
+        }
+    }
+
+    fn zst() -> Layout {
+        Layout {
+            size: 0,
+            align: 1,
+            fields: Vec::new(),
+            variants: Variants::Single,
+            largest_niche: None,
+        }
+    }
+}
+
+pub(crate) fn layout_of_query(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    ty: Ty,
+) -> Result<Arc<Layout>, LayoutError> {
+    let _p = profile("layout_of_query");
+    let mut cx = LayoutCx { db, target: TargetDataLayout::for_crate(db, krate), stack: Vec::new() };
+    cx.layout_of_ty(&ty).map(Arc::new)
+}
+
+struct LayoutCx<'a> {
+    db: &'a dyn HirDatabase,
+    target: TargetDataLayout,
+    /// ADTs we are currently computing the layout of, to detect types
+    /// containing themselves.
+    stack: Vec<AdtId>,
+}
+
+impl LayoutCx<'_> {
+    fn layout_of_ty(&mut self, ty: &Ty) -> Result<Layout, LayoutError> {
+        let a_ty = match ty {
+            Ty::Apply(a_ty) => a_ty,
+            Ty::Dyn(_) => return Err(LayoutError::Unsized),
+            _ => return Err(LayoutError::NotConcrete),
+        };
+        let layout = match a_ty.ctor {
+            TypeCtor::Bool => Layout::scalar(1, Some(254)),
+            TypeCtor::Char => Layout::scalar(4, Some(u128::from(u32::max_value()) - 0x10FFFF)),
+            TypeCtor::Int(Uncertain::Known(int)) => {
+                Layout::scalar(self.target.int_size(int.bitness), None)
+            }
+            TypeCtor::Int(Uncertain::Unknown) => Layout::scalar(4, None),
+            TypeCtor::Float(Uncertain::Known(float)) => match float.bitness {
+                FloatBitness::X32 => Layout::scalar(4, None),
+                FloatBitness::X64 => Layout::scalar(8, None),
+            },
+            TypeCtor::Float(Uncertain::Unknown) => Layout::scalar(8, None),
+            TypeCtor::Str | TypeCtor::Slice => return Err(LayoutError::Unsized),
+            TypeCtor::Array => return Err(LayoutError::UnknownArrayLength),
+            TypeCtor::RawPtr(_) => self.pointer(a_ty.parameters.as_single(), None),
+            TypeCtor::Ref(_) => self.pointer(a_ty.parameters.as_single(), Some(1)),
+            TypeCtor::FnPtr { .. } => Layout::scalar(self.target.pointer_size, Some(1)),
+            TypeCtor::FnDef(_) | TypeCtor::Never => Layout::zst(),
+            TypeCtor::Tuple { .. } => {
+                let mut fields = Vec::with_capacity(a_ty.parameters.len());
+                for ty in a_ty.parameters.iter() {
+                    fields.push(self.layout_of_ty(ty)?);
+                }
+                univariant(&fields, &ReprOptions::default(), None)?
+            }
+            TypeCtor::Adt(adt) => self.layout_of_adt(adt, &a_ty.parameters, &[])?,
+            TypeCtor::AssociatedType(_)
+            | TypeCtor::Closure { .. }
+            | TypeCtor::AsyncBlock { .. }
+            | TypeCtor::Generator { .. } => return Err(LayoutError::NotConcrete),
+        };
+        Ok(layout)
+    }
+
+    /// Like `layout_of_ty`, but also looks at the written type to find out
+    /// array lengths, which our types don't track. The lengths are found in
+    /// arrays nested in tuples and in the type arguments of ADTs, too.
+    fn layout_of_field(&mut self, ty: &Ty, type_ref: &TypeRef) -> Result<Layout, LayoutError> {
+        let a_ty = match ty {
+            Ty::Apply(a_ty) => a_ty,
+            _ => return self.layout_of_ty(ty),
+        };
+        match (a_ty.ctor, type_ref) {
+            (TypeCtor::Array, TypeRef::Array(elem_ref, Some(len))) => {
+                let elem = self.layout_of_field(a_ty.parameters.as_single(), elem_ref)?;
+                Ok(Layout {
+                    size: elem.size.checked_mul(*len).ok_or(LayoutError::SizeOverflow)?,
+                    align: elem.align,
+                    fields: Vec::new(),
+                    variants: Variants::Single,
+                    largest_niche: if *len > 0 { elem.largest_niche } else { None },
+                })
+            }
+            (TypeCtor::Tuple { .. }, TypeRef::Tuple(elem_refs))
+                if elem_refs.len() == a_ty.parameters.len() =>
+            {
+                let mut fields = Vec::with_capacity(elem_refs.len());
+                for (ty, type_ref) in a_ty.parameters.iter().zip(elem_refs) {
+                    fields.push(self.layout_of_field(ty, type_ref)?);
+                }
+                univariant(&fields, &ReprOptions::default(), None)
+            }
+            (TypeCtor::Adt(adt), TypeRef::Path(path)) => {
+                let arg_refs: Vec<&TypeRef> = path
+                    .segments()
+                    .last()
+                    .and_then(|it| it.args_and_bindings)
+                    .map(|args| {
+                        args.args
+                            .iter()
+                            .map(|arg| match arg {
+                                GenericArg::Type(type_ref) => type_ref,
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                self.layout_of_adt(adt, &a_ty.parameters, &arg_refs)
+            }
+            _ => self.layout_of_ty(ty),
+        }
+    }
+
+    /// `arg_refs` are the written type arguments of the ADT, if any; they let
+    /// us find the lengths of arrays passed as type arguments.
+    fn layout_of_adt(
+        &mut self,
+        adt: AdtId,
+        substs: &Substs,
+        arg_refs: &[&TypeRef],
+    ) -> Result<Layout, LayoutError> {
+        if self.stack.contains(&adt) {
+            return Err(LayoutError::Recursive);
+        }
+        self.stack.push(adt);
+        let res = self.layout_of_adt_inner(adt, substs, arg_refs);
+        self.stack.pop();
+        res
+    }
+
+    fn layout_of_adt_inner(
+        &mut self,
+        adt: AdtId,
+        substs: &Substs,
+        arg_refs: &[&TypeRef],
+    ) -> Result<Layout, LayoutError> {
+        match adt {
+            AdtId::StructId(it) => {
+                let data = self.db.struct_data(it);
+                let fields =
+                    self.layout_of_variant(it.into(), &data.variant_data, substs, arg_refs)?;
+                let mut layout = univariant(&fields, &data.repr, None)?;
+                if let Some(start) = self.valid_range_start(adt) {
+                    layout.largest_niche =
+                        Some(Niche { offset: 0, size: layout.size, available: start });
+                }
+                Ok(layout)
+            }
+            AdtId::UnionId(it) => {
+                let data = self.db.union_data(it);
+                let fields =
+                    self.layout_of_variant(it.into(), &data.variant_data, substs, arg_refs)?;
+                union(&fields, &data.repr)
+            }
+            AdtId::EnumId(it) => {
+                let data = self.db.enum_data(it);
+                let mut variants = Vec::with_capacity(data.variants.len());
+                for (local_id, variant) in data.variants.iter() {
+                    let variant_id = EnumVariantId { parent: it, local_id };
+                    variants.push(self.layout_of_variant(
+                        variant_id.into(),
+                        &variant.variant_data,
+                        substs,
+                        arg_refs,
+                    )?);
+                }
+                enum_(&variants, &data.repr, &self.target)
+            }
+        }
+    }
+
+    fn layout_of_variant(
+        &mut self,
+        variant: VariantId,
+        variant_data: &VariantData,
+        substs: &Substs,
+        arg_refs: &[&TypeRef],
+    ) -> Result<Vec<Layout>, LayoutError> {
+        let field_types = self.db.field_types(variant);
+        let mut res = Vec::with_capacity(variant_data.fields().len());
+        for (id, field) in variant_data.fields().iter() {
+            // A field whose type is just a type parameter is written as the
+            // corresponding type argument.
+            let type_ref = match &field_types[id].value {
+                Ty::Bound(bound) if bound.debruijn == DebruijnIndex::INNERMOST => {
+                    arg_refs.get(bound.index).copied().unwrap_or(&field.type_ref)
+                }
+                _ => &field.type_ref,
+            };
+            let ty = field_types[id].clone().subst(substs);
+            res.push(self.layout_of_field(&ty, type_ref)?);
+        }
+        Ok(res)
+    }
+
+    fn pointer(&self, pointee: &Ty, niche: Option<u128>) -> Layout {
+        let mut layout = Layout::scalar(self.target.pointer_size, niche);
+        let is_fat = match pointee {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Str, .. })
+            | Ty::Apply(ApplicationTy { ctor: TypeCtor::Slice, .. })
+            | Ty::Dyn(_) => true,
+            _ => false,
+        };
+        if is_fat {
+            layout.size *= 2;
+        }
+        layout
+    }
+
+    /// `#[rustc_layout_scalar_valid_range_start(N)]`, which `core` uses for
+    /// `NonNull` and the `NonZero` integers, makes the values below `N` a niche.
+    fn valid_range_start(&self, adt: AdtId) -> Option<u128> {
+        let attrs = self.db.attrs(AttrDefId::from(adt));
+        let tt = attrs.by_key("rustc_layout_scalar_valid_range_start").tt_values().next()?;
+        match tt.token_trees.first()? {
+            TokenTree::Leaf(Leaf::Literal(lit)) => lit.text.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+fn univariant(
+    fields: &[Layout],
+    repr: &ReprOptions,
+    tag_size: Option<u64>,
+) -> Result<Layout, LayoutError> {
+    let field_align = |field: &Layout| match repr.packed {
+        Some(pack) => cmp::min(field.align, pack),
+        None => field.align,
+    };
+
+    let mut order: Vec<usize> = (0..fields.len()).collect();
+    let reorder = !repr.c && !repr.transparent && repr.packed.is_none() && repr.int.is_none();
+    if reorder {
+        if tag_size.is_some() {
+            // Put small fields first, so that they can share padding with the tag.
+            order.sort_by_key(|&idx| field_align(&fields[idx]));
+        } else {
+            order.sort_by_key(|&idx| {
+                let field = &fields[idx];
+                (field.size != 0, cmp::Reverse(field_align(field)))
+            });
+        }
+    }
+
+    let mut offset = tag_size.unwrap_or(0);
+    let mut align = tag_size.unwrap_or(1);
+    let mut largest_niche: Option<Niche> = None;
+    let mut field_layouts = vec![FieldLayout { offset: 0, size: 0, align: 1 }; fields.len()];
+    for idx in order {
+        let field = &fields[idx];
+        let field_align = field_align(field);
+        offset = align_to(offset, field_align)?;
+        align = cmp::max(align, field_align);
+        field_layouts[idx] = FieldLayout { offset, size: field.size, align: field_align };
+        let end = offset.checked_add(field.size).ok_or(LayoutError::SizeOverflow)?;
+        if let Some(niche) = field.largest_niche {
+            if largest_niche.map_or(true, |it| niche.available > it.available) {
+                largest_niche = Some(Niche { offset: offset + niche.offset, ..niche });
+            }
+        }
+        offset = end;
+    }
+    if let Some(repr_align) = repr.align {
+        align = cmp::max(align, repr_align);
+    }
+
+    Ok(Layout {
+        size: align_to(offset, align)?,
+        align,
+        fields: field_layouts,
+        variants: Variants::Single,
+        largest_niche,
+    })
+}
+
+fn union(fields: &[Layout], repr: &ReprOptions) -> Result<Layout, LayoutError> {
+    let mut size = 0;
+    let mut align = 1;
+    let mut field_layouts = Vec::with_capacity(fields.len());
+    for field in fields {
+        let field_align = repr.packed.map_or(field.align, |pack| cmp::min(field.align, pack));
+        size = cmp::max(size, field.size);
+        align = cmp::max(align, field_align);
+        field_layouts.push(FieldLayout { offset: 0, size: field.size, align: field_align });
+    }
+    if let Some(repr_align) = repr.align {
+        align = cmp::max(align, repr_align);
+    }
+    Ok(Layout {
+        size: align_to(size, align)?,
+        align,
+        fields: field_layouts,
+        variants: Variants::Single,
+        largest_niche: None,
+    })
+}
+
+fn enum_(
+    variants: &[Vec<Layout>],
+    repr: &ReprOptions,
+    target: &TargetDataLayout,
+) -> Result<Layout, LayoutError> {
+    if variants.is_empty() {
+        return Ok(Layout::zst());
+    }
+    let explicit_tag = repr.c || repr.int.is_some();
+
+    if !explicit_tag {
+        if variants.len() == 1 {
+            return univariant(&variants[0], repr, None);
+        }
+
+        let variant_layouts = variants
+            .iter()
+            .map(|fields| univariant(fields, repr, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut dataful = variant_layouts.iter().enumerate().filter(|(_, it)| it.size != 0);
+        if let (Some((dataful_variant, layout)), None) = (dataful.next(), dataful.next()) {
+            let needed = (variants.len() - 1) as u128;
+            if let Some(niche) = layout.largest_niche.filter(|it| it.available >= needed) {
+                let (size, align) = (layout.size, layout.align);
+                return Ok(Layout {
+                    size,
+                    align,
+                    fields: Vec::new(),
+                    largest_niche: Some(Niche { available: niche.available - needed, ..niche }),
+                    variants: Variants::NicheFilling {
+                        dataful_variant,
+                        niche,
+                        variants: variant_layouts,
+                    },
+                });
+            }
+        }
+    }
+
+    let tag_size = match repr.int {
+        Some(int) => target.int_size(int.bitness),
+        // `c_int`
+        None if repr.c => 4,
+        None if variants.len() <= 1 << 8 => 1,
+        None if variants.len() <= 1 << 16 => 2,
+        None => 4,
+    };
+
+    let variant_layouts = if repr.c {
+        // `#[repr(C)]` enums are a tag followed by a union of the variants.
+        let variant_structs = variants
+            .iter()
+            .map(|fields| univariant(fields, repr, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let union_align = variant_structs.iter().map(|it| it.align).max().unwrap_or(1);
+        let union_offset = align_to(tag_size, union_align)?;
+        variant_structs
+            .into_iter()
+            .map(|mut layout| {
+                for field in layout.fields.iter_mut() {
+                    field.offset += union_offset;
+                }
+                layout.size =
+                    layout.size.checked_add(union_offset).ok_or(LayoutError::SizeOverflow)?;
+                layout.align = cmp::max(layout.align, tag_size);
+                Ok(layout)
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        variants
+            .iter()
+            .map(|fields| univariant(fields, repr, Some(tag_size)))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let align = variant_layouts.iter().map(|it| it.align).fold(tag_size, cmp::max);
+    let size = variant_layouts.iter().map(|it| it.size).fold(tag_size, cmp::max);
+    let max_tag = u128::max_value() >> (128 - 8 * tag_size);
+    Ok(Layout {
+        size: align_to(size, align)?,
+        align,
+        fields: Vec::new(),
+        largest_niche: Some(Niche {
+            offset: 0,
+            size: tag_size,
+            available: max_tag - (variants.len() as u128 - 1),
+        }),
+        variants: Variants::Tagged { tag_size, variants: variant_layouts },
+    })
+}
+
+fn align_to(offset: u64, align: u64) -> Result<u64, LayoutError> {
+    let padded = offset.checked_add(align - 1).ok_or(LayoutError::SizeOverflow)?;
+    Ok(padded / align * align)
+}
@@ -37,6 +37,9 @@ pub(crate) mod utils;
 pub mod db;
 pub mod diagnostics;
 pub mod expr;
+pub mod coherence;
+pub mod layout;
+pub mod object_safety;
 
 #[cfg(test)]
 mod tests;
@@ -51,7 +54,7 @@ use std::{iter, mem};
 
 use hir_def::{
     expr::ExprId, type_ref::Mutability, AdtId, AssocContainerId, DefWithBodyId, GenericDefId,
-    HasModule, Lookup, TraitId, TypeAliasId, TypeParamId,
+    FunctionId, HasModule, Lookup, TraitId, TypeAliasId, TypeParamId,
 };
 use ra_db::{impl_intern_key, salsa, CrateId};
 
@@ -63,13 +66,14 @@ use crate::{
 use display::HirDisplay;
 
 pub use autoderef::autoderef;
-pub use infer::{InferTy, InferenceResult};
+pub use infer::{unify, BindingMode, InferTy, InferenceResult};
 pub use lower::CallableDef;
 pub use lower::{
     associated_type_shorthand_candidates, callable_item_sig, ImplTraitLoweringMode, TyDefId,
     TyLoweringContext, ValueTyDefId,
 };
 pub use traits::{InEnvironment, Obligation, ProjectionPredicate, TraitEnvironment};
+pub use utils::direct_super_traits;
 
 pub use chalk_ir::{BoundVar, DebruijnIndex};
 
@@ -151,6 +155,18 @@ pub enum TypeCtor {
     /// The closure signature is stored in a `FnPtr` type in the first type
     /// parameter.
     Closure { def: DefWithBodyId, expr: ExprId },
+
+    /// The type of a specific `async` block, or of the body of an `async fn`.
+    ///
+    /// The type the block evaluates to is stored in the first type parameter;
+    /// the block implements `Future` with that `Output`.
+    AsyncBlock { def: DefWithBodyId, expr: ExprId },
+
+    /// The type of a specific generator, i.e. a closure containing `yield`.
+    ///
+    /// The resume, yield and return types are stored in the three type
+    /// parameters, in this order.
+    Generator { def: DefWithBodyId, expr: ExprId },
 }
 
 /// This exists just for Chalk, because Chalk just has a single `StructId` where
@@ -174,7 +190,9 @@ impl TypeCtor {
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::Closure { .. } // 1 param representing the signature of the closure
+            | TypeCtor::AsyncBlock { .. } // 1 param representing the output of the block
             => 1,
+            TypeCtor::Generator { .. } => 3,
             TypeCtor::Adt(adt) => {
                 let generic_params = generics(db.upcast(), adt.into());
                 generic_params.len()
@@ -207,7 +225,9 @@ impl TypeCtor {
             | TypeCtor::FnPtr { .. }
             | TypeCtor::Tuple { .. } => None,
             // Closure's krate is irrelevant for coherence I would think?
-            TypeCtor::Closure { .. } => None,
+            TypeCtor::Closure { .. }
+            | TypeCtor::AsyncBlock { .. }
+            | TypeCtor::Generator { .. } => None,
             TypeCtor::Adt(adt) => Some(adt.module(db.upcast()).krate),
             TypeCtor::FnDef(callable) => Some(callable.krate(db)),
             TypeCtor::AssociatedType(type_alias) => {
@@ -230,7 +250,9 @@ impl TypeCtor {
             | TypeCtor::Ref(_)
             | TypeCtor::FnPtr { .. }
             | TypeCtor::Tuple { .. }
-            | TypeCtor::Closure { .. } => None,
+            | TypeCtor::Closure { .. }
+            | TypeCtor::AsyncBlock { .. }
+            | TypeCtor::Generator { .. } => None,
             TypeCtor::Adt(adt) => Some(adt.into()),
             TypeCtor::FnDef(callable) => Some(callable.into()),
             TypeCtor::AssociatedType(type_alias) => Some(type_alias.into()),
@@ -258,7 +280,18 @@ pub struct ProjectionTy {
 
 impl ProjectionTy {
     pub fn trait_ref(&self, db: &dyn HirDatabase) -> TraitRef {
-        TraitRef { trait_: self.trait_(db), substs: self.parameters.clone() }
+        let trait_ = self.trait_(db);
+        TraitRef {
+            trait_,
+            substs: self.parameters.prefix(generics(db.upcast(), trait_.into()).len()),
+        }
+    }
+
+    /// The parameters of a generic associated type itself, i.e. `T` in
+    /// `<Self as Trait>::Assoc<T>`.
+    pub fn own_parameters(&self, db: &dyn HirDatabase) -> &[Ty] {
+        let num_trait_params = generics(db.upcast(), self.trait_(db).into()).len();
+        &self.parameters[num_trait_params.min(self.parameters.len())..]
     }
 
     fn trait_(&self, db: &dyn HirDatabase) -> TraitId {
@@ -283,6 +316,75 @@ impl TypeWalk for ProjectionTy {
     }
 }
 
+/// Identifies a return-position `impl Trait` by the function whose return type
+/// it appears in, and its index among the `impl Trait`s of that return type.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct OpaqueTyId {
+    pub func: FunctionId,
+    pub idx: u16,
+}
+
+/// An opaque type (`impl Trait`).
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct OpaqueTy {
+    /// `None` while the return type of the function is still being lowered.
+    pub id: Option<OpaqueTyId>,
+    /// The arguments for the generic parameters of the function.
+    pub parameters: Substs,
+    /// The predicates are quantified over the `Self` type; see `Ty::Dyn` for
+    /// more.
+    pub predicates: Arc<[GenericPredicate]>,
+}
+
+impl OpaqueTy {
+    /// Returns the concrete type hidden behind this `impl Trait`, as inferred
+    /// from the body of the function returning it.
+    pub fn hidden_ty(&self, db: &dyn HirDatabase) -> Option<Ty> {
+        let id = self.id?;
+        let infer = db.infer(id.func.into());
+        let hidden = infer.hidden_type(id.idx as usize)?;
+        if let Ty::Unknown = hidden {
+            return None;
+        }
+        // The hidden type is inferred in terms of the function's own type
+        // parameters, so replace those by the arguments.
+        let generics = generics(db.upcast(), id.func.into());
+        let ty = hidden.clone().fold_binders(
+            &mut |ty, binders| match ty {
+                Ty::Placeholder(param) => match generics.param_idx(param) {
+                    Some(idx) if idx < self.parameters.len() => {
+                        self.parameters[idx].clone().shift_bound_vars(binders)
+                    }
+                    _ => Ty::Placeholder(param),
+                },
+                ty => ty,
+            },
+            DebruijnIndex::INNERMOST,
+        );
+        Some(ty)
+    }
+}
+
+impl TypeWalk for OpaqueTy {
+    fn walk(&self, f: &mut impl FnMut(&Ty)) {
+        self.parameters.walk(f);
+        for p in self.predicates.iter() {
+            p.walk(f);
+        }
+    }
+
+    fn walk_mut_binders(
+        &mut self,
+        f: &mut impl FnMut(&mut Ty, DebruijnIndex),
+        binders: DebruijnIndex,
+    ) {
+        self.parameters.walk_mut_binders(f, binders);
+        for p in make_mut_slice(&mut self.predicates) {
+            p.walk_mut_binders(f, binders.shifted_in());
+        }
+    }
+}
+
 /// A type.
 ///
 /// See also the `TyKind` enum in rustc (librustc/ty/sty.rs), which represents
@@ -326,10 +428,7 @@ pub enum Ty {
     Dyn(Arc<[GenericPredicate]>),
 
     /// An opaque type (`impl Trait`).
-    ///
-    /// The predicates are quantified over the `Self` type; see `Ty::Dyn` for
-    /// more.
-    Opaque(Arc<[GenericPredicate]>),
+    Opaque(OpaqueTy),
 
     /// A placeholder for a type which could not be computed; this is propagated
     /// to avoid useless error messages. Doubles as a placeholder where type
@@ -796,7 +895,7 @@ impl Ty {
     /// If this is an `impl Trait` or `dyn Trait`, returns that trait.
     pub fn inherent_trait(&self) -> Option<TraitId> {
         match self {
-            Ty::Dyn(predicates) | Ty::Opaque(predicates) => {
+            Ty::Dyn(predicates) | Ty::Opaque(OpaqueTy { predicates, .. }) => {
                 predicates.iter().find_map(|pred| match pred {
                     GenericPredicate::Implemented(tr) => Some(tr.trait_),
                     _ => None,
@@ -805,6 +904,32 @@ impl Ty {
             _ => None,
         }
     }
+
+    /// Replaces each return-position `impl Trait` by the concrete type it
+    /// hides, where that is known.
+    pub fn reveal_opaque_types(self, db: &dyn HirDatabase) -> Ty {
+        // Hidden types may themselves contain `impl Trait`s, possibly even
+        // recursively, so we only look through a few layers.
+        self.reveal_opaque_types_to_depth(db, 8)
+    }
+
+    fn reveal_opaque_types_to_depth(self, db: &dyn HirDatabase, depth: usize) -> Ty {
+        if depth == 0 {
+            return self;
+        }
+        self.fold_binders(
+            &mut |ty, binders| match ty {
+                Ty::Opaque(o_ty) => match o_ty.hidden_ty(db) {
+                    Some(hidden) => hidden
+                        .reveal_opaque_types_to_depth(db, depth - 1)
+                        .shift_bound_vars(binders),
+                    None => Ty::Opaque(o_ty),
+                },
+                ty => ty,
+            },
+            DebruijnIndex::INNERMOST,
+        )
+    }
 }
 
 /// This allows walking structures that contain types to do something with those
@@ -915,11 +1040,14 @@ impl TypeWalk for Ty {
                     t.walk(f);
                 }
             }
-            Ty::Dyn(predicates) | Ty::Opaque(predicates) => {
+            Ty::Dyn(predicates) => {
                 for p in predicates.iter() {
                     p.walk(f);
                 }
             }
+            Ty::Opaque(o_ty) => {
+                o_ty.walk(f);
+            }
             Ty::Placeholder { .. } | Ty::Bound(_) | Ty::Infer(_) | Ty::Unknown => {}
         }
         f(self);
@@ -937,11 +1065,14 @@ impl TypeWalk for Ty {
             Ty::Projection(p_ty) => {
                 p_ty.parameters.walk_mut_binders(f, binders);
             }
-            Ty::Dyn(predicates) | Ty::Opaque(predicates) => {
+            Ty::Dyn(predicates) => {
                 for p in make_mut_slice(predicates) {
                     p.walk_mut_binders(f, binders.shifted_in());
                 }
             }
+            Ty::Opaque(o_ty) => {
+                o_ty.walk_mut_binders(f, binders);
+            }
             Ty::Placeholder { .. } | Ty::Bound(_) | Ty::Infer(_) | Ty::Unknown => {}
         }
         f(self, binders);
@@ -29,10 +29,10 @@ use crate::{
     primitive::{FloatTy, IntTy},
     utils::{
         all_super_trait_refs, associated_type_by_name_including_super_traits, generics,
-        make_mut_slice, variant_data,
+        make_mut_slice, variant_data, Generics,
     },
-    Binders, BoundVar, DebruijnIndex, FnSig, GenericPredicate, PolyFnSig, ProjectionPredicate,
-    ProjectionTy, Substs, TraitEnvironment, TraitRef, Ty, TypeCtor, TypeWalk,
+    Binders, BoundVar, DebruijnIndex, FnSig, GenericPredicate, OpaqueTy, OpaqueTyId, PolyFnSig,
+    ProjectionPredicate, ProjectionTy, Substs, TraitEnvironment, TraitRef, Ty, TypeCtor, TypeWalk,
 };
 use hir_expand::name::Name;
 
@@ -137,7 +137,7 @@ impl Ty {
                 let inner_ty = Ty::from_hir(ctx, inner);
                 Ty::apply_one(TypeCtor::RawPtr(*mutability), inner_ty)
             }
-            TypeRef::Array(inner) => {
+            TypeRef::Array(inner, _) => {
                 let inner_ty = Ty::from_hir(ctx, inner);
                 Ty::apply_one(TypeCtor::Array, inner_ty)
             }
@@ -176,7 +176,9 @@ impl Ty {
                                 })
                                 .collect()
                         });
-                        Ty::Opaque(predicates)
+                        // The id is assigned once the whole return type is
+                        // lowered, see `assign_opaque_ty_ids`.
+                        Ty::Opaque(OpaqueTy { id: None, parameters: Substs::empty(), predicates })
                     }
                     ImplTraitLoweringMode::Param => {
                         let idx = ctx.impl_trait_counter.get();
@@ -290,13 +292,12 @@ impl Ty {
                         &segment.name,
                     );
                     match associated_ty {
-                        Some(associated_ty) => {
-                            // FIXME handle type parameters on the segment
-                            Ty::Projection(ProjectionTy {
-                                associated_ty,
-                                parameters: trait_ref.substs,
-                            })
-                        }
+                        Some(associated_ty) => Ty::Projection(ProjectionTy::from_segment(
+                            ctx,
+                            segment,
+                            associated_ty,
+                            trait_ref.substs,
+                        )),
                         None => {
                             // FIXME: report error (associated type not found)
                             Ty::Unknown
@@ -402,11 +403,12 @@ impl Ty {
                             }
                             TypeParamLoweringMode::Variable => t.substs.clone(),
                         };
-                        // FIXME handle type parameters on the segment
-                        return Some(Ty::Projection(ProjectionTy {
+                        return Some(Ty::Projection(ProjectionTy::from_segment(
+                            ctx,
+                            segment,
                             associated_ty,
-                            parameters: substs,
-                        }));
+                            substs,
+                        )));
                     }
 
                     None
@@ -518,6 +520,25 @@ pub(super) fn substs_from_path_segment(
     Substs(substs.into())
 }
 
+impl ProjectionTy {
+    /// Lowers `Assoc<Args>` in `<T as Trait>::Assoc<Args>`, given the
+    /// substitutions for the trait.
+    fn from_segment(
+        ctx: &TyLoweringContext<'_>,
+        segment: PathSegment<'_>,
+        associated_ty: TypeAliasId,
+        trait_substs: Substs,
+    ) -> ProjectionTy {
+        let own_substs = substs_from_path_segment(ctx, segment, Some(associated_ty.into()), false);
+        let parameters = trait_substs
+            .iter()
+            .cloned()
+            .chain(own_substs.iter().skip(trait_substs.len()).cloned())
+            .collect();
+        ProjectionTy { associated_ty, parameters: Substs(parameters) }
+    }
+}
+
 impl TraitRef {
     fn from_path(
         ctx: &TyLoweringContext<'_>,
@@ -867,10 +888,29 @@ fn fn_sig_for_fn(db: &dyn HirDatabase, def: FunctionId) -> PolyFnSig {
     let ctx_ret = ctx_params.with_impl_trait_mode(ImplTraitLoweringMode::Opaque);
     let ret = Ty::from_hir(&ctx_ret, &data.ret_type);
     let generics = generics(db.upcast(), def.into());
+    let ret = assign_opaque_ty_ids(ret, def, &generics);
     let num_binders = generics.len();
     Binders::new(num_binders, FnSig::from_params_and_return(params, ret))
 }
 
+/// Numbers the `impl Trait`s in the return type of `func`, in the same order in
+/// which inference records their hidden types.
+fn assign_opaque_ty_ids(ret: Ty, func: FunctionId, generics: &Generics) -> Ty {
+    let mut idx = 0;
+    ret.fold_binders(
+        &mut |ty, binders| match ty {
+            Ty::Opaque(o_ty) => {
+                let id = OpaqueTyId { func, idx };
+                idx += 1;
+                let parameters = Substs::bound_vars(generics, binders);
+                Ty::Opaque(OpaqueTy { id: Some(id), parameters, ..o_ty })
+            }
+            ty => ty,
+        },
+        DebruijnIndex::INNERMOST,
+    )
+}
+
 /// Build the declared type of a function. This should not need to look at the
 /// function body.
 fn type_for_fn(db: &dyn HirDatabase, def: FunctionId) -> Binders<Ty> {
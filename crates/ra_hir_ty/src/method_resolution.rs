@@ -19,6 +19,7 @@ use crate::{
     autoderef,
     db::HirDatabase,
     primitive::{FloatBitness, Uncertain},
+    traits::trait_solve_runs_out_of_fuel,
     utils::all_super_traits,
     ApplicationTy, Canonical, DebruijnIndex, InEnvironment, TraitEnvironment, TraitRef, Ty,
     TypeCtor, TypeWalk,
@@ -132,7 +133,7 @@ impl Ty {
         // The corresponding impls are marked with lang items, so we can use them to find the required crates.
         macro_rules! lang_item_crate {
             ($($name:expr),+ $(,)?) => {{
-                let mut v = ArrayVec::<[LangItemTarget; 2]>::new();
+                let mut v = ArrayVec::<[LangItemTarget; 4]>::new();
                 $(
                     v.extend(db.lang_item(cur_crate, $name.into()));
                 )+
@@ -154,21 +155,34 @@ impl Ty {
                 },
                 TypeCtor::Int(Uncertain::Known(i)) => lang_item_crate!(i.ty_to_string()),
                 TypeCtor::Str => lang_item_crate!("str_alloc", "str"),
-                TypeCtor::Slice => lang_item_crate!("slice_alloc", "slice"),
-                TypeCtor::RawPtr(Mutability::Shared) => lang_item_crate!("const_ptr"),
-                TypeCtor::RawPtr(Mutability::Mut) => lang_item_crate!("mut_ptr"),
+                // `[u8]` has some extra methods in impls of their own
+                TypeCtor::Slice => {
+                    lang_item_crate!("slice_alloc", "slice", "slice_u8_alloc", "slice_u8")
+                }
+                TypeCtor::Array => lang_item_crate!("array"),
+                // `*const [T]` and `*mut [T]` as well
+                TypeCtor::RawPtr(Mutability::Shared) => {
+                    lang_item_crate!("const_ptr", "const_slice_ptr")
+                }
+                TypeCtor::RawPtr(Mutability::Mut) => lang_item_crate!("mut_ptr", "mut_slice_ptr"),
                 _ => return None,
             },
             _ => return None,
         };
-        let res = lang_item_targets
+        let mut res = ArrayVec::new();
+        let crates = lang_item_targets
             .into_iter()
             .filter_map(|it| match it {
                 LangItemTarget::ImplDefId(it) => Some(it),
                 _ => None,
             })
-            .map(|it| it.lookup(db.upcast()).container.module(db.upcast()).krate)
-            .collect();
+            .map(|it| it.lookup(db.upcast()).container.module(db.upcast()).krate);
+        for krate in crates {
+            // several of the lang items usually live in the same crate
+            if !res.contains(&krate) && res.try_push(krate).is_err() {
+                break;
+            }
+        }
         Some(res)
     }
 }
@@ -197,6 +211,46 @@ pub(crate) fn lookup_method(
     )
 }
 
+/// Checks whether the trait solver runs out of fuel on a trait in scope which
+/// has a method called `name`, for the receiver or one of its autoderef or
+/// autoref steps. If so, `lookup_method` not finding the method doesn't mean
+/// that there is none.
+pub(crate) fn method_lookup_runs_out_of_fuel(
+    ty: &Canonical<Ty>,
+    db: &dyn HirDatabase,
+    env: Arc<TraitEnvironment>,
+    krate: CrateId,
+    traits_in_scope: &FxHashSet<TraitId>,
+    name: &Name,
+) -> bool {
+    let traits: Vec<TraitId> = traits_in_scope
+        .iter()
+        .copied()
+        .filter(|&t| db.trait_data(t).items.iter().any(|(item_name, _)| item_name == name))
+        .collect();
+    if traits.is_empty() {
+        return false;
+    }
+    let ty = InEnvironment { value: ty.clone(), environment: env.clone() };
+    let deref_chain = autoderef_method_receiver(db, krate, ty);
+    let self_tys = deref_chain.iter().flat_map(|self_ty| {
+        let autoref = |mutability| Canonical {
+            num_vars: self_ty.num_vars,
+            value: Ty::apply_one(TypeCtor::Ref(mutability), self_ty.value.clone()),
+        };
+        vec![self_ty.clone(), autoref(Mutability::Shared), autoref(Mutability::Mut)]
+    });
+    for self_ty in self_tys {
+        for &t in traits.iter() {
+            let goal = generic_implements_goal(db, env.clone(), t, self_ty.clone());
+            if trait_solve_runs_out_of_fuel(db, krate, goal) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
 /// Whether we're looking up a dotted method call (like `v.len()`) or a path
 /// (like `Vec::new`).
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
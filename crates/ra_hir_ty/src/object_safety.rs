@@ -0,0 +1,179 @@
+//! Checks whether a trait is object safe, i.e. whether `dyn Trait` is a valid
+//! type.
+//!
+//! The rules follow rustc's `object_safety` module: a trait is object safe if
+//! neither it nor any of its supertraits
+//!
+//! * requires `Self: Sized`,
+//! * mentions `Self` in a supertrait bound, like `trait Foo: PartialEq<Self>`,
+//! * has associated consts, or
+//! * has methods that can't be called through a vtable, unless those methods
+//!   opt out with `where Self: Sized`.
+//!
+//! We don't check that method receivers are dispatchable (e.g. `self: Rc<Self>`
+//! is fine, but `self: Vec<Self>` isn't), since that requires the unstable
+//! `DispatchFromDyn` machinery.
+
+use std::sync::Arc;
+
+use hir_def::{
+    lang_item::LangItemTarget, AssocItemId, ConstId, FunctionId, HasModule, Lookup, TraitId,
+    TypeParamId,
+};
+use ra_prof::profile;
+
+use crate::{
+    db::HirDatabase,
+    utils::{all_super_traits, generics},
+    CallableDef, GenericPredicate, Substs, Ty, TypeWalk,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectSafetyViolation {
+    /// The given trait (the checked one or a supertrait) requires
+    /// `Self: Sized`.
+    SizedSelf(TraitId),
+    /// A supertrait bound of the given trait mentions `Self`, like
+    /// `trait Foo: PartialEq<Self>`.
+    SupertraitSelf(TraitId),
+    Method(FunctionId, MethodViolationCode),
+    AssocConst(ConstId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodViolationCode {
+    /// The method has no `self` parameter, like `fn new() -> Self`.
+    StaticMethod,
+    /// `Self` appears in the type of a parameter other than the receiver.
+    ReferencesSelfInput,
+    /// `Self` appears in the return type.
+    ReferencesSelfOutput,
+    /// The method has type parameters.
+    Generic,
+}
+
+pub(crate) fn object_safety_query(
+    db: &dyn HirDatabase,
+    trait_: TraitId,
+) -> Arc<[ObjectSafetyViolation]> {
+    let _p = profile("object_safety_query");
+    let krate = trait_.lookup(db.upcast()).container.module(db.upcast()).krate;
+    let sized_trait = match db.lang_item(krate, "sized".into()) {
+        Some(LangItemTarget::TraitId(it)) => Some(it),
+        _ => None,
+    };
+
+    let mut res = Vec::new();
+    for trait_ in all_super_traits(db.upcast(), trait_) {
+        let self_param = match trait_self_param(db, trait_) {
+            Some(it) => it,
+            None => continue,
+        };
+        let substs = Substs::type_params(db, trait_);
+        let mut requires_sized = false;
+        let mut supertrait_mentions_self = false;
+        for pred in db.generic_predicates_for_param(self_param).iter() {
+            match pred.clone().subst(&substs) {
+                GenericPredicate::Implemented(tr) => {
+                    if Some(tr.trait_) == sized_trait {
+                        requires_sized = true;
+                    }
+                    if tr.substs.iter().skip(1).any(|ty| references_self(ty, self_param)) {
+                        supertrait_mentions_self = true;
+                    }
+                }
+                GenericPredicate::Projection(proj) => {
+                    if references_self(&proj.ty, self_param) {
+                        supertrait_mentions_self = true;
+                    }
+                }
+                GenericPredicate::Error => {}
+            }
+        }
+        if requires_sized {
+            res.push(ObjectSafetyViolation::SizedSelf(trait_));
+        }
+        if supertrait_mentions_self {
+            res.push(ObjectSafetyViolation::SupertraitSelf(trait_));
+        }
+
+        for (_, item) in db.trait_data(trait_).items.iter() {
+            match *item {
+                AssocItemId::FunctionId(it) => {
+                    if let Some(code) = method_violation(db, it, self_param, sized_trait) {
+                        res.push(ObjectSafetyViolation::Method(it, code));
+                    }
+                }
+                AssocItemId::ConstId(it) => res.push(ObjectSafetyViolation::AssocConst(it)),
+                AssocItemId::TypeAliasId(_) => {}
+            }
+        }
+    }
+    res.into()
+}
+
+fn method_violation(
+    db: &dyn HirDatabase,
+    func: FunctionId,
+    self_param: TypeParamId,
+    sized_trait: Option<TraitId>,
+) -> Option<MethodViolationCode> {
+    let substs = Substs::type_params(db, func);
+    let self_ty = Ty::Placeholder(self_param);
+    let opts_out =
+        db.generic_predicates(func.into()).iter().any(|pred| match pred.clone().subst(&substs) {
+            GenericPredicate::Implemented(tr) => {
+                Some(tr.trait_) == sized_trait && tr.self_ty() == &self_ty
+            }
+            _ => false,
+        });
+    if opts_out {
+        return None;
+    }
+
+    let data = db.function_data(func);
+    if !data.has_self_param {
+        return Some(MethodViolationCode::StaticMethod);
+    }
+    let sig = db.callable_item_signature(CallableDef::FunctionId(func)).subst(&substs);
+    if sig.params().iter().skip(1).any(|ty| references_self(ty, self_param)) {
+        return Some(MethodViolationCode::ReferencesSelfInput);
+    }
+    if references_self(sig.ret(), self_param) {
+        return Some(MethodViolationCode::ReferencesSelfOutput);
+    }
+    let (_parent, _self_params, list_params, impl_trait_params) =
+        generics(db.upcast(), func.into()).provenance_split();
+    if list_params + impl_trait_params > 0 {
+        return Some(MethodViolationCode::Generic);
+    }
+    None
+}
+
+fn trait_self_param(db: &dyn HirDatabase, trait_: TraitId) -> Option<TypeParamId> {
+    let local_id = db.generic_params(trait_.into()).find_trait_self_param()?;
+    Some(TypeParamId { parent: trait_.into(), local_id })
+}
+
+fn references_self(ty: &Ty, self_param: TypeParamId) -> bool {
+    match ty {
+        Ty::Placeholder(it) => *it == self_param,
+        Ty::Apply(a_ty) => a_ty.parameters.iter().any(|ty| references_self(ty, self_param)),
+        // Associated types of `Self` are fine, since the object type specifies
+        // them, as in `dyn Iterator<Item = u32>`.
+        Ty::Projection(proj) => {
+            proj.parameters.iter().skip(1).any(|ty| references_self(ty, self_param))
+        }
+        Ty::Dyn(preds) => preds.iter().any(|pred| {
+            let mut res = false;
+            pred.walk(&mut |ty| res |= *ty == Ty::Placeholder(self_param));
+            res
+        }),
+        Ty::Opaque(o_ty) => {
+            let mut res = false;
+            o_ty.walk(&mut |ty| res |= *ty == Ty::Placeholder(self_param));
+            res
+        }
+        Ty::Bound(_) | Ty::Infer(_) | Ty::Unknown => false,
+    }
+}
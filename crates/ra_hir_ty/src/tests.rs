@@ -6,6 +6,9 @@ mod patterns;
 mod traits;
 mod method_resolution;
 mod macros;
+mod layout;
+mod object_safety;
+mod coherence;
 
 use std::sync::Arc;
 
@@ -29,7 +32,8 @@ use ra_syntax::{
 use stdx::format_to;
 
 use crate::{
-    db::HirDatabase, display::HirDisplay, infer::TypeMismatch, test_db::TestDB, InferenceResult, Ty,
+    db::HirDatabase, display::HirDisplay, infer::TypeMismatch, test_db::TestDB,
+    traits::TraitSolverConfig, InferenceResult, Ty,
 };
 
 // These tests compare the inference results for all expressions in a file
@@ -37,6 +41,10 @@ use crate::{
 // update the snapshots.
 
 fn type_at_pos(db: &TestDB, pos: FilePosition) -> String {
+    ty_at_pos(db, pos).display(db).to_string()
+}
+
+fn ty_at_pos(db: &TestDB, pos: FilePosition) -> Ty {
     let file = db.parse(pos.file_id).ok().unwrap();
     let expr = algo::find_node_at_offset::<ast::Expr>(file.syntax(), pos.offset).unwrap();
     let fn_def = expr.syntax().ancestors().find_map(ast::FnDef::cast).unwrap();
@@ -48,8 +56,7 @@ fn type_at_pos(db: &TestDB, pos: FilePosition) -> String {
     let (_body, source_map) = db.body_with_source_map(func.into());
     if let Some(expr_id) = source_map.node_expr(InFile::new(pos.file_id.into(), &expr)) {
         let infer = db.infer(func.into());
-        let ty = &infer[expr_id];
-        return ty.display(db).to_string();
+        return infer[expr_id].clone();
     }
     panic!("Can't find expression")
 }
@@ -59,6 +66,31 @@ fn type_at(content: &str) -> String {
     type_at_pos(&db, file_pos)
 }
 
+fn revealed_type_at(content: &str) -> String {
+    let (db, file_pos) = TestDB::with_position(content);
+    ty_at_pos(&db, file_pos).reveal_opaque_types(&db).display(&db).to_string()
+}
+
+/// The expected types of the typed holes in the functions of the file.
+fn typed_hole_types(ra_fixture: &str) -> Vec<String> {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module = db.module_for_file(file_id);
+    let crate_def_map = db.crate_def_map(module.krate);
+    let mut res = Vec::new();
+    for decl in crate_def_map[module.local_id].scope.declarations() {
+        if let ModuleDefId::FunctionId(func) = decl {
+            let body = db.body(func.into());
+            let infer = db.infer(func.into());
+            for (expr, _) in body.exprs.iter() {
+                if let Some(ty) = infer.expected_type_of_hole(expr) {
+                    res.push(ty.display(&db).to_string());
+                }
+            }
+        }
+    }
+    res
+}
+
 fn infer(ra_fixture: &str) -> String {
     infer_with_mismatches(ra_fixture, false)
 }
@@ -86,7 +118,10 @@ fn infer_with_mismatches(content: &str, include_mismatches: bool) -> String {
                 }
                 Err(SyntheticSyntax) => continue,
             };
-            types.push((syntax_ptr, ty));
+            types.push((syntax_ptr.clone(), ty));
+            if let Some(mismatch) = inference_result.type_mismatch_for_pat(pat) {
+                mismatches.push((syntax_ptr, mismatch));
+            }
         }
 
         for (expr, ty) in inference_result.type_of_expr.iter() {
@@ -518,3 +553,93 @@ fn missing_record_pat_field_no_diagnostic_if_not_exhaustive() {
 
     assert_snapshot!(diagnostics, @"");
 }
+
+#[test]
+fn unresolved_method_call_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r"
+        //- /lib.rs
+        struct S;
+        mod m {
+            pub trait Foo { fn foo(&self); }
+            impl Foo for super::S { fn foo(&self) {} }
+        }
+        fn test(x: S) {
+            x.foo();
+            x.bar();
+            unknown.foo();
+        }
+        ",
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "x.foo()": no method named `foo` found for type `S`
+    "x.bar()": no method named `bar` found for type `S`
+    "###
+    );
+}
+
+#[test]
+fn no_unresolved_method_call_diagnostic_for_projection_receiver() {
+    let diagnostics = TestDB::with_files(
+        r"
+        //- /lib.rs
+        trait Tr { type Assoc; }
+        fn test<T: Tr>(x: T::Assoc) {
+            x.foo();
+        }
+        ",
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @"");
+}
+
+#[test]
+fn no_unresolved_method_call_diagnostic_for_opaque_receiver() {
+    let diagnostics = TestDB::with_files(
+        r"
+        //- /lib.rs
+        trait Tr {}
+        struct S;
+        impl Tr for S {}
+        fn make() -> impl Tr { S }
+        fn test() {
+            make().foo();
+        }
+        ",
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @"");
+}
+
+#[test]
+fn no_unresolved_method_call_diagnostic_when_out_of_fuel() {
+    let fixture = r"
+        //- /lib.rs
+        mod m {
+            pub trait Foo { fn foo(&self); }
+            pub trait Bar {}
+            impl<T: Bar> Foo for T { fn foo(&self) {} }
+        }
+        use m::Foo;
+        struct S;
+        fn test(x: S) {
+            x.foo();
+        }
+        ";
+    let diagnostics = TestDB::with_files(fixture).diagnostics().0;
+    assert_snapshot!(diagnostics, @r###"
+    "x.foo()": no method named `foo` found for type `S`
+    "###
+    );
+
+    let mut db = TestDB::with_files(fixture);
+    db.set_trait_solver_config(TraitSolverConfig { fuel: 0, ..Default::default() });
+    assert_snapshot!(db.diagnostics().0, @"");
+}
@@ -0,0 +1,157 @@
+use hir_def::db::DefDatabase;
+use ra_db::fixture::WithFixture;
+
+use crate::{coherence::CoherenceError, db::HirDatabase, display::HirDisplay, test_db::TestDB};
+
+fn check_coherence(ra_fixture: &str, expected: &[&str]) {
+    let (db, pos) = TestDB::with_position(ra_fixture);
+    let krate = db.module_for_file(pos.file_id).krate;
+    let describe = |impl_id| {
+        let trait_ = db.impl_trait(impl_id).unwrap().value.trait_;
+        format!(
+            "impl {} for {}",
+            db.trait_data(trait_).name,
+            db.impl_self_ty(impl_id).value.display(&db)
+        )
+    };
+    let actual: Vec<String> = db
+        .coherence_errors(krate)
+        .iter()
+        .map(|error| match error {
+            CoherenceError::Overlap { impl_, conflicting } => {
+                format!("Overlap({}, {})", describe(*impl_), describe(*conflicting))
+            }
+            CoherenceError::Orphan { impl_, .. } => format!("Orphan({})", describe(*impl_)),
+        })
+        .collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn coherence_same_impl_twice() {
+    check_coherence(
+        r#"
+//- /main.rs
+trait Foo {}
+struct S;
+impl Foo for S {}
+impl Foo for S {}<|>
+"#,
+        &["Overlap(impl Foo for S, impl Foo for S)"],
+    );
+}
+
+#[test]
+fn coherence_blanket_impl_overlaps() {
+    check_coherence(
+        r#"
+//- /main.rs
+trait Foo {}
+impl<T> Foo for T {}
+impl Foo for u32 {}<|>
+"#,
+        &["Overlap(impl Foo for u32, impl Foo for T)"],
+    );
+}
+
+#[test]
+fn coherence_reports_first_conflicting_impl() {
+    check_coherence(
+        r#"
+//- /main.rs
+trait Foo {}
+impl Foo for u32 {}
+impl<T> Foo for T {}
+impl Foo for u32 {}<|>
+"#,
+        &[
+            "Overlap(impl Foo for T, impl Foo for u32)",
+            "Overlap(impl Foo for u32, impl Foo for u32)",
+        ],
+    );
+}
+
+#[test]
+fn coherence_generic_impls_dont_overlap() {
+    check_coherence(
+        r#"
+//- /main.rs
+trait Foo {}
+struct S<T>(T);
+impl Foo for S<u32> {}
+impl Foo for S<i32> {}
+impl<T> Foo for S<&T> {}<|>
+"#,
+        &[],
+    );
+}
+
+#[test]
+fn coherence_where_clause_prevents_overlap() {
+    check_coherence(
+        r#"
+//- /main.rs
+trait Foo {}
+trait Bar {}
+struct S;
+struct B;
+impl Bar for B {}
+impl<T: Bar> Foo for T {}
+impl Foo for S {}<|>
+impl Foo for B {}
+"#,
+        &["Overlap(impl Foo for B, impl Foo for T)"],
+    );
+}
+
+#[test]
+fn coherence_overlap_with_dependency() {
+    check_coherence(
+        r#"
+//- /main.rs crate:main deps:other
+use other::{Foo, S};
+struct Local;
+impl Foo for Local {}<|>
+impl Foo for S {}
+
+//- /other.rs crate:other
+pub trait Foo {}
+pub struct S;
+impl<T> Foo for T {}
+"#,
+        &["Overlap(impl Foo for Local, impl Foo for T)", "Orphan(impl Foo for S)"],
+    );
+}
+
+#[test]
+fn coherence_orphan_rules() {
+    check_coherence(
+        r#"
+//- /main.rs crate:main deps:other
+use other::{Display, Box, Ext};
+struct Local;
+impl Display for Local {}
+impl Display for &Local {}
+impl Display for Box<Local> {}
+impl<T> Ext<T> for Local {}<|>
+impl<T> Ext<Local> for T {}
+impl Display for u32 {}
+impl<T> Display for T {}
+impl<T> Display for Box<T> {}
+impl Display for (Local, u32) {}
+
+//- /other.rs crate:other
+pub trait Display {}
+pub trait Ext<T> {}
+#[fundamental]
+pub struct Box<T>(T);
+"#,
+        &[
+            "Orphan(impl Ext for T)",
+            "Orphan(impl Display for u32)",
+            "Orphan(impl Display for T)",
+            "Orphan(impl Display for Box<T>)",
+            "Orphan(impl Display for (Local, u32))",
+        ],
+    );
+}
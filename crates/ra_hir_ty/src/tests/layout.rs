@@ -0,0 +1,195 @@
+use std::sync::Arc;
+
+use hir_def::{db::DefDatabase, AdtId, ModuleDefId};
+use ra_db::fixture::WithFixture;
+
+use crate::{
+    db::HirDatabase,
+    layout::{Layout, LayoutError},
+    test_db::TestDB,
+    Substs, Ty, TypeCtor,
+};
+
+fn layout_of_goal(ra_fixture: &str) -> Result<Arc<Layout>, LayoutError> {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module = db.module_for_file(file_id);
+    let crate_def_map = db.crate_def_map(module.krate);
+    let adt = crate_def_map[module.local_id]
+        .scope
+        .declarations()
+        .find_map(|decl| {
+            let adt = match decl {
+                ModuleDefId::AdtId(it) => it,
+                _ => return None,
+            };
+            let name = match adt {
+                AdtId::StructId(it) => db.struct_data(it).name.clone(),
+                AdtId::UnionId(it) => db.union_data(it).name.clone(),
+                AdtId::EnumId(it) => db.enum_data(it).name.clone(),
+            };
+            if name.to_string() == "Goal" {
+                Some(adt)
+            } else {
+                None
+            }
+        })
+        .expect("no type named `Goal`");
+    let ty = Ty::apply(TypeCtor::Adt(adt), Substs::type_params(&db, adt));
+    db.layout_of(module.krate, ty)
+}
+
+fn check_size_and_align(ra_fixture: &str, size: u64, align: u64) {
+    let layout = layout_of_goal(ra_fixture).unwrap();
+    assert_eq!((layout.size, layout.align), (size, align));
+}
+
+fn check_field_offsets(ra_fixture: &str, offsets: &[u64]) {
+    let layout = layout_of_goal(ra_fixture).unwrap();
+    let actual: Vec<u64> = layout.fields.iter().map(|it| it.offset).collect();
+    assert_eq!(actual, offsets);
+}
+
+#[test]
+fn layout_primitives() {
+    check_size_and_align("struct Goal(u8);", 1, 1);
+    check_size_and_align("struct Goal(u128);", 16, 16);
+    check_size_and_align("struct Goal(char);", 4, 4);
+    check_size_and_align("struct Goal(f64);", 8, 8);
+    check_size_and_align("struct Goal(usize);", 8, 8);
+    check_size_and_align("struct Goal;", 0, 1);
+    check_size_and_align("struct Goal(());", 0, 1);
+}
+
+#[test]
+fn layout_default_repr_reorders_fields() {
+    let fixture = "struct Goal { a: u8, b: u32, c: u16 }";
+    check_size_and_align(fixture, 8, 4);
+    check_field_offsets(fixture, &[6, 0, 4]);
+}
+
+#[test]
+fn layout_repr_c() {
+    let fixture = r#"
+#[repr(C)]
+struct Goal { a: u8, b: u32, c: u16 }
+"#;
+    check_size_and_align(fixture, 12, 4);
+    check_field_offsets(fixture, &[0, 4, 8]);
+}
+
+#[test]
+fn layout_repr_packed() {
+    let fixture = r#"
+#[repr(C, packed)]
+struct Goal { a: u8, b: u32, c: u16 }
+"#;
+    check_size_and_align(fixture, 7, 1);
+    check_field_offsets(fixture, &[0, 1, 5]);
+
+    let fixture = r#"
+#[repr(packed(2))]
+struct Goal { a: u8, b: u32, c: u16 }
+"#;
+    check_size_and_align(fixture, 8, 2);
+    check_field_offsets(fixture, &[0, 2, 6]);
+}
+
+#[test]
+fn layout_repr_transparent_and_align() {
+    check_size_and_align("#[repr(transparent)] struct Goal(u64);", 8, 8);
+    check_size_and_align("#[repr(align(16))] struct Goal(u8);", 16, 16);
+}
+
+#[test]
+fn layout_arrays_and_tuples() {
+    let fixture = "struct Goal { a: [u8; 3], b: u16 }";
+    check_size_and_align(fixture, 6, 2);
+    check_field_offsets(fixture, &[2, 0]);
+
+    check_size_and_align("struct Goal([[u16; 0x2]; 3]);", 12, 2);
+    check_size_and_align("struct Goal((u8, u32));", 8, 4);
+    check_size_and_align("struct Goal(([u8; 3], u16));", 6, 2);
+    check_size_and_align("enum Option<T> { None, Some(T) } struct Goal(Option<[u16; 3]>);", 8, 2);
+    assert_eq!(
+        layout_of_goal("const N: usize = 3; struct Goal([u8; N]);"),
+        Err(LayoutError::UnknownArrayLength)
+    );
+}
+
+#[test]
+fn layout_pointers() {
+    check_size_and_align("struct Goal(&'static u8);", 8, 8);
+    check_size_and_align("struct Goal(&'static str);", 16, 8);
+    check_size_and_align("struct Goal(*const [u8]);", 16, 8);
+    check_size_and_align("struct Goal(fn(u8) -> u8);", 8, 8);
+}
+
+#[test]
+fn layout_uses_target_pointer_width() {
+    check_size_and_align(
+        r#"
+//- /main.rs cfg:target_pointer_width=32
+struct Goal { s: &'static str, x: usize }
+"#,
+        12,
+        4,
+    );
+    check_size_and_align(
+        r#"
+//- /main.rs cfg:target_pointer_width=32
+#[repr(isize)]
+enum Goal { A, B }
+"#,
+        4,
+        4,
+    );
+}
+
+#[test]
+fn layout_enums() {
+    check_size_and_align("enum Goal { A, B, C }", 1, 1);
+    check_size_and_align("enum Goal { A(u8), B(u16), C }", 4, 2);
+    check_size_and_align("#[repr(u32)] enum Goal { A, B }", 4, 4);
+    check_size_and_align("#[repr(u8)] enum Goal { A(u32), B }", 8, 4);
+    check_size_and_align("#[repr(C)] enum Goal { A(u8), B(u32) }", 8, 4);
+    check_size_and_align("enum Goal {}", 0, 1);
+}
+
+#[test]
+fn layout_niche_optimization() {
+    let option = "enum Option<T> { None, Some(T) }";
+    check_size_and_align(&format!("{} struct Goal(Option<&'static u32>);", option), 8, 8);
+    check_size_and_align(&format!("{} struct Goal(Option<fn()>);", option), 8, 8);
+    check_size_and_align(&format!("{} struct Goal(Option<bool>);", option), 1, 1);
+    check_size_and_align(&format!("{} struct Goal(Option<Option<bool>>);", option), 1, 1);
+    check_size_and_align(&format!("{} struct Goal(Option<char>);", option), 4, 4);
+    check_size_and_align(&format!("{} struct Goal(Option<u32>);", option), 8, 4);
+    check_size_and_align(
+        &format!("{} enum E {{ A, B }} struct Goal(Option<Option<E>>);", option),
+        1,
+        1,
+    );
+    check_size_and_align(
+        &format!(
+            "{} #[rustc_layout_scalar_valid_range_start(1)] struct NonZero(u32); struct Goal(Option<NonZero>);",
+            option
+        ),
+        4,
+        4,
+    );
+}
+
+#[test]
+fn layout_errors() {
+    assert_eq!(layout_of_goal("struct Goal<T>(T);"), Err(LayoutError::NotConcrete));
+    assert_eq!(layout_of_goal("struct Goal(Goal);"), Err(LayoutError::Recursive));
+    assert_eq!(layout_of_goal("struct Goal([u8]);"), Err(LayoutError::Unsized));
+    assert_eq!(
+        layout_of_goal("struct Goal([u64; 0xffff_ffff_ffff_ffff]);"),
+        Err(LayoutError::SizeOverflow)
+    );
+    assert_eq!(
+        layout_of_goal("struct Goal(u16, [u8; 0xffff_ffff_ffff_fffe]);"),
+        Err(LayoutError::SizeOverflow)
+    );
+}
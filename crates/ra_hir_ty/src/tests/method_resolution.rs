@@ -856,6 +856,68 @@ fn test() {
     assert_eq!(t, "usize");
 }
 
+#[test]
+fn method_resolution_slice_u8_lang_item() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:core
+fn test() {
+    let a = [1u8, 2, 3];
+    a.is_ascii()<|>;
+}
+
+//- /core.rs crate:core
+#[lang = "slice"]
+impl<T> [T] {}
+#[lang = "slice_u8"]
+impl [u8] {
+    pub fn is_ascii(&self) -> bool { loop {} }
+}
+"#,
+    );
+    assert_eq!(t, "bool");
+}
+
+#[test]
+fn method_resolution_array_lang_item() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:core
+fn test(a: [u32; 2]) {
+    a.first_elem()<|>;
+}
+
+//- /core.rs crate:core
+#[lang = "array"]
+impl<T> [T; 2] {
+    pub fn first_elem(&self) -> T { loop {} }
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn method_resolution_raw_slice_ptr_lang_item() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:core
+fn test(p: *mut [u8]) {
+    p.len()<|>;
+}
+
+//- /core.rs crate:core
+#[lang = "mut_ptr"]
+impl<T: ?Sized> *mut T {}
+#[lang = "mut_slice_ptr"]
+impl<T> *mut [T] {
+    pub fn len(self) -> usize { loop {} }
+}
+"#,
+    );
+    assert_eq!(t, "usize");
+}
+
 #[test]
 fn method_resolution_trait_from_prelude() {
     let (db, pos) = TestDB::with_position(
@@ -1,4 +1,6 @@
-use super::type_at;
+use insta::assert_snapshot;
+
+use super::{infer_with_mismatches, type_at, typed_hole_types};
 
 #[test]
 fn infer_never1() {
@@ -11,7 +13,7 @@ fn test() {
 }
 "#,
     );
-    assert_eq!(t, "!");
+    assert_eq!(t, "()");
 }
 
 #[test]
@@ -28,7 +30,7 @@ fn test() {
 }
 "#,
     );
-    assert_eq!(t, "!");
+    assert_eq!(t, "()");
 }
 
 #[test]
@@ -45,7 +47,7 @@ fn test() {
 }
 "#,
     );
-    assert_eq!(t, "!");
+    assert_eq!(t, "()");
 }
 
 #[test]
@@ -61,7 +63,7 @@ fn test() {
 }
 "#,
     );
-    assert_eq!(t, "Option<!>");
+    assert_eq!(t, "Option<()>");
 }
 
 #[test]
@@ -135,7 +137,7 @@ fn test(a: Void) {
 }
 "#,
     );
-    assert_eq!(t, "!");
+    assert_eq!(t, "()");
 }
 
 #[test]
@@ -242,7 +244,7 @@ fn test(a: i32) {
 }
 "#,
     );
-    assert_eq!(t, "!");
+    assert_eq!(t, "()");
 }
 
 #[test]
@@ -261,3 +263,148 @@ fn test(a: i32) {
     );
     assert_eq!(t, "f64");
 }
+
+#[test]
+fn loop_break_value() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test() {
+    let x = loop {
+        if true {
+            break 5u32;
+        }
+    };
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn loop_break_without_value() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test() {
+    let x = loop {
+        break;
+    };
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "()");
+}
+
+#[test]
+fn labeled_break_from_inner_loop() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test() {
+    let x = 'outer: loop {
+        loop {
+            break 'outer 1i8;
+        }
+    };
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "i8");
+}
+
+#[test]
+fn labeled_block_break_value() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(b: bool) {
+    let x = 'a: {
+        if b {
+            break 'a 1u64;
+        }
+        2
+    };
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+}
+
+#[test]
+fn never_type_fallback_in_block() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test() {
+    let x = {
+        let y = return;
+        y<|>
+    };
+}
+"#,
+    );
+    assert_eq!(t, "()");
+}
+
+#[test]
+fn never_type_fallback_can_be_reinferred() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test() {
+    let x = return;
+    let y: u32 = x;
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn no_mismatches_in_unreachable_code() {
+    assert_snapshot!(
+        infer_with_mismatches(r#"
+fn foo() -> u32 {
+    let x = return 1;
+    let y: bool = 92u32;
+}
+"#, true),
+        @r###"
+    17..67 '{     ...u32; }': !
+    27..28 'x': ()
+    31..39 'return 1': !
+    38..39 '1': u32
+    49..50 'y': bool
+    59..64 '92u32': u32
+    "###
+    );
+}
+
+#[test]
+fn only_underscore_and_todo_are_typed_holes() {
+    let holes = typed_hole_types(
+        r#"
+macro_rules! todo { () => { loop {} } }
+macro_rules! panic { () => { loop {} } }
+fn diverge() -> ! { loop {} }
+
+fn test() -> u64 {
+    let a: u8 = _;
+    let b: u16 = todo!();
+    let c: u32 = diverge();
+    let d: i8 = loop {};
+    let e: i16 = panic!();
+    let f: i32 = ;
+    let g: i64 = undefined_macro!();
+    return 1;
+}
+"#,
+    );
+    assert_eq!(holes, vec!["u8", "u16"]);
+}
@@ -0,0 +1,142 @@
+use hir_def::{db::DefDatabase, ModuleDefId};
+use ra_db::fixture::WithFixture;
+
+use crate::{db::HirDatabase, object_safety::ObjectSafetyViolation, test_db::TestDB};
+
+fn check_violations(ra_fixture: &str, expected: &[&str]) {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module = db.module_for_file(file_id);
+    let crate_def_map = db.crate_def_map(module.krate);
+    let trait_ = crate_def_map[module.local_id]
+        .scope
+        .declarations()
+        .find_map(|decl| match decl {
+            ModuleDefId::TraitId(it) if db.trait_data(it).name.to_string() == "Goal" => Some(it),
+            _ => None,
+        })
+        .expect("no trait named `Goal`");
+    let actual: Vec<String> = db
+        .object_safety(trait_)
+        .iter()
+        .map(|violation| match violation {
+            ObjectSafetyViolation::SizedSelf(it) => {
+                format!("SizedSelf({})", db.trait_data(*it).name)
+            }
+            ObjectSafetyViolation::SupertraitSelf(it) => {
+                format!("SupertraitSelf({})", db.trait_data(*it).name)
+            }
+            ObjectSafetyViolation::Method(it, code) => {
+                format!("{:?}({})", code, db.function_data(*it).name)
+            }
+            ObjectSafetyViolation::AssocConst(it) => {
+                format!("AssocConst({})", db.const_data(*it).name.as_ref().unwrap())
+            }
+        })
+        .collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn object_safe_trait() {
+    check_violations(
+        r#"
+#[lang = "sized"]
+trait Sized {}
+trait Iterator {
+    type Item;
+    fn next(&mut self) -> Option<Self::Item>;
+    fn count(self) -> usize where Self: Sized;
+    fn collect<B>(self) -> B where Self: Sized;
+}
+enum Option<T> { None, Some(T) }
+trait Goal: Iterator {
+    fn boxed(self: Box<Self>) -> u32;
+    fn new() -> Self where Self: Sized;
+}
+struct Box<T>(T);
+"#,
+        &[],
+    );
+}
+
+#[test]
+fn object_safety_method_violations() {
+    check_violations(
+        r#"
+trait Goal {
+    fn new() -> u32;
+    fn eq(&self, other: &Self) -> bool;
+    fn clone(&self) -> Self;
+    fn map<T>(&self, t: T);
+    fn show(&self, it: impl Copy);
+    fn fine(&self, x: u32) -> u32;
+}
+"#,
+        &[
+            "StaticMethod(new)",
+            "ReferencesSelfInput(eq)",
+            "ReferencesSelfOutput(clone)",
+            "Generic(map)",
+            "Generic(show)",
+        ],
+    );
+}
+
+#[test]
+fn object_safety_assoc_const() {
+    check_violations(
+        r#"
+trait Goal {
+    const N: usize;
+}
+"#,
+        &["AssocConst(N)"],
+    );
+}
+
+#[test]
+fn object_safety_sized_self() {
+    check_violations(
+        r#"
+#[lang = "sized"]
+trait Sized {}
+trait Goal: Sized {}
+"#,
+        &["SizedSelf(Goal)"],
+    );
+    check_violations(
+        r#"
+#[lang = "sized"]
+trait Sized {}
+trait Clone: Sized {}
+trait Goal where Self: Clone {}
+"#,
+        &["SizedSelf(Clone)"],
+    );
+}
+
+#[test]
+fn object_safety_supertrait_mentions_self() {
+    check_violations(
+        r#"
+trait PartialEq<Rhs> {}
+trait Goal: PartialEq<Self> {}
+"#,
+        &["SupertraitSelf(Goal)"],
+    );
+}
+
+#[test]
+fn object_safety_includes_supertrait_items() {
+    check_violations(
+        r#"
+trait Base {
+    fn make() -> u32;
+}
+trait Goal: Base {
+    fn ok(&self);
+}
+"#,
+        &["StaticMethod(make)"],
+    );
+}
@@ -1,7 +1,7 @@
 use insta::assert_snapshot;
 use test_utils::covers;
 
-use super::{infer, infer_with_mismatches};
+use super::{infer, infer_with_mismatches, type_at};
 
 #[test]
 fn infer_pattern() {
@@ -451,11 +451,107 @@ fn test() {
     274..277 'Bar': usize
     281..284 'Bar': usize
     201..224: expected (), got Foo
+    212..215: expected (), got Foo
     263..286: expected (), got usize
+    274..277: expected (), got usize
     "###
     );
 }
 
+#[test]
+fn infer_ref_const_and_str_pattern() {
+    assert_snapshot!(
+        infer_with_mismatches(r#"
+const C: &str = "";
+fn test(s: &str) {
+    match s {
+        C => {}
+        "x" => {}
+        _ => {}
+    }
+}
+"#, true),
+        @r###"
+    17..19 '""': &str
+    29..30 's': &str
+    38..111 '{     ...   } }': ()
+    44..109 'match ...     }': ()
+    50..51 's': &str
+    62..63 'C': &str
+    67..69 '{}': ()
+    78..81 '"x"': &str
+    78..81 '"x"': &str
+    85..87 '{}': ()
+    96..97 '_': &str
+    101..103 '{}': ()
+    "###
+    );
+}
+
+#[test]
+fn infer_ref_pattern_mutability_mismatch() {
+    assert_snapshot!(
+        infer_with_mismatches(r#"
+fn test(x: &u32) {
+    let &mut a = x;
+}
+"#, true),
+        @r###"
+    9..10 'x': &u32
+    18..41 '{     ...= x; }': ()
+    28..34 '&mut a': &mut u32
+    33..34 'a': u32
+    37..38 'x': &u32
+    28..34: expected &u32, got &mut u32
+    "###
+    );
+}
+
+#[test]
+fn infer_slice_rest_binding() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(v: &[u32]) {
+    if let [first, rest @ ..] = v {
+        rest<|>;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "&[u32]");
+}
+
+#[test]
+fn infer_array_rest_binding() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(arr: [u32; 3]) {
+    let [first, rest @ ..] = arr;
+    rest<|>;
+}
+"#,
+    );
+    assert_eq!(t, "[u32; _]");
+}
+
+#[test]
+fn infer_tuple_pattern_with_ellipsis() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct S(u8, u16, u32);
+fn test(s: S) {
+    let (a, .., b) = (1u8, 2u16, 3u32, 4u64);
+    let S(.., c) = s;
+    (b, c)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(u64, u32)");
+}
+
 #[test]
 fn infer_guard() {
     assert_snapshot!(
@@ -378,8 +378,8 @@ fn issue_2669() {
     147..262 '{     ...     }': ()
     161..164 'end': fn end<{unknown}>()
     161..166 'end()': ()
-    199..252 '{     ...     }': ()
-    221..223 '_x': !
+    199..252 '{     ...     }': !
+    221..223 '_x': ()
     230..237 'loop {}': !
     235..237 '{}': ()
     "###
@@ -501,6 +501,8 @@ fn foo(params: &[usize]) {
     32..79 'match ...     }': ()
     38..44 'params': &[usize]
     55..67 '[ps @ .., _]': [usize]
+    56..63 'ps @ ..': &[usize]
+    61..63 '..': [usize]
     65..66 '_': usize
     71..73 '{}': ()
     "###
@@ -1772,9 +1772,11 @@ fn main() {
     21..22 'x': i32
     32..38 '{ 92 }': i32
     34..36 '92': i32
-    48..49 'y': {unknown}
-    58..80 '{ asyn...wait }': {unknown}
-    60..78 'async ....await': {unknown}
+    48..49 'y': impl Future<Output = ()>
+    52..80 'async ...wait }': impl Future<Output = ()>
+    58..80 '{ asyn...wait }': ()
+    60..72 'async { () }': impl Future<Output = ()>
+    60..78 'async ....await': ()
     66..72 '{ () }': ()
     68..70 '()': ()
     90..91 'z': {unknown}
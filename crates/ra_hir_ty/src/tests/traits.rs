@@ -2,7 +2,7 @@ use insta::assert_snapshot;
 
 use ra_db::fixture::WithFixture;
 
-use super::{infer, infer_with_mismatches, type_at, type_at_pos};
+use super::{infer, infer_with_mismatches, revealed_type_at, type_at, type_at_pos};
 use crate::test_db::TestDB;
 
 #[test]
@@ -95,6 +95,235 @@ mod future {
     assert_eq!("impl Future<Output = u64>", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_async_block() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:std
+async fn test() {
+    let a = async { 42u64 };
+    a<|>;
+}
+
+//- /std.rs crate:std
+#[prelude_import] use future::*;
+mod future {
+    #[lang = "future_trait"]
+    trait Future {
+        type Output;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "impl Future<Output = u64>");
+}
+
+#[test]
+fn infer_await_async_block() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:std
+async fn test() {
+    let v = async { 42u64 }.await;
+    v<|>;
+}
+
+//- /std.rs crate:std
+#[prelude_import] use future::*;
+mod future {
+    #[lang = "future_trait"]
+    trait Future {
+        type Output;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+}
+
+#[test]
+fn infer_return_in_async_block() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:std
+fn test() -> bool {
+    let a = async {
+        if true {
+            return 1u8;
+        }
+        2
+    };
+    a<|>;
+    true
+}
+
+//- /std.rs crate:std
+#[prelude_import] use future::*;
+mod future {
+    #[lang = "future_trait"]
+    trait Future {
+        type Output;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "impl Future<Output = u8>");
+}
+
+#[test]
+fn infer_try_in_async_block() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:std
+fn test(r: Result<i32, u64>) {
+    let v = async {
+        let x = r?;
+        x<|>;
+        Result::Ok(x)
+    };
+}
+
+//- /std.rs crate:std
+#[prelude_import] use ops::*;
+mod ops {
+    trait Try {
+        type Ok;
+        type Error;
+    }
+}
+
+#[prelude_import] use result::*;
+mod result {
+    enum Result<O, E> {
+        Ok(O),
+        Err(E)
+    }
+
+    impl<O, E> crate::ops::Try for Result<O, E> {
+        type Ok = O;
+        type Error = E;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "i32");
+}
+
+#[test]
+fn infer_async_fn_body_against_output() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:std
+async fn foo() -> u8 {
+    let x = 1;
+    x<|>
+}
+
+//- /std.rs crate:std
+#[prelude_import] use future::*;
+mod future {
+    #[lang = "future_trait"]
+    trait Future {
+        type Output;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "u8");
+}
+
+#[test]
+fn infer_await_into_future() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:std
+struct Lazy;
+impl IntoFuture for Lazy {
+    type Output = u32;
+}
+
+async fn test() {
+    let v = Lazy.await;
+    let w = async { 1i8 }.await;
+    (v, w)<|>;
+}
+
+//- /std.rs crate:std
+#[prelude_import] use future::*;
+pub mod future {
+    #[lang = "future_trait"]
+    pub trait Future {
+        type Output;
+    }
+    pub trait IntoFuture {
+        type Output;
+    }
+    impl<F: Future> IntoFuture for F {
+        type Output = F::Output;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "(u32, i8)");
+}
+
+#[test]
+fn infer_generator() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:core
+fn test() {
+    let g = |x: u32| {
+        let y = yield 1i64;
+        y<|>;
+        "done"
+    };
+}
+
+//- /core.rs crate:core
+#[prelude_import] use ops::*;
+mod ops {
+    #[lang = "generator"]
+    pub trait Generator<R = ()> {
+        type Yield;
+        type Return;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn infer_generator_type_and_projections() {
+    let t = type_at(
+        r#"
+//- /main.rs crate:main deps:core
+fn yield_of<G: Generator>(g: G) -> G::Yield { loop {} }
+fn return_of<G: Generator>(g: G) -> G::Return { loop {} }
+
+fn test() {
+    let g = || {
+        yield 1i64;
+        "done"
+    };
+    (g, yield_of(g), return_of(g))<|>;
+}
+
+//- /core.rs crate:core
+#[prelude_import] use ops::*;
+mod ops {
+    #[lang = "generator"]
+    pub trait Generator<R = ()> {
+        type Yield;
+        type Return;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "(impl Generator<Yield = i64, Return = &str>, i64, &str)");
+}
+
 #[test]
 fn infer_try() {
     let (db, pos) = TestDB::with_position(
@@ -2470,3 +2699,167 @@ fn test(x: &dyn Foo) {
     "###
     );
 }
+
+#[test]
+fn associated_type_default() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Iterable {
+    type Item = u32;
+    fn item(&self) -> Self::Item;
+}
+struct S;
+impl Iterable for S {}
+struct T;
+impl Iterable for T { type Item = u8; }
+
+fn test(s: S, t: T) {
+    (s.item(), t.item())<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(u32, u8)");
+}
+
+#[test]
+fn associated_type_default_mentions_self() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Container {
+    type Elem;
+    type Boxed = Box<Self::Elem>;
+    fn boxed(&self) -> Self::Boxed;
+}
+struct Box<T>(T);
+struct S;
+impl Container for S { type Elem = u64; }
+
+fn test(s: S) {
+    s.boxed()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Box<u64>");
+}
+
+#[test]
+fn generic_associated_type() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Family {
+    type Member<T>;
+    fn make<T>(&self, t: T) -> Self::Member<T>;
+}
+struct Vec<T>(T);
+struct VecFamily;
+impl Family for VecFamily {
+    type Member<T> = Vec<T>;
+}
+
+fn test(f: VecFamily) {
+    f.make(1u8)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Vec<u8>");
+}
+
+#[test]
+fn generic_associated_type_through_projection() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Family {
+    type Member<T>;
+}
+struct Option<T>(T);
+struct OptionFamily;
+impl Family for OptionFamily {
+    type Member<T> = Option<T>;
+}
+
+fn test(x: <OptionFamily as Family>::Member<i32>) {
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Option<i32>");
+}
+
+#[test]
+fn generic_associated_type_with_default() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Ref<T>(T);
+trait Family {
+    type Member<T> = Ref<T>;
+}
+struct RefFamily;
+impl Family for RefFamily {}
+
+fn test<F: Family>(x: <RefFamily as Family>::Member<bool>, y: F::Member<u8>) {
+    (x, y)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(Ref<bool>, Family::Member<F, u8>)");
+}
+
+#[test]
+fn reveal_return_position_impl_trait() {
+    let t = revealed_type_at(
+        r#"
+//- /main.rs
+trait Trait {}
+struct S<T>(T);
+impl<T> Trait for S<T> {}
+fn make<T>(t: T) -> impl Trait { S(t) }
+fn test() {
+    let x = make(1u32);
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "S<u32>");
+}
+
+#[test]
+fn reveal_multiple_impl_traits_in_return_type() {
+    let t = revealed_type_at(
+        r#"
+//- /main.rs
+trait Trait {}
+struct S<T>(T);
+impl<T> Trait for S<T> {}
+fn pair() -> (impl Trait, impl Trait) {
+    if true {
+        return (S(1u8), S(true));
+    }
+    (S(2u8), S(false))
+}
+fn test() {
+    pair()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(S<u8>, S<bool>)");
+}
+
+#[test]
+fn reveal_recursive_impl_trait() {
+    let t = revealed_type_at(
+        r#"
+//- /main.rs
+trait Trait {}
+fn rec() -> impl Trait { rec() }
+fn test() {
+    rec()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "impl Trait");
+}
@@ -1,5 +1,11 @@
 //! Trait solving using Chalk.
-use std::{panic, sync::Arc};
+use std::{
+    any::Any,
+    cell::Cell,
+    fmt, panic,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use chalk_ir::cast::Cast;
 use hir_def::{expr::ExprId, DefWithBodyId, ImplId, TraitId, TypeAliasId};
@@ -7,7 +13,10 @@ use ra_db::{impl_intern_key, salsa, CrateId};
 use ra_prof::profile;
 use rustc_hash::FxHashSet;
 
-use crate::{db::HirDatabase, method_resolution::TyFingerprint, DebruijnIndex};
+use crate::{
+    db::HirDatabase, infer::unify, method_resolution::TyFingerprint, DebruijnIndex, Substs,
+    TypeCtor,
+};
 
 use super::{Canonical, GenericPredicate, HirDisplay, ProjectionTy, TraitRef, Ty, TypeWalk};
 
@@ -16,14 +25,22 @@ use self::chalk::{from_chalk, Interner, ToChalk};
 pub(crate) mod chalk;
 mod builtin;
 
-// This controls the maximum size of types Chalk considers. If we set this too
-// high, we can run into slow edge cases; if we set it too low, Chalk won't
-// find some solutions.
-// FIXME this is currently hardcoded in the recursive solver
-// const CHALK_SOLVER_MAX_SIZE: usize = 10;
+/// Limits for the Chalk solver. If we set these too high, we can run into slow
+/// edge cases; if we set them too low, Chalk won't find some solutions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraitSolverConfig {
+    /// How much 'time' we give the solver for a single goal before giving up.
+    pub fuel: u32,
+    /// How deeply the solver may recurse into subgoals before it considers a
+    /// goal to overflow.
+    pub overflow_depth: usize,
+}
 
-/// This controls how much 'time' we give the Chalk solver before giving up.
-const CHALK_SOLVER_FUEL: i32 = 100;
+impl Default for TraitSolverConfig {
+    fn default() -> Self {
+        TraitSolverConfig { fuel: 100, overflow_depth: 100 }
+    }
+}
 
 #[derive(Debug, Copy, Clone)]
 struct ChalkContext<'a> {
@@ -31,8 +48,11 @@ struct ChalkContext<'a> {
     krate: CrateId,
 }
 
-fn create_chalk_solver() -> chalk_solve::Solver<Interner> {
-    let solver_choice = chalk_solve::SolverChoice::recursive();
+fn create_chalk_solver(config: TraitSolverConfig) -> chalk_solve::Solver<Interner> {
+    let solver_choice = chalk_solve::SolverChoice::Recursive {
+        overflow_depth: config.overflow_depth,
+        caching_enabled: true,
+    };
     solver_choice.into_solver()
 }
 
@@ -47,21 +67,29 @@ pub(crate) fn impls_for_trait_query(
     // the fact that the trait and the self type need to be in the dependency
     // tree of a crate somewhere for an impl to exist, we could skip looking in
     // a lot of crates completely
-    let mut impls = FxHashSet::default();
+    // Keep the impls in a deterministic order: those of the dependencies first,
+    // then the ones of this crate in declaration order.
+    let mut impls = Vec::new();
+    let mut seen = FxHashSet::default();
+    let mut add = |impl_id: ImplId| {
+        if seen.insert(impl_id) {
+            impls.push(impl_id);
+        }
+    };
     // We call the query recursively here. On the one hand, this means we can
     // reuse results from queries for different crates; on the other hand, this
     // will only ever get called for a few crates near the root of the tree (the
     // ones the user is editing), so this may actually be a waste of memory. I'm
     // doing it like this mainly for simplicity for now.
     for dep in &db.crate_graph()[krate].dependencies {
-        impls.extend(db.impls_for_trait(dep.crate_id, trait_, self_ty_fp).iter());
+        db.impls_for_trait(dep.crate_id, trait_, self_ty_fp).iter().copied().for_each(&mut add);
     }
     let crate_impl_defs = db.impls_in_crate(krate);
     match self_ty_fp {
-        Some(fp) => impls.extend(crate_impl_defs.lookup_impl_defs_for_trait_and_ty(trait_, fp)),
-        None => impls.extend(crate_impl_defs.lookup_impl_defs_for_trait(trait_)),
+        Some(fp) => crate_impl_defs.lookup_impl_defs_for_trait_and_ty(trait_, fp).for_each(add),
+        None => crate_impl_defs.lookup_impl_defs_for_trait(trait_).for_each(add),
     }
-    impls.into_iter().collect()
+    impls.into()
 }
 
 /// A set of clauses that we assume to be true. E.g. if we are inside this function:
@@ -153,10 +181,7 @@ pub(crate) fn trait_solve_query(
     krate: CrateId,
     goal: Canonical<InEnvironment<Obligation>>,
 ) -> Option<Solution> {
-    let _p = profile("trait_solve_query").detail(|| match &goal.value.value {
-        Obligation::Trait(it) => db.trait_data(it.trait_).name.to_string(),
-        Obligation::Projection(_) => "projection".to_string(),
-    });
+    let _p = profile("trait_solve_query").detail(|| goal.value.value.display(db).to_string());
     log::info!("trait_solve_query({})", goal.value.value.display(db));
 
     if let Obligation::Projection(pred) = &goal.value.value {
@@ -171,41 +196,206 @@ pub(crate) fn trait_solve_query(
     // We currently don't deal with universes (I think / hope they're not yet
     // relevant for our use cases?)
     let u_canonical = chalk_ir::UCanonical { canonical, universes: 1 };
-    let solution = solve(db, krate, &u_canonical);
+    let (solution, _) = solve(db, krate, &u_canonical);
     solution.map(|solution| solution_from_chalk(db, solution))
 }
 
+/// Solves `goal` like `trait_solve_query`, but bypassing the query cache, and
+/// checks whether the solver ran out of fuel, i.e. whether it gave up on the
+/// goal rather than finding that it doesn't hold.
+pub(crate) fn trait_solve_runs_out_of_fuel(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    goal: Canonical<InEnvironment<Obligation>>,
+) -> bool {
+    let canonical = goal.to_chalk(db).cast(&Interner);
+    let u_canonical = chalk_ir::UCanonical { canonical, universes: 1 };
+    let (_, stats) = solve(db, krate, &u_canonical);
+    stats.fuel_exhausted
+}
+
+/// Solves `goal` like `trait_solve_query`, but bypassing the query cache and
+/// recording the goal tree: for each impl which could prove a trait goal, its
+/// where clauses are traced as subgoals. This is only meant for debugging.
+pub fn trace_trait_solve(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    goal: Canonical<InEnvironment<Obligation>>,
+) -> SolverTrace {
+    let _p = profile("trace_trait_solve");
+    let config = db.trait_solver_config();
+    let root = trace_goal(db, krate, goal, 0);
+    SolverTrace { fuel: config.fuel, root }
+}
+
+/// How deep `trace_trait_solve` expands the goal tree. Chalk itself may go
+/// deeper, this only keeps the trace readable.
+const MAX_TRACE_DEPTH: usize = 8;
+
+fn trace_goal(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    goal: Canonical<InEnvironment<Obligation>>,
+    depth: usize,
+) -> TracedGoal {
+    let goal_text = goal.value.value.display(db).to_string();
+    let _p = profile("trace_goal").detail(|| goal_text.clone());
+
+    let canonical = goal.to_chalk(db).cast(&Interner);
+    let u_canonical = chalk_ir::UCanonical { canonical, universes: 1 };
+    let start = Instant::now();
+    let (solution, stats) = solve(db, krate, &u_canonical);
+    let elapsed = start.elapsed();
+
+    let candidates = match &goal.value.value {
+        Obligation::Trait(trait_ref) if depth < MAX_TRACE_DEPTH => {
+            trace_candidates(db, krate, &goal, trait_ref, depth)
+        }
+        _ => Vec::new(),
+    };
+    TracedGoal {
+        goal: goal_text,
+        solution: solution.map(|solution| {
+            let solution = solution_from_chalk(db, solution);
+            describe_solution(db, &solution)
+        }),
+        elapsed,
+        fuel_used: stats.fuel_used,
+        fuel_exhausted: stats.fuel_exhausted,
+        overflowed: stats.overflowed,
+        candidates,
+    }
+}
+
+/// Finds the impls whose trait ref unifies with `trait_ref`, and traces their
+/// where clauses as subgoals.
+fn trace_candidates(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    goal: &Canonical<InEnvironment<Obligation>>,
+    trait_ref: &TraitRef,
+    depth: usize,
+) -> Vec<TracedCandidate> {
+    let self_ty_fp = TyFingerprint::for_impl(trait_ref.self_ty());
+    let impls = db.impls_for_trait(krate, trait_ref.trait_, self_ty_fp);
+    impls
+        .iter()
+        .filter_map(|&impl_id| {
+            let impl_trait_ref = db.impl_trait(impl_id)?;
+            // The impl's type parameters become variables after the goal's.
+            let impl_vars = Substs::build_for_def(db, impl_id)
+                .fill_with_bound_vars(DebruijnIndex::INNERMOST, goal.num_vars)
+                .build();
+            let num_vars = goal.num_vars + impl_vars.len();
+            let as_tuple = |trait_ref: TraitRef| {
+                let cardinality = trait_ref.substs.len() as u16;
+                Canonical {
+                    num_vars,
+                    value: Ty::apply(TypeCtor::Tuple { cardinality }, trait_ref.substs),
+                }
+            };
+            let unified =
+                unify(&as_tuple(trait_ref.clone()), &as_tuple(impl_trait_ref.subst(&impl_vars)))?;
+
+            let subgoals = db
+                .generic_predicates(impl_id.into())
+                .iter()
+                .filter_map(|pred| {
+                    Obligation::from_predicate(
+                        pred.clone().subst(&impl_vars).subst_bound_vars(&unified),
+                    )
+                })
+                .map(|obligation| {
+                    let value = InEnvironment::new(goal.value.environment.clone(), obligation);
+                    trace_goal(db, krate, Canonical { num_vars, value }, depth + 1)
+                })
+                .collect();
+            let impl_ = db
+                .impl_trait(impl_id)?
+                .subst(&Substs::type_params(db, impl_id))
+                .display(db)
+                .to_string();
+            Some(TracedCandidate { impl_, subgoals })
+        })
+        .collect()
+}
+
+/// What happened while solving a goal, apart from the solution.
+#[derive(Debug, Default, Clone, Copy)]
+struct SolveStats {
+    fuel_used: u32,
+    fuel_exhausted: bool,
+    overflowed: bool,
+}
+
 fn solve(
     db: &dyn HirDatabase,
     krate: CrateId,
     goal: &chalk_ir::UCanonical<chalk_ir::InEnvironment<chalk_ir::Goal<Interner>>>,
-) -> Option<chalk_solve::Solution<Interner>> {
+) -> (Option<chalk_solve::Solution<Interner>>, SolveStats) {
     let context = ChalkContext { db, krate };
     log::debug!("solve goal: {:?}", goal);
-    let mut solver = create_chalk_solver();
+    let config = db.trait_solver_config();
+    let mut solver = create_chalk_solver(config);
 
-    let fuel = std::cell::Cell::new(CHALK_SOLVER_FUEL);
+    let fuel = Cell::new(config.fuel);
+    let fuel_exhausted = Cell::new(false);
 
     let should_continue = || {
         context.db.check_canceled();
         let remaining = fuel.get();
-        fuel.set(remaining - 1);
         if remaining == 0 {
-            log::debug!("fuel exhausted");
+            if !fuel_exhausted.replace(true) {
+                log::warn!("trait solver ran out of fuel on {:?}", goal);
+            }
+            return false;
         }
-        remaining > 0
+        fuel.set(remaining - 1);
+        true
     };
     let mut solve = || {
         let solution = solver.solve_limited(&context, goal, should_continue);
         log::debug!("solve({:?}) => {:?}", goal, solution);
         solution
     };
-    // don't set the TLS for Chalk unless Chalk debugging is active, to make
-    // extra sure we only use it for debugging
-    let solution =
-        if is_chalk_debug() { chalk::tls::set_current_program(db, solve) } else { solve() };
+    // Chalk panics when it reaches the overflow depth. Treat that as an
+    // ambiguous result instead, but let every other panic (including salsa
+    // cancellation) through.
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        // don't set the TLS for Chalk unless Chalk debugging is active, to make
+        // extra sure we only use it for debugging
+        if is_chalk_debug() {
+            chalk::tls::set_current_program(db, solve)
+        } else {
+            solve()
+        }
+    }));
+    let (solution, overflowed) = match result {
+        Ok(solution) => (solution, false),
+        Err(payload) if is_overflow_panic(&*payload) => {
+            log::warn!("trait solver overflowed on {:?}", goal);
+            (Some(chalk_solve::Solution::Ambig(chalk_solve::Guidance::Unknown)), true)
+        }
+        Err(payload) => panic::resume_unwind(payload),
+    };
 
-    solution
+    let stats = SolveStats {
+        fuel_used: config.fuel - fuel.get(),
+        fuel_exhausted: fuel_exhausted.get(),
+        overflowed,
+    };
+    (solution, stats)
+}
+
+fn is_overflow_panic(payload: &(dyn Any + Send)) -> bool {
+    let message = match payload.downcast_ref::<&str>() {
+        Some(it) => *it,
+        None => match payload.downcast_ref::<String>() {
+            Some(it) => it.as_str(),
+            None => return false,
+        },
+    };
+    message.contains("overflow depth reached")
 }
 
 fn is_chalk_debug() -> bool {
@@ -248,6 +438,91 @@ fn solution_from_chalk(
     }
 }
 
+fn describe_solution(db: &dyn HirDatabase, solution: &Solution) -> String {
+    let describe_vars = |vars: &SolutionVariables| {
+        let tys: Vec<_> = vars.0.value.iter().map(|ty| ty.display(db).to_string()).collect();
+        format!("[{}]", tys.join(", "))
+    };
+    match solution {
+        Solution::Unique(vars) => format!("unique {}", describe_vars(vars)),
+        Solution::Ambig(Guidance::Definite(vars)) => {
+            format!("ambiguous, definitely {}", describe_vars(vars))
+        }
+        Solution::Ambig(Guidance::Suggested(vars)) => {
+            format!("ambiguous, suggested {}", describe_vars(vars))
+        }
+        Solution::Ambig(Guidance::Unknown) => "ambiguous".to_string(),
+    }
+}
+
+/// What the solver did while solving a goal, as recorded by
+/// `trace_trait_solve`.
+#[derive(Debug)]
+pub struct SolverTrace {
+    /// The fuel the solver gets for each goal.
+    pub fuel: u32,
+    pub root: TracedGoal,
+}
+
+#[derive(Debug)]
+pub struct TracedGoal {
+    pub goal: String,
+    /// `None` if the goal can't be proven.
+    pub solution: Option<String>,
+    pub elapsed: Duration,
+    pub fuel_used: u32,
+    pub fuel_exhausted: bool,
+    /// Whether Chalk reached the overflow depth; the goal is then considered
+    /// ambiguous.
+    pub overflowed: bool,
+    /// The impls which could prove the goal. Builtin impls, e.g. for closures,
+    /// aren't listed.
+    pub candidates: Vec<TracedCandidate>,
+}
+
+#[derive(Debug)]
+pub struct TracedCandidate {
+    pub impl_: String,
+    /// The where clauses of the impl, for the types the goal unified with.
+    pub subgoals: Vec<TracedGoal>,
+}
+
+impl fmt::Display for SolverTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.fmt_indented(f, 0, self.fuel)
+    }
+}
+
+impl TracedGoal {
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, indent: usize, fuel: u32) -> fmt::Result {
+        let pad = "  ".repeat(indent);
+        writeln!(f, "{}{}", pad, self.goal)?;
+        match &self.solution {
+            Some(solution) => writeln!(f, "{}  solution: {}", pad, solution)?,
+            None => writeln!(f, "{}  solution: none", pad)?,
+        }
+        writeln!(f, "{}  time: {:?}", pad, self.elapsed)?;
+        write!(f, "{}  fuel: {}/{}", pad, self.fuel_used, fuel)?;
+        if self.fuel_exhausted {
+            write!(f, " (exhausted)")?;
+        }
+        writeln!(f)?;
+        if self.overflowed {
+            writeln!(f, "{}  overflowed", pad)?;
+        }
+        if self.solution.is_none() && self.candidates.is_empty() {
+            writeln!(f, "{}  no impls", pad)?;
+        }
+        for candidate in &self.candidates {
+            writeln!(f, "{}  impl {}", pad, candidate.impl_)?;
+            for subgoal in &candidate.subgoals {
+                subgoal.fmt_indented(f, indent + 2, fuel)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SolutionVariables(pub Canonical<Vec<Ty>>);
 
@@ -307,6 +582,18 @@ pub struct ClosureFnTraitImplData {
     fn_trait: FnTrait,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AsyncBlockImplData {
+    def: DefWithBodyId,
+    expr: ExprId,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GeneratorImplData {
+    def: DefWithBodyId,
+    expr: ExprId,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct UnsizeToSuperTraitObjectData {
     trait_: TraitId,
@@ -321,6 +608,10 @@ pub enum Impl {
     ImplDef(ImplId),
     /// Closure types implement the Fn traits synthetically.
     ClosureFnTraitImpl(ClosureFnTraitImplData),
+    /// Async blocks implement `Future` synthetically.
+    AsyncBlockFutureImpl(AsyncBlockImplData),
+    /// Generators implement `Generator` synthetically.
+    GeneratorImpl(GeneratorImplData),
     /// [T; n]: Unsize<[T]>
     UnsizeArray,
     /// T: Unsize<dyn Trait> where T: Trait
@@ -340,8 +631,17 @@ impl_intern_key!(GlobalImplId);
 pub enum AssocTyValue {
     /// A normal assoc type value from an impl block.
     TypeAlias(TypeAliasId),
+    /// The default from the trait's declaration of the assoc type, for an impl
+    /// block that doesn't specify it.
+    TraitDefault { impl_: ImplId, assoc_ty: TypeAliasId },
     /// The output type of the Fn trait implementation.
     ClosureFnTraitImplOutput(ClosureFnTraitImplData),
+    /// The output type of the `Future` implementation of an async block.
+    AsyncBlockOutput(AsyncBlockImplData),
+    /// The `Yield` type of the `Generator` implementation.
+    GeneratorYield(GeneratorImplData),
+    /// The `Return` type of the `Generator` implementation.
+    GeneratorReturn(GeneratorImplData),
 }
 /// This exists just for Chalk, because it needs a unique ID for each associated
 /// type value in an impl (even synthetic ones).
@@ -1,10 +1,14 @@
 //! This module provides the built-in trait implementations, e.g. to make
 //! closures implement `Fn`.
+use std::iter;
+
 use hir_def::{expr::Expr, lang_item::LangItemTarget, TraitId, TypeAliasId};
-use hir_expand::name::name;
+use hir_expand::name::{name, Name};
 use ra_db::CrateId;
 
-use super::{AssocTyValue, Impl, UnsizeToSuperTraitObjectData};
+use super::{
+    AssocTyValue, AsyncBlockImplData, GeneratorImplData, Impl, UnsizeToSuperTraitObjectData,
+};
 use crate::{
     db::HirDatabase,
     utils::{all_super_traits, generics},
@@ -51,6 +55,22 @@ pub(super) fn get_builtin_impls(
         }
     }
 
+    if let Ty::Apply(ApplicationTy { ctor: TypeCtor::AsyncBlock { def, expr }, .. }) = ty {
+        if get_future_trait(db, krate) == Some(trait_)
+            && check_async_block_impl_prerequisites(db, krate)
+        {
+            callback(Impl::AsyncBlockFutureImpl(AsyncBlockImplData { def: *def, expr: *expr }));
+        }
+    }
+
+    if let Ty::Apply(ApplicationTy { ctor: TypeCtor::Generator { def, expr }, .. }) = ty {
+        if get_generator_trait(db, krate) == Some(trait_)
+            && check_generator_impl_prerequisites(db, krate)
+        {
+            callback(Impl::GeneratorImpl(GeneratorImplData { def: *def, expr: *expr }));
+        }
+    }
+
     let unsize_trait = get_unsize_trait(db, krate);
     if let Some(actual_trait) = unsize_trait {
         if trait_ == actual_trait {
@@ -99,6 +119,8 @@ pub(super) fn impl_datum(db: &dyn HirDatabase, krate: CrateId, impl_: Impl) -> B
     match impl_ {
         Impl::ImplDef(_) => unreachable!(),
         Impl::ClosureFnTraitImpl(data) => closure_fn_trait_impl_datum(db, krate, data),
+        Impl::AsyncBlockFutureImpl(data) => async_block_future_impl_datum(db, krate, data),
+        Impl::GeneratorImpl(data) => generator_impl_datum(db, krate, data),
         Impl::UnsizeArray => array_unsize_impl_datum(db, krate),
         Impl::UnsizeToTraitObject(trait_) => trait_object_unsize_impl_datum(db, krate, trait_),
         Impl::UnsizeToSuperTraitObject(data) => {
@@ -113,10 +135,17 @@ pub(super) fn associated_ty_value(
     data: AssocTyValue,
 ) -> BuiltinImplAssocTyValueData {
     match data {
-        AssocTyValue::TypeAlias(_) => unreachable!(),
+        AssocTyValue::TypeAlias(_) | AssocTyValue::TraitDefault { .. } => unreachable!(),
         AssocTyValue::ClosureFnTraitImplOutput(data) => {
             closure_fn_trait_output_assoc_ty_value(db, krate, data)
         }
+        AssocTyValue::AsyncBlockOutput(data) => async_block_output_assoc_ty_value(db, krate, data),
+        AssocTyValue::GeneratorYield(data) => {
+            generator_assoc_ty_value(db, krate, data, &name![Yield], 1)
+        }
+        AssocTyValue::GeneratorReturn(data) => {
+            generator_assoc_ty_value(db, krate, data, &name![Return], 2)
+        }
     }
 }
 
@@ -226,6 +255,136 @@ fn closure_fn_trait_output_assoc_ty_value(
     }
 }
 
+// Async block Future impls
+
+fn check_async_block_impl_prerequisites(db: &dyn HirDatabase, krate: CrateId) -> bool {
+    // the Future trait needs to exist, have no type parameters besides Self
+    // and an assoc type named Output
+    let future_trait = match get_future_trait(db, krate) {
+        Some(t) => t,
+        None => return false,
+    };
+    generics(db.upcast(), future_trait.into()).len() == 1
+        && db.trait_data(future_trait).associated_type_by_name(&name![Output]).is_some()
+}
+
+fn async_block_future_impl_datum(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    data: AsyncBlockImplData,
+) -> BuiltinImplData {
+    // impl<T> Future for async_block<T> { Output = T }
+
+    let trait_ = get_future_trait(db, krate) // get future trait
+        // the existence of the Future trait has been checked before
+        .expect("Future trait missing");
+
+    let self_ty = Ty::apply_one(
+        TypeCtor::AsyncBlock { def: data.def, expr: data.expr },
+        Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 0)),
+    );
+
+    let trait_ref = TraitRef { trait_, substs: Substs::single(self_ty) };
+
+    BuiltinImplData {
+        num_vars: 1,
+        trait_ref,
+        where_clauses: Vec::new(),
+        assoc_ty_values: vec![AssocTyValue::AsyncBlockOutput(data)],
+    }
+}
+
+fn async_block_output_assoc_ty_value(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    data: AsyncBlockImplData,
+) -> BuiltinImplAssocTyValueData {
+    let future_trait = get_future_trait(db, krate).expect("assoc ty value should not exist");
+
+    let output_ty_id = db
+        .trait_data(future_trait)
+        .associated_type_by_name(&name![Output])
+        .expect("assoc ty value should not exist");
+
+    BuiltinImplAssocTyValueData {
+        impl_: Impl::AsyncBlockFutureImpl(data),
+        assoc_ty_id: output_ty_id,
+        num_vars: 1,
+        value: Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 0)),
+    }
+}
+
+// Generator impls
+
+fn check_generator_impl_prerequisites(db: &dyn HirDatabase, krate: CrateId) -> bool {
+    // the Generator trait needs to exist, have at most a resume type parameter
+    // besides Self and assoc types named Yield and Return
+    let generator_trait = match get_generator_trait(db, krate) {
+        Some(t) => t,
+        None => return false,
+    };
+    let trait_data = db.trait_data(generator_trait);
+    generics(db.upcast(), generator_trait.into()).len() <= 2
+        && trait_data.associated_type_by_name(&name![Yield]).is_some()
+        && trait_data.associated_type_by_name(&name![Return]).is_some()
+}
+
+fn generator_impl_datum(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    data: GeneratorImplData,
+) -> BuiltinImplData {
+    // impl<R, Y, T> Generator<R> for generator<R, Y, T> { Yield = Y, Return = T }
+    // (older versions of the trait don't have the resume type parameter)
+
+    let trait_ = get_generator_trait(db, krate) // get generator trait
+        // the existence of the Generator trait has been checked before
+        .expect("Generator trait missing");
+
+    let self_ty = Ty::apply(
+        TypeCtor::Generator { def: data.def, expr: data.expr },
+        Substs::builder(3).fill_with_bound_vars(DebruijnIndex::INNERMOST, 0).build(),
+    );
+    let resume_ty = Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 0));
+
+    let trait_ref = TraitRef {
+        trait_,
+        substs: Substs::build_for_def(db, trait_).push(self_ty).fill(iter::once(resume_ty)).build(),
+    };
+
+    BuiltinImplData {
+        num_vars: 3,
+        trait_ref,
+        where_clauses: Vec::new(),
+        assoc_ty_values: vec![
+            AssocTyValue::GeneratorYield(data),
+            AssocTyValue::GeneratorReturn(data),
+        ],
+    }
+}
+
+fn generator_assoc_ty_value(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    data: GeneratorImplData,
+    name: &Name,
+    param_idx: usize,
+) -> BuiltinImplAssocTyValueData {
+    let generator_trait = get_generator_trait(db, krate).expect("assoc ty value should not exist");
+
+    let assoc_ty_id = db
+        .trait_data(generator_trait)
+        .associated_type_by_name(name)
+        .expect("assoc ty value should not exist");
+
+    BuiltinImplAssocTyValueData {
+        impl_: Impl::GeneratorImpl(data),
+        assoc_ty_id,
+        num_vars: 3,
+        value: Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, param_idx)),
+    }
+}
+
 // Array unsizing
 
 fn check_unsize_impl_prerequisites(db: &dyn HirDatabase, krate: CrateId) -> bool {
@@ -369,6 +528,22 @@ fn get_fn_trait(db: &dyn HirDatabase, krate: CrateId, fn_trait: super::FnTrait)
     }
 }
 
+fn get_future_trait(db: &dyn HirDatabase, krate: CrateId) -> Option<TraitId> {
+    let target = db.lang_item(krate, "future_trait".into())?;
+    match target {
+        LangItemTarget::TraitId(t) => Some(t),
+        _ => None,
+    }
+}
+
+fn get_generator_trait(db: &dyn HirDatabase, krate: CrateId) -> Option<TraitId> {
+    let target = db.lang_item(krate, "generator".into())?;
+    match target {
+        LangItemTarget::TraitId(t) => Some(t),
+        _ => None,
+    }
+}
+
 fn get_unsize_trait(db: &dyn HirDatabase, krate: CrateId) -> Option<TraitId> {
     let target = db.lang_item(krate, "unsize".into())?;
     match target {
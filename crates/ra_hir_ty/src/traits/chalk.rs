@@ -1061,20 +1061,36 @@ fn impl_def_datum(
 
     let impl_datum_bound = chalk_rust_ir::ImplDatumBound { trait_ref, where_clauses };
     let trait_data = db.trait_data(trait_);
-    let associated_ty_value_ids = impl_data
+    let impl_type_aliases: Vec<TypeAliasId> = impl_data
         .items
         .iter()
         .filter_map(|item| match item {
             AssocItemId::TypeAliasId(type_alias) => Some(*type_alias),
             _ => None,
         })
+        .collect();
+    let impl_values = impl_type_aliases
+        .iter()
+        .copied()
         .filter(|&type_alias| {
             // don't include associated types that don't exist in the trait
             let name = &db.type_alias_data(type_alias).name;
             trait_data.associated_type_by_name(name).is_some()
         })
-        .map(|type_alias| AssocTyValue::TypeAlias(type_alias).to_chalk(db))
-        .collect();
+        .map(AssocTyValue::TypeAlias);
+    // associated types the impl omits fall back to the trait's default, if any
+    let default_values = trait_data
+        .associated_types()
+        .filter(|&assoc_ty| {
+            let data = db.type_alias_data(assoc_ty);
+            data.type_ref.is_some()
+                && !impl_type_aliases
+                    .iter()
+                    .any(|&type_alias| db.type_alias_data(type_alias).name == data.name)
+        })
+        .map(|assoc_ty| AssocTyValue::TraitDefault { impl_: impl_id, assoc_ty });
+    let associated_ty_value_ids =
+        impl_values.chain(default_values).map(|value| value.to_chalk(db)).collect();
     debug!("impl_datum: {:?}", impl_datum_bound);
     let impl_datum = ImplDatum {
         binders: make_binders(impl_datum_bound, bound_vars.len()),
@@ -1095,6 +1111,9 @@ pub(crate) fn associated_ty_value_query(
         AssocTyValue::TypeAlias(type_alias) => {
             type_alias_associated_ty_value(db, krate, type_alias)
         }
+        AssocTyValue::TraitDefault { impl_, assoc_ty } => {
+            trait_default_associated_ty_value(db, impl_, assoc_ty)
+        }
         _ => Arc::new(builtin::associated_ty_value(db, krate, data).to_chalk(db)),
     }
 }
@@ -1126,6 +1145,32 @@ fn type_alias_associated_ty_value(
     Arc::new(value)
 }
 
+fn trait_default_associated_ty_value(
+    db: &dyn HirDatabase,
+    impl_id: hir_def::ImplId,
+    assoc_ty: TypeAliasId,
+) -> Arc<AssociatedTyValue> {
+    // The default is written in terms of the trait's parameters followed by
+    // the associated type's own ones; the value is bound by the impl's
+    // parameters followed by the associated type's own ones.
+    let trait_ref = db.impl_trait(impl_id).expect("assoc ty value should not exist").value;
+    let num_impl_params = generics(db.upcast(), impl_id.into()).len();
+    let (_, num_trait_params, num_own_params) = generics(db.upcast(), assoc_ty.into()).len_split();
+    let own_vars = (0..num_own_params).map(|idx| {
+        Ty::Bound(crate::BoundVar::new(DebruijnIndex::INNERMOST, num_impl_params + idx))
+    });
+    let substs =
+        Substs(trait_ref.substs.iter().cloned().take(num_trait_params).chain(own_vars).collect());
+    let ty = db.ty(assoc_ty.into()).subst(&substs);
+    let value_bound = chalk_rust_ir::AssociatedTyValueBound { ty: ty.to_chalk(db) };
+    let value = chalk_rust_ir::AssociatedTyValue {
+        impl_id: Impl::ImplDef(impl_id).to_chalk(db),
+        associated_ty_id: assoc_ty.to_chalk(db),
+        value: make_binders(value_bound, num_impl_params + num_own_params),
+    };
+    Arc::new(value)
+}
+
 impl From<StructId> for crate::TypeCtorId {
     fn from(struct_id: StructId) -> Self {
         InternKey::from_intern_id(struct_id.0)
@@ -92,6 +92,12 @@ impl DebugContext<'_> {
                 };
                 write!(f, "}}")?;
             }
+            TypeCtor::AsyncBlock { expr, .. } => {
+                write!(f, "{{async block {:?}}}", expr.into_raw())?;
+            }
+            TypeCtor::Generator { expr, .. } => {
+                write!(f, "{{generator {:?}}}", expr.into_raw())?;
+            }
         }
         Ok(())
     }
@@ -16,7 +16,9 @@ use hir_expand::name::{name, Name};
 
 use crate::{db::HirDatabase, GenericPredicate, TraitRef};
 
-fn direct_super_traits(db: &dyn DefDatabase, trait_: TraitId) -> Vec<TraitId> {
+/// Returns the traits `trait_` directly lists as its super traits, from its
+/// `Self` bounds.
+pub fn direct_super_traits(db: &dyn DefDatabase, trait_: TraitId) -> Vec<TraitId> {
     let resolver = trait_.resolver(db);
     // returning the iterator directly doesn't easily work because of
     // lifetime problems, but since there usually shouldn't be more than a
@@ -1,10 +1,11 @@
 //! FIXME: write short doc here
-use hir::Semantics;
+use hir::{Adt, AssocItem, ModuleDef, PathResolution, Semantics, VariantDef};
 use ra_ide_db::RootDatabase;
 use ra_syntax::{
-    ast::{self, ArgListOwner},
-    match_ast, AstNode, SyntaxNode, SyntaxToken,
+    ast::{self, ArgListOwner, NameOwner},
+    match_ast, AstNode, SyntaxNode, SyntaxToken, TextRange, TextSize,
 };
+use rustc_hash::FxHashSet;
 use test_utils::tested_by;
 
 use crate::{CallInfo, FilePosition, FunctionSignature};
@@ -15,14 +16,18 @@ pub(crate) fn call_info(db: &RootDatabase, position: FilePosition) -> Option<Cal
     let file = sema.parse(position.file_id);
     let file = file.syntax();
     let token = file.token_at_offset(position.offset).next()?;
-    let token = sema.descend_into_macros(token);
-    call_info_for_token(&sema, token)
+    let descended = sema.descend_into_macros(token.clone());
+    if descended == token {
+        return call_info_for_token(&sema, token);
+    }
+    // Arguments of a macro call which don't end up in a call in the expansion
+    // are still arguments of the macro.
+    call_info_for_token(&sema, descended).or_else(|| call_info_for_token(&sema, token))
 }
 
 #[derive(Debug)]
 pub(crate) struct ActiveParameter {
-    /// FIXME: should be `Type` and `Name
-    pub(crate) ty: String,
+    /// FIXME: should be `Name`
     pub(crate) name: String,
 }
 
@@ -37,6 +42,30 @@ impl ActiveParameter {
 }
 
 fn call_info_for_token(sema: &Semantics<RootDatabase>, token: SyntaxToken) -> Option<CallInfo> {
+    // Generic arguments, record literals and patterns nested in the arguments
+    // of a call take precedence over the call.
+    for node in token.parent().ancestors() {
+        match_ast! {
+            match node {
+                ast::TypeArgList(it) => return generic_args_info(sema, &it, &token),
+                ast::RecordFieldList(it) => return record_lit_info(sema, &it, &token),
+                ast::RecordFieldPatList(it) => return record_pat_info(sema, &it, &token),
+                ast::TupleStructPat(it) => {
+                    let l_paren = it.l_paren_token()?;
+                    if l_paren.text_range().end() <= token.text_range().start() {
+                        return tuple_struct_pat_info(sema, &it, &token);
+                    }
+                },
+                ast::ArgList(_) => break,
+                ast::MacroCall(_) => break,
+                _ => (),
+            }
+        }
+    }
+    fn_call_info(sema, token)
+}
+
+fn fn_call_info(sema: &Semantics<RootDatabase>, token: SyntaxToken) -> Option<CallInfo> {
     // Find the calling expression and it's NameRef
     let calling_node = FnCallNode::with_node(&token.parent())?;
 
@@ -63,7 +92,8 @@ fn call_info_for_token(sema: &Semantics<RootDatabase>, token: SyntaxToken) -> Op
         }
         FnCallNode::MacroCallExpr(macro_call) => {
             let macro_def = sema.resolve_macro_call(&macro_call)?;
-            (CallInfo::with_macro(sema.db, macro_def)?, false)
+            let args = macro_call.token_tree()?;
+            return CallInfo::with_macro(sema.db, macro_def, &args, token.text_range().end());
         }
     };
 
@@ -111,6 +141,133 @@ fn call_info_for_token(sema: &Semantics<RootDatabase>, token: SyntaxToken) -> Op
     Some(call_info)
 }
 
+fn generic_args_info(
+    sema: &Semantics<RootDatabase>,
+    arg_list: &ast::TypeArgList,
+    token: &SyntaxToken,
+) -> Option<CallInfo> {
+    let parent = arg_list.syntax().parent()?;
+    let def = match_ast! {
+        match parent {
+            ast::PathSegment(it) => match sema.resolve_path(&it.parent_path())? {
+                PathResolution::Def(it) => it,
+                PathResolution::AssocItem(AssocItem::Function(it)) => it.into(),
+                PathResolution::AssocItem(AssocItem::TypeAlias(it)) => it.into(),
+                _ => return None,
+            },
+            ast::MethodCallExpr(it) => sema.resolve_method_call(&it)?.into(),
+            _ => return None,
+        }
+    };
+    let signature = FunctionSignature::from_generic_def(sema.db, def)?;
+    if signature.parameters.is_empty() {
+        return None;
+    }
+    let param = arg_list
+        .generic_args()
+        .take_while(|arg| arg.syntax().text_range().end() <= token.text_range().start())
+        .count();
+    let active_parameter = Some(param).filter(|&it| it < signature.parameters.len());
+    Some(CallInfo { signature, active_parameter })
+}
+
+fn record_lit_info(
+    sema: &Semantics<RootDatabase>,
+    field_list: &ast::RecordFieldList,
+    token: &SyntaxToken,
+) -> Option<CallInfo> {
+    let record_lit = field_list.syntax().parent().and_then(ast::RecordLit::cast)?;
+    let variant = resolve_variant(sema, &record_lit.path()?)?;
+    let fields = field_list.fields().filter_map(|field| {
+        let name = field.field_name()?.text().to_string();
+        Some((name, field.syntax().text_range()))
+    });
+    Some(record_fields_info(sema.db, variant, fields, token))
+}
+
+fn record_pat_info(
+    sema: &Semantics<RootDatabase>,
+    field_list: &ast::RecordFieldPatList,
+    token: &SyntaxToken,
+) -> Option<CallInfo> {
+    let record_pat = field_list.syntax().parent().and_then(ast::RecordPat::cast)?;
+    let variant = resolve_variant(sema, &record_pat.path()?)?;
+    let fields = field_list.pats().filter_map(|pat| {
+        let name = match &pat {
+            ast::RecordInnerPat::RecordFieldPat(it) => it.field_name()?.to_string(),
+            ast::RecordInnerPat::BindPat(it) => it.name()?.text().to_string(),
+        };
+        Some((name, pat.syntax().text_range()))
+    });
+    Some(record_fields_info(sema.db, variant, fields, token))
+}
+
+/// Lists the fields of `variant` which are not written yet, the written ones
+/// being the `(name, range)` pairs in `fields`. The active parameter is the
+/// field at the cursor, or the first remaining field.
+fn record_fields_info(
+    db: &RootDatabase,
+    variant: VariantDef,
+    fields: impl Iterator<Item = (String, TextRange)>,
+    token: &SyntaxToken,
+) -> CallInfo {
+    let offset = token.text_range().start();
+    let mut current = None;
+    let mut written = FxHashSet::default();
+    for (name, range) in fields {
+        if range.contains_inclusive(offset) {
+            current = Some(name);
+        } else {
+            written.insert(name);
+        }
+    }
+    let remaining = variant
+        .fields(db)
+        .into_iter()
+        .filter(|field| !written.contains(&field.name(db).to_string()))
+        .collect::<Vec<_>>();
+    let signature = FunctionSignature::from_record_fields(db, variant, &remaining);
+    let active_parameter = match current {
+        Some(current) => signature.parameter_names.iter().position(|it| *it == current),
+        None if remaining.is_empty() => None,
+        None => Some(0),
+    };
+    CallInfo { signature, active_parameter }
+}
+
+fn tuple_struct_pat_info(
+    sema: &Semantics<RootDatabase>,
+    pat: &ast::TupleStructPat,
+    token: &SyntaxToken,
+) -> Option<CallInfo> {
+    let mut call_info = match resolve_variant(sema, &pat.path()?)? {
+        VariantDef::Struct(it) => CallInfo::with_struct(sema.db, it)?,
+        VariantDef::EnumVariant(it) => CallInfo::with_enum_variant(sema.db, it)?,
+        VariantDef::Union(_) => return None,
+    };
+    let param = pat
+        .args()
+        .take_while(|arg| arg.syntax().text_range().end() <= token.text_range().start())
+        .count();
+    call_info.active_parameter = Some(param).filter(|&it| it < call_info.parameters().len());
+    Some(call_info)
+}
+
+fn resolve_variant(sema: &Semantics<RootDatabase>, path: &ast::Path) -> Option<VariantDef> {
+    let res = match sema.resolve_path(path)? {
+        PathResolution::Def(ModuleDef::Adt(Adt::Struct(it))) => it.into(),
+        PathResolution::Def(ModuleDef::Adt(Adt::Union(it))) => it.into(),
+        PathResolution::Def(ModuleDef::EnumVariant(it)) => it.into(),
+        PathResolution::SelfType(impl_def) => match impl_def.target_ty(sema.db).as_adt()? {
+            Adt::Struct(it) => it.into(),
+            Adt::Union(it) => it.into(),
+            Adt::Enum(_) => return None,
+        },
+        _ => return None,
+    };
+    Some(res)
+}
+
 #[derive(Debug)]
 pub(crate) enum FnCallNode {
     CallExpr(ast::CallExpr),
@@ -176,9 +333,8 @@ impl FnCallNode {
 impl CallInfo {
     fn into_active_parameter(self) -> Option<ActiveParameter> {
         let idx = self.active_parameter?;
-        let ty = self.signature.parameter_types.get(idx)?.clone();
         let name = self.signature.parameter_names.get(idx)?.clone();
-        let res = ActiveParameter { ty, name };
+        let res = ActiveParameter { name };
         Some(res)
     }
 
@@ -200,10 +356,23 @@ impl CallInfo {
         Some(CallInfo { signature, active_parameter: None })
     }
 
-    fn with_macro(db: &RootDatabase, macro_def: hir::MacroDef) -> Option<Self> {
-        let signature = FunctionSignature::from_macro(db, macro_def)?;
+    /// The parameters of a macro call are the top-level metavariables and
+    /// repetitions of the matching rule, the active one is the one matching the
+    /// arguments up to `offset`.
+    fn with_macro(
+        db: &RootDatabase,
+        macro_def: hir::MacroDef,
+        args: &ast::TokenTree,
+        offset: TextSize,
+    ) -> Option<Self> {
+        let (rule, fragment) = match macro_def.matching_fragment(db, args, offset) {
+            Some((rule, fragment)) => (Some(rule), fragment),
+            None => (None, None),
+        };
+        let signature = FunctionSignature::from_macro(db, macro_def, rule)?;
+        let active_parameter = fragment.filter(|&it| it < signature.parameters.len());
 
-        Some(CallInfo { signature, active_parameter: None })
+        Some(CallInfo { signature, active_parameter })
     }
 
     fn parameters(&self) -> &[String] {
@@ -684,4 +853,131 @@ fn f() {
 
         assert_eq!(info.label(), "fn foo()");
     }
+
+    #[test]
+    fn call_info_for_generic_args() {
+        let info = call_info(
+            r#"
+struct HashMap<K: Eq, V, S = RandomState> where K: Hash {}
+fn f(_: HashMap<u32, <|>>) {}
+"#,
+        );
+
+        assert_eq!(info.parameters(), ["K: Eq", "V", "S = RandomState"]);
+        assert_eq!(info.label(), "HashMap<K: Eq, V, S = RandomState>\nwhere K: Hash");
+        assert_eq!(info.active_parameter, Some(1));
+    }
+
+    #[test]
+    fn call_info_for_macro_rule() {
+        let info = call_info(
+            r#"
+macro_rules! foo {
+    ($a:expr) => {};
+    ($a:expr, $b:ident) => {};
+}
+fn f() {
+    foo!(1, x<|>);
+}
+"#,
+        );
+
+        assert_eq!(info.parameters(), ["$a:expr", "$b:ident"]);
+        assert_eq!(info.label(), "foo!($a:expr, $b:ident)");
+        assert_eq!(info.active_parameter, Some(1));
+    }
+
+    #[test]
+    fn call_info_for_macro_rule_without_commas() {
+        let info = call_info(
+            r#"
+macro_rules! foo {
+    ($i:ident = $e:expr) => {};
+    ($e:expr; $($es:expr);*) => {};
+}
+fn f() {
+    foo!(x = 1<|>);
+}
+"#,
+        );
+
+        assert_eq!(info.parameters(), ["$i:ident", "$e:expr"]);
+        assert_eq!(info.label(), "foo!($i:ident = $e:expr)");
+        assert_eq!(info.active_parameter, Some(1));
+
+        let info = call_info(
+            r#"
+macro_rules! foo {
+    ($i:ident = $e:expr) => {};
+    ($e:expr; $($es:expr);*) => {};
+}
+fn f() {
+    foo!(1; 2; 3<|>);
+}
+"#,
+        );
+
+        assert_eq!(info.parameters(), ["$e:expr", "$($es:expr);*"]);
+        assert_eq!(info.label(), "foo!($e:expr; $($es:expr);*)");
+        assert_eq!(info.active_parameter, Some(1));
+    }
+
+    #[test]
+    fn call_info_for_record_literal() {
+        let info = call_info(
+            r#"
+struct S { a: u32, b: i32, c: bool }
+fn f() {
+    S { b: 1, <|> };
+}
+"#,
+        );
+
+        assert_eq!(info.parameters(), ["a: u32", "c: bool"]);
+        assert_eq!(info.label(), "S { a: u32, c: bool }");
+        assert_eq!(info.active_parameter, Some(0));
+
+        let info = call_info(
+            r#"
+struct S { a: u32, b: i32, c: bool }
+fn f() {
+    S { a: 1, b: 2<|> };
+}
+"#,
+        );
+
+        assert_eq!(info.parameters(), ["b: i32", "c: bool"]);
+        assert_eq!(info.active_parameter, Some(0));
+    }
+
+    #[test]
+    fn call_info_for_record_pattern() {
+        let info = call_info(
+            r#"
+enum E { V { x: u32, y: u32 } }
+fn f(e: E) {
+    let E::V { x, <|> } = e;
+}
+"#,
+        );
+
+        assert_eq!(info.parameters(), ["y: u32"]);
+        assert_eq!(info.label(), "E::V { y: u32 }");
+        assert_eq!(info.active_parameter, Some(0));
+    }
+
+    #[test]
+    fn call_info_for_tuple_struct_pattern() {
+        let info = call_info(
+            r#"
+struct P(u32, bool);
+fn f(p: P) {
+    let P(a, <|>) = p;
+}
+"#,
+        );
+
+        assert_eq!(info.parameters(), ["u32", "bool"]);
+        assert_eq!(info.active_parameter, Some(1));
+    }
 }
@@ -17,6 +17,7 @@ mod complete_unqualified_path;
 mod complete_postfix;
 mod complete_macro_in_item_position;
 mod complete_trait_impl;
+mod complete_typed_hole;
 #[cfg(test)]
 mod test_utils;
 
@@ -79,6 +80,25 @@ pub(crate) fn completions(
     complete_postfix::complete_postfix(&mut acc, &ctx);
     complete_macro_in_item_position::complete_macro_in_item_position(&mut acc, &ctx);
     complete_trait_impl::complete_trait_impl(&mut acc, &ctx);
+    complete_typed_hole::complete_typed_hole(&mut acc, &ctx);
 
+    if !config.resolve_lazily {
+        acc.resolve_all(db, position);
+    }
     Some(acc)
 }
+
+/// Computes the detail, documentation and `use` edit of the item labeled
+/// `label` among the completions at `position`, which were left out of the
+/// completions for clients which resolve the item they pick.
+pub(crate) fn resolve_completion(
+    db: &RootDatabase,
+    position: FilePosition,
+    config: &CompletionConfig,
+    label: &str,
+) -> Option<CompletionItem> {
+    let items: Vec<CompletionItem> = completions(db, position, config)?.into();
+    let mut item = items.into_iter().find(|it| it.label() == label)?;
+    item.resolve(db, position);
+    Some(item)
+}
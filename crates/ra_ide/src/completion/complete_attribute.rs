@@ -3,7 +3,10 @@
 //! This module uses a bit of static metadata to provide completions
 //! for built-in attributes.
 
-use ra_syntax::{ast, AstNode, SyntaxKind};
+use std::iter::successors;
+
+use ra_db::SourceDatabase;
+use ra_syntax::{ast, AstNode, SyntaxKind, SyntaxToken, TextRange, TextSize, T};
 use rustc_hash::FxHashSet;
 
 use crate::completion::{
@@ -15,10 +18,14 @@ pub(super) fn complete_attribute(acc: &mut Completions, ctx: &CompletionContext)
     let attribute = ctx.attribute_under_caret.as_ref()?;
 
     match (attribute.path(), attribute.input()) {
-        (Some(path), Some(ast::AttrInput::TokenTree(token_tree)))
-            if path.to_string() == "derive" =>
-        {
-            complete_derive(acc, ctx, token_tree)
+        (Some(path), Some(ast::AttrInput::TokenTree(token_tree))) => {
+            match path.to_string().as_str() {
+                "derive" => complete_derive(acc, ctx, token_tree),
+                "allow" | "warn" | "deny" | "forbid" => complete_lint(acc, ctx, token_tree),
+                "cfg" => complete_cfg(acc, ctx),
+                "repr" => complete_repr(acc, ctx, token_tree),
+                _ => complete_attribute_start(acc, ctx, attribute),
+            }
         }
         _ => complete_attribute_start(acc, ctx, attribute),
     }
@@ -131,7 +138,7 @@ const ATTRIBUTES: &[AttrCompletion] = &[
 ];
 
 fn complete_derive(acc: &mut Completions, ctx: &CompletionContext, derive_input: ast::TokenTree) {
-    if let Ok(existing_derives) = parse_derive_input(derive_input) {
+    if let Ok(existing_derives) = parse_comma_sep_input(derive_input) {
         for derive_completion in DEFAULT_DERIVE_COMPLETIONS
             .into_iter()
             .filter(|completion| !existing_derives.contains(completion.label))
@@ -164,15 +171,152 @@ fn complete_derive(acc: &mut Completions, ctx: &CompletionContext, derive_input:
     }
 }
 
-fn parse_derive_input(derive_input: ast::TokenTree) -> Result<FxHashSet<String>, ()> {
-    match (derive_input.left_delimiter_token(), derive_input.right_delimiter_token()) {
+fn complete_lint(acc: &mut Completions, ctx: &CompletionContext, lint_input: ast::TokenTree) {
+    if let Ok(existing_lints) = parse_comma_sep_input(lint_input) {
+        // Lints can be paths, like `clippy::all`, so complete the whole path.
+        let start = successors(Some(ctx.original_token.clone()), |it| it.prev_token())
+            .take_while(|it| it.kind() == SyntaxKind::IDENT || it.kind() == T![:])
+            .last()
+            .map_or(ctx.source_range().start(), |it| it.text_range().start());
+        let source_range = TextRange::new(start, ctx.source_range().end());
+
+        for &lint in DEFAULT_LINT_COMPLETIONS
+            .iter()
+            .chain(CLIPPY_LINT_COMPLETIONS)
+            .filter(|&&lint| !existing_lints.contains(lint))
+        {
+            acc.add(
+                CompletionItem::new(CompletionKind::Attribute, source_range, lint)
+                    .kind(CompletionItemKind::Attribute),
+            );
+        }
+    }
+}
+
+fn complete_cfg(acc: &mut Completions, ctx: &CompletionContext) {
+    let krate = match ctx.krate {
+        Some(it) => it,
+        None => return,
+    };
+    let crate_graph = ctx.db.crate_graph();
+    let cfg_options = &crate_graph[krate.into()].cfg_options;
+
+    // `#[cfg(key = "<|>")]`
+    let token = &ctx.original_token;
+    let in_string = token.kind() == SyntaxKind::STRING && ctx.offset < token.text_range().end();
+    let before_value = if in_string { token.prev_token() } else { Some(token.clone()) };
+    if let Some(key) = before_value.and_then(cfg_key_of_value) {
+        let mut values: Vec<_> = cfg_options.key_values(&key).collect();
+        values.sort();
+        values.dedup();
+        for value in values {
+            let item = if in_string {
+                let start = token.text_range().start() + TextSize::of('"');
+                let source_range = TextRange::new(start, ctx.offset);
+                CompletionItem::new(CompletionKind::Attribute, source_range, value.to_string())
+            } else {
+                CompletionItem::new(
+                    CompletionKind::Attribute,
+                    ctx.source_range(),
+                    format!("\"{}\"", value),
+                )
+                .lookup_by(value.to_string())
+            };
+            acc.add(item.kind(CompletionItemKind::Attribute));
+        }
+        return;
+    }
+
+    let mut keys: Vec<_> = cfg_options.keys().collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        let item = CompletionItem::new(CompletionKind::Attribute, ctx.source_range(), key.as_str())
+            .kind(CompletionItemKind::Attribute);
+        let item = match ctx.config.snippet_cap {
+            Some(cap) => item.insert_snippet(cap, format!("{} = \"$0\"", key)),
+            None => item.insert_text(format!("{} = \"\"", key)),
+        };
+        acc.add(item);
+    }
+
+    let mut atoms: Vec<_> = cfg_options.atoms().collect();
+    atoms.sort();
+    for atom in atoms {
+        acc.add(
+            CompletionItem::new(CompletionKind::Attribute, ctx.source_range(), atom.as_str())
+                .kind(CompletionItemKind::Attribute),
+        );
+    }
+
+    for predicate in &["all", "any", "not"] {
+        let item = CompletionItem::new(
+            CompletionKind::Attribute,
+            ctx.source_range(),
+            format!("{}(…)", predicate),
+        )
+        .kind(CompletionItemKind::Attribute)
+        .lookup_by(*predicate);
+        let item = match ctx.config.snippet_cap {
+            Some(cap) => item.insert_snippet(cap, format!("{}($0)", predicate)),
+            None => item.insert_text(format!("{}()", predicate)),
+        };
+        acc.add(item);
+    }
+}
+
+/// If `token` is the `=` of `key = value`, possibly followed by whitespace,
+/// returns the `key`.
+fn cfg_key_of_value(token: SyntaxToken) -> Option<String> {
+    let mut tokens = successors(Some(token), |it| it.prev_token())
+        .filter(|it| it.kind() != SyntaxKind::WHITESPACE);
+    if tokens.next()?.kind() != T![=] {
+        return None;
+    }
+    let key = tokens.next()?;
+    if key.kind() != SyntaxKind::IDENT {
+        return None;
+    }
+    Some(key.text().to_string())
+}
+
+fn complete_repr(acc: &mut Completions, ctx: &CompletionContext, repr_input: ast::TokenTree) {
+    if let Ok(existing_reprs) = parse_comma_sep_input(repr_input) {
+        let existing_reprs: FxHashSet<_> = existing_reprs
+            .iter()
+            .map(|it| it.split('(').next().unwrap_or_default().to_string())
+            .collect();
+        for repr_completion in
+            REPR_COMPLETIONS.iter().filter(|completion| !existing_reprs.contains(completion.name))
+        {
+            let item = match (repr_completion.snippet, ctx.config.snippet_cap) {
+                (Some(snippet), Some(cap)) => CompletionItem::new(
+                    CompletionKind::Attribute,
+                    ctx.source_range(),
+                    format!("{}(…)", repr_completion.name),
+                )
+                .lookup_by(repr_completion.name)
+                .insert_snippet(cap, snippet),
+                _ => CompletionItem::new(
+                    CompletionKind::Attribute,
+                    ctx.source_range(),
+                    repr_completion.name,
+                ),
+            };
+            acc.add(item.kind(CompletionItemKind::Attribute));
+        }
+    }
+}
+
+fn parse_comma_sep_input(input: ast::TokenTree) -> Result<FxHashSet<String>, ()> {
+    match (input.left_delimiter_token(), input.right_delimiter_token()) {
         (Some(left_paren), Some(right_paren))
             if left_paren.kind() == SyntaxKind::L_PAREN
                 && right_paren.kind() == SyntaxKind::R_PAREN =>
         {
-            let mut input_derives = FxHashSet::default();
-            let mut current_derive = String::new();
-            for token in derive_input
+            let mut items = FxHashSet::default();
+            let mut current_item = String::new();
+            for token in input
                 .syntax()
                 .children_with_tokens()
                 .filter_map(|token| token.into_token())
@@ -181,19 +325,19 @@ fn parse_derive_input(derive_input: ast::TokenTree) -> Result<FxHashSet<String>,
                 .take_while(|token| token != &right_paren)
             {
                 if SyntaxKind::COMMA == token.kind() {
-                    if !current_derive.is_empty() {
-                        input_derives.insert(current_derive);
-                        current_derive = String::new();
+                    if !current_item.is_empty() {
+                        items.insert(current_item);
+                        current_item = String::new();
                     }
                 } else {
-                    current_derive.push_str(token.to_string().trim());
+                    current_item.push_str(token.to_string().trim());
                 }
             }
 
-            if !current_derive.is_empty() {
-                input_derives.insert(current_derive);
+            if !current_item.is_empty() {
+                items.insert(current_item);
             }
-            Ok(input_derives)
+            Ok(items)
         }
         _ => Err(()),
     }
@@ -230,15 +374,169 @@ const DEFAULT_DERIVE_COMPLETIONS: &[DeriveCompletion] = &[
     DeriveCompletion { label: "Ord", dependencies: &["PartialOrd", "Eq", "PartialEq"] },
 ];
 
+/// Commonly used lints and lint groups built into rustc. This is a selection,
+/// not the full list, which changes with every toolchain.
+const DEFAULT_LINT_COMPLETIONS: &[&str] = &[
+    "box_pointers",
+    "dead_code",
+    "deprecated",
+    "elided_lifetimes_in_paths",
+    "explicit_outlives_requirements",
+    "future_incompatible",
+    "improper_ctypes",
+    "irrefutable_let_patterns",
+    "missing_copy_implementations",
+    "missing_debug_implementations",
+    "missing_docs",
+    "non_camel_case_types",
+    "non_snake_case",
+    "non_upper_case_globals",
+    "nonstandard_style",
+    "overflowing_literals",
+    "path_statements",
+    "renamed_and_removed_lints",
+    "rust_2018_idioms",
+    "single_use_lifetimes",
+    "stable_features",
+    "trivial_casts",
+    "trivial_numeric_casts",
+    "unconditional_recursion",
+    "unknown_lints",
+    "unreachable_code",
+    "unreachable_patterns",
+    "unreachable_pub",
+    "unsafe_code",
+    "unused",
+    "unused_assignments",
+    "unused_extern_crates",
+    "unused_imports",
+    "unused_macros",
+    "unused_must_use",
+    "unused_mut",
+    "unused_parens",
+    "unused_qualifications",
+    "unused_results",
+    "unused_unsafe",
+    "unused_variables",
+    "variant_size_differences",
+    "warnings",
+    "while_true",
+];
+
+/// Clippy's lint groups and a selection of its lints, which are not complete
+/// either.
+const CLIPPY_LINT_COMPLETIONS: &[&str] = &[
+    "clippy::all",
+    "clippy::cargo",
+    "clippy::complexity",
+    "clippy::correctness",
+    "clippy::nursery",
+    "clippy::pedantic",
+    "clippy::perf",
+    "clippy::restriction",
+    "clippy::style",
+    "clippy::cognitive_complexity",
+    "clippy::collapsible_if",
+    "clippy::float_cmp",
+    "clippy::large_enum_variant",
+    "clippy::len_zero",
+    "clippy::let_and_return",
+    "clippy::many_single_char_names",
+    "clippy::missing_safety_doc",
+    "clippy::module_name_repetitions",
+    "clippy::needless_lifetimes",
+    "clippy::needless_return",
+    "clippy::new_ret_no_self",
+    "clippy::new_without_default",
+    "clippy::redundant_closure",
+    "clippy::redundant_field_names",
+    "clippy::should_implement_trait",
+    "clippy::single_match",
+    "clippy::too_many_arguments",
+    "clippy::type_complexity",
+    "clippy::unreadable_literal",
+    "clippy::wrong_self_convention",
+];
+
+struct ReprCompletion {
+    name: &'static str,
+    snippet: Option<&'static str>,
+}
+
+const REPR_COMPLETIONS: &[ReprCompletion] = &[
+    ReprCompletion { name: "C", snippet: None },
+    ReprCompletion { name: "align", snippet: Some("align(${0:1})") },
+    ReprCompletion { name: "i8", snippet: None },
+    ReprCompletion { name: "i16", snippet: None },
+    ReprCompletion { name: "i32", snippet: None },
+    ReprCompletion { name: "i64", snippet: None },
+    ReprCompletion { name: "i128", snippet: None },
+    ReprCompletion { name: "isize", snippet: None },
+    ReprCompletion { name: "packed", snippet: None },
+    ReprCompletion { name: "transparent", snippet: None },
+    ReprCompletion { name: "u8", snippet: None },
+    ReprCompletion { name: "u16", snippet: None },
+    ReprCompletion { name: "u32", snippet: None },
+    ReprCompletion { name: "u64", snippet: None },
+    ReprCompletion { name: "u128", snippet: None },
+    ReprCompletion { name: "usize", snippet: None },
+];
+
 #[cfg(test)]
 mod tests {
-    use crate::completion::{test_utils::do_completion, CompletionItem, CompletionKind};
     use insta::assert_debug_snapshot;
+    use ra_cfg::CfgOptions;
+    use ra_db::Env;
+    use ra_syntax::TextRange;
+
+    use crate::{
+        completion::{test_utils::do_completion, CompletionConfig, CompletionItem, CompletionKind},
+        mock_analysis::MockAnalysis,
+        AnalysisChange, CrateGraph,
+        Edition::Edition2018,
+    };
 
     fn do_attr_completion(code: &str) -> Vec<CompletionItem> {
         do_completion(code, CompletionKind::Attribute)
     }
 
+    /// Completes in a crate with `unix` and the `std` and `serde` features
+    /// enabled.
+    fn do_cfg_completion(code: &str) -> Vec<String> {
+        let mut mock = MockAnalysis::new();
+        let position = mock.add_file_with_position("/lib.rs", code);
+        let mut host = mock.analysis_host();
+
+        let mut cfg_options = CfgOptions::default();
+        cfg_options.insert_atom("unix".into());
+        cfg_options.insert_key_value("feature".into(), "std".into());
+        cfg_options.insert_key_value("feature".into(), "serde".into());
+        let mut crate_graph = CrateGraph::default();
+        crate_graph.add_crate_root(
+            position.file_id,
+            Edition2018,
+            None,
+            cfg_options,
+            Env::default(),
+            Default::default(),
+            Default::default(),
+        );
+        let mut change = AnalysisChange::new();
+        change.set_crate_graph(crate_graph);
+        host.apply_change(change);
+
+        let completions =
+            host.analysis().completions(position, &CompletionConfig::default()).unwrap().unwrap();
+        let completions: Vec<CompletionItem> = completions.into();
+        let mut labels: Vec<String> = completions
+            .into_iter()
+            .filter(|it| it.completion_kind == CompletionKind::Attribute)
+            .map(|it| it.label().to_string())
+            .collect();
+        labels.sort();
+        labels
+    }
+
     #[test]
     fn empty_derive_completion() {
         assert_debug_snapshot!(
@@ -403,6 +701,189 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lint_completion() {
+        let completions = do_attr_completion(
+            r"
+            #[allow(dead_code, <|>)]
+            struct Test {}
+            ",
+        );
+        let labels: Vec<_> = completions.iter().map(|it| it.label()).collect();
+        assert!(labels.contains(&"unused_variables"));
+        assert!(labels.contains(&"clippy::all"));
+        assert!(!labels.contains(&"dead_code"));
+    }
+
+    #[test]
+    fn clippy_lint_completion_replaces_the_path() {
+        let completions = do_attr_completion(r"#[allow(clippy::<|>)] struct Test {}");
+        let all = completions.iter().find(|it| it.label() == "clippy::all").unwrap();
+        assert_eq!(all.source_range(), TextRange::new(8.into(), 16.into()));
+    }
+
+    #[test]
+    fn cfg_completion() {
+        assert_debug_snapshot!(
+            do_attr_completion(
+                    r"
+                    #[cfg(<|>)]
+                    struct Test {}
+                    ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "all(…)",
+                source_range: 27..27,
+                delete: 27..27,
+                insert: "all($0)",
+                kind: Attribute,
+                lookup: "all",
+            },
+            CompletionItem {
+                label: "any(…)",
+                source_range: 27..27,
+                delete: 27..27,
+                insert: "any($0)",
+                kind: Attribute,
+                lookup: "any",
+            },
+            CompletionItem {
+                label: "not(…)",
+                source_range: 27..27,
+                delete: 27..27,
+                insert: "not($0)",
+                kind: Attribute,
+                lookup: "not",
+            },
+        ]
+            "###
+        );
+    }
+
+    #[test]
+    fn repr_completion() {
+        assert_debug_snapshot!(
+            do_attr_completion(
+                    r"
+                    #[repr(C, <|>)]
+                    struct Test {}
+                    ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "align(…)",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "align(${0:1})",
+                kind: Attribute,
+                lookup: "align",
+            },
+            CompletionItem {
+                label: "i128",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "i128",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "i16",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "i16",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "i32",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "i32",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "i64",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "i64",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "i8",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "i8",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "isize",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "isize",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "packed",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "packed",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "transparent",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "transparent",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "u128",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "u128",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "u16",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "u16",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "u32",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "u32",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "u64",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "u64",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "u8",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "u8",
+                kind: Attribute,
+            },
+            CompletionItem {
+                label: "usize",
+                source_range: 31..31,
+                delete: 31..31,
+                insert: "usize",
+                kind: Attribute,
+            },
+        ]
+            "###
+        );
+    }
+
     #[test]
     fn test_attribute_completion() {
         assert_debug_snapshot!(
@@ -852,4 +1333,17 @@ mod tests {
         "###
         );
     }
+
+    #[test]
+    fn completes_cfg_options_of_crate() {
+        assert_eq!(
+            do_cfg_completion("#[cfg(<|>)]\nfn f() {}"),
+            vec!["all(…)", "any(…)", "feature", "not(…)", "unix"]
+        );
+        assert_eq!(do_cfg_completion("#[cfg(feature = \"<|>\")]\nfn f() {}"), vec!["serde", "std"]);
+        assert_eq!(
+            do_cfg_completion("#[cfg(all(unix, feature = <|>))]\nfn f() {}"),
+            vec!["\"serde\"", "\"std\""]
+        );
+    }
 }
@@ -839,6 +839,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn works_in_incomplete_macro_repetition() {
+        assert_debug_snapshot!(
+            do_ref_completion(
+                r"
+                macro_rules! vec { ($($e:expr),*) => { [$($e),*] } }
+                struct A { the_field: u32 }
+                fn foo(a: A) {
+                    vec![a.<|>]
+                }
+                ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "the_field",
+                source_range: 172..172,
+                delete: 172..172,
+                insert: "the_field",
+                kind: Field,
+                detail: "u32",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn test_method_completion_3547() {
         assert_debug_snapshot!(
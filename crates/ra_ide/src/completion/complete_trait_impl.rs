@@ -31,7 +31,7 @@
 //! }
 //! ```
 
-use hir::{self, Docs, HasSource};
+use hir::{self, HasSource};
 use ra_assists::utils::get_missing_assoc_items;
 use ra_syntax::{
     ast::{self, edit, ImplDef},
@@ -41,7 +41,8 @@ use ra_text_edit::TextEdit;
 
 use crate::{
     completion::{
-        CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+        completion_item::CompletionDetails, CompletionContext, CompletionItem, CompletionItemKind,
+        CompletionKind, Completions,
     },
     display::FunctionSignature,
 };
@@ -140,7 +141,7 @@ fn add_function_impl(
 
     let builder = CompletionItem::new(CompletionKind::Magic, ctx.source_range(), label)
         .lookup_by(fn_name)
-        .set_documentation(func.docs(ctx.db));
+        .details(CompletionDetails::Docs((*func).into()));
 
     let completion_kind = if func.has_self_param(ctx.db) {
         CompletionItemKind::Method
@@ -179,7 +180,7 @@ fn add_type_alias_impl(
         .text_edit(TextEdit::replace(range, snippet))
         .lookup_by(alias_name)
         .kind(CompletionItemKind::TypeAlias)
-        .set_documentation(type_alias.docs(ctx.db))
+        .details(CompletionDetails::Docs((*type_alias).into()))
         .add_to(acc);
 }
 
@@ -200,7 +201,7 @@ fn add_const_impl(
             .text_edit(TextEdit::replace(range, snippet))
             .lookup_by(const_name)
             .kind(CompletionItemKind::Const)
-            .set_documentation(const_.docs(ctx.db))
+            .details(CompletionDetails::Docs((*const_).into()))
             .add_to(acc);
     }
 }
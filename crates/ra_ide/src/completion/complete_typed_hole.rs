@@ -0,0 +1,99 @@
+//! Completes typed holes, i.e. `_` in expression position, with terms of the
+//! expected type.
+
+use hir::{HirDisplay, Term, VariantDef};
+use ra_syntax::{ast, AstNode, T};
+
+use crate::completion::{
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+};
+
+pub(super) fn complete_typed_hole(acc: &mut Completions, ctx: &CompletionContext) {
+    if ctx.original_token.kind() != T![_] {
+        return;
+    }
+    let hole = match ctx.original_token.parent().and_then(ast::UnderscoreExpr::cast) {
+        Some(it) => ast::Expr::from(it),
+        None => return,
+    };
+    let goal = match ctx.sema.expected_type_of_hole(&hole) {
+        Some(it) => it,
+        None => return,
+    };
+    let scope = ctx.sema.scope(hole.syntax());
+    let module = match scope.module() {
+        Some(it) => it,
+        None => return,
+    };
+    let detail = goal.display(ctx.db).to_string();
+    for term in scope.term_search(&goal) {
+        let label = term.to_source(ctx.db, module);
+        CompletionItem::new(CompletionKind::Magic, ctx.original_token.text_range(), label)
+            .kind(term_kind(&term))
+            .lookup_by("_")
+            .detail(detail.clone())
+            .add_to(acc);
+    }
+}
+
+fn term_kind(term: &Term) -> CompletionItemKind {
+    match term {
+        Term::Local(_) => CompletionItemKind::Binding,
+        Term::Field(..) => CompletionItemKind::Field,
+        Term::Const(_) => CompletionItemKind::Const,
+        Term::Static(_) => CompletionItemKind::Static,
+        Term::Constructor(VariantDef::EnumVariant(_), _) => CompletionItemKind::EnumVariant,
+        Term::Constructor(..) => CompletionItemKind::Struct,
+        Term::Call(..) | Term::Default => CompletionItemKind::Function,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_debug_snapshot;
+
+    use crate::completion::{test_utils::do_completion, CompletionItem, CompletionKind};
+
+    fn do_magic_completion(code: &str) -> Vec<CompletionItem> {
+        do_completion(code, CompletionKind::Magic)
+    }
+
+    #[test]
+    fn completes_locals_and_fields_in_hole() {
+        assert_debug_snapshot!(
+            do_magic_completion(
+                r"struct S { f: u32 } fn foo(s: S) { let n: u32 = 0; let x: u32 = _<|>; }",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "n",
+                source_range: 64..65,
+                delete: 64..65,
+                insert: "n",
+                kind: Binding,
+                lookup: "_",
+                detail: "u32",
+            },
+            CompletionItem {
+                label: "s.f",
+                source_range: 64..65,
+                delete: 64..65,
+                insert: "s.f",
+                kind: Field,
+                lookup: "_",
+                detail: "u32",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn no_hole_completion_for_wildcard_pattern() {
+        assert_debug_snapshot!(
+            do_magic_completion(r"fn foo(x: Option<u32>) { match x { _<|> => () } }"),
+            @"[]"
+        );
+    }
+}
@@ -1,11 +1,23 @@
 //! Completion of names from the current scope, e.g. locals and imported items.
 
-use hir::ScopeDef;
+use either::Either;
+use hir::{Hygiene, ModPath, ScopeDef, Semantics};
+use ra_assists::utils::insert_use_statement;
+use ra_ide_db::{imports_locator::ImportsLocator, RootDatabase};
+use ra_text_edit::{TextEdit, TextEditBuilder};
+use rustc_hash::FxHashSet;
 use test_utils::tested_by;
 
-use crate::completion::{CompletionContext, Completions};
+use crate::{
+    completion::{CompletionContext, CompletionItem, Completions},
+    FilePosition,
+};
 use hir::{Adt, ModuleDef, Type};
-use ra_syntax::AstNode;
+use ra_syntax::{ast, AstNode, SourceFile};
+
+/// The maximum number of items not in scope to look up, to keep completion
+/// fast in big workspaces.
+const IMPORT_COMPLETION_LIMIT: usize = 40;
 
 pub(super) fn complete_unqualified_path(acc: &mut Completions, ctx: &CompletionContext) {
     if !(ctx.is_trivial_path || ctx.is_pat_binding_or_const) {
@@ -26,7 +38,9 @@ pub(super) fn complete_unqualified_path(acc: &mut Completions, ctx: &CompletionC
         return;
     }
 
+    let mut names_in_scope = FxHashSet::default();
     ctx.scope().process_all_names(&mut |name, res| {
+        names_in_scope.insert(name.to_string());
         if ctx.use_item_syntax.is_some() {
             if let (ScopeDef::Unknown, Some(name_ref)) = (&res, &ctx.name_ref_syntax) {
                 if name_ref.syntax().text() == name.to_string().as_str() {
@@ -37,6 +51,74 @@ pub(super) fn complete_unqualified_path(acc: &mut Completions, ctx: &CompletionC
         }
         acc.add_resolution(ctx, name.to_string(), &res)
     });
+
+    if ctx.config.enable_autoimport_completions && ctx.use_item_syntax.is_none() {
+        complete_items_to_import(acc, ctx, &names_in_scope);
+    }
+}
+
+/// Completes the items from the workspace and the dependencies which are not in
+/// scope yet, inserting the `use` for the chosen one. The `use` is computed when
+/// the item is resolved, see `import_edit`.
+fn complete_items_to_import(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    names_in_scope: &FxHashSet<String>,
+) -> Option<()> {
+    let name_ref = ctx.name_ref_syntax.as_ref()?;
+    let module = ctx.scope().module()?;
+
+    let mut seen = FxHashSet::default();
+    let candidates =
+        ImportsLocator::new(ctx.db).find_similar(name_ref.text(), IMPORT_COMPLETION_LIMIT);
+    for candidate in candidates {
+        let (name, path, resolution) = match candidate {
+            Either::Left(def) => {
+                (def.name(ctx.db), module.find_use_path(ctx.db, def), ScopeDef::ModuleDef(def))
+            }
+            Either::Right(mac) => {
+                (mac.name(ctx.db), module.find_use_path(ctx.db, mac), ScopeDef::MacroDef(mac))
+            }
+        };
+        let (name, path) = match (name, path) {
+            // Items with a single-segment path are in scope already.
+            (Some(name), Some(path)) if path.segments.len() > 1 => (name, path),
+            _ => continue,
+        };
+        let name = name.to_string();
+        let import_path = path.to_string();
+        if names_in_scope.contains(&name) || !seen.insert(import_path.clone()) {
+            continue;
+        }
+
+        let mut items = Completions::default();
+        items.add_resolution(ctx, name, &resolution);
+        let items: Vec<CompletionItem> = items.into();
+        for item in items {
+            acc.add(item.with_import(&import_path));
+        }
+    }
+    Some(())
+}
+
+/// The edit inserting `use import_path;` for an item completed at `position`.
+pub(super) fn import_edit(
+    db: &RootDatabase,
+    position: FilePosition,
+    import_path: &str,
+) -> Option<TextEdit> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let anchor = file.syntax().token_at_offset(position.offset).left_biased()?.parent();
+    let path = SourceFile::parse(&format!("use {};", import_path))
+        .tree()
+        .syntax()
+        .descendants()
+        .find_map(ast::Path::cast)?;
+    let path = ModPath::from_src(path, &Hygiene::new_unhygienic())?;
+    let mut builder = TextEditBuilder::default();
+    insert_use_statement(&anchor, &path, &sema, &mut builder);
+    Some(builder.finish())
 }
 
 fn complete_enum_variants(acc: &mut Completions, ctx: &CompletionContext, ty: &Type) {
@@ -68,7 +150,10 @@ mod tests {
     use insta::assert_debug_snapshot;
     use test_utils::covers;
 
-    use crate::completion::{test_utils::do_completion, CompletionItem, CompletionKind};
+    use crate::{
+        completion::{test_utils::do_completion, CompletionConfig, CompletionItem, CompletionKind},
+        mock_analysis::analysis_and_position,
+    };
 
     fn do_reference_completion(ra_fixture: &str) -> Vec<CompletionItem> {
         do_completion(ra_fixture, CompletionKind::Reference)
@@ -1108,6 +1193,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn completes_bindings_from_macro_call_args() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                r"
+                macro_rules! m { ($e:expr) => { $e } }
+                fn quux() {
+                    m!({ let inner = 92; inn<|> });
+                }
+                "
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "inner",
+                source_range: 125..128,
+                delete: 125..128,
+                insert: "inner",
+                kind: Binding,
+                detail: "i32",
+            },
+            CompletionItem {
+                label: "m!(…)",
+                source_range: 125..128,
+                delete: 125..128,
+                insert: "m!($0)",
+                kind: Macro,
+                detail: "macro_rules! m",
+            },
+            CompletionItem {
+                label: "quux()",
+                source_range: 125..128,
+                delete: 125..128,
+                insert: "quux()$0",
+                kind: Function,
+                lookup: "quux",
+                detail: "fn quux()",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn completes_unresolved_uses() {
         assert_debug_snapshot!(
@@ -1296,6 +1424,7 @@ mod tests {
                 kind: EnumVariant,
                 lookup: "Bar",
                 detail: "()",
+                score: TypeMatch,
             },
             CompletionItem {
                 label: "Foo::Baz",
@@ -1305,6 +1434,7 @@ mod tests {
                 kind: EnumVariant,
                 lookup: "Baz",
                 detail: "()",
+                score: TypeMatch,
             },
             CompletionItem {
                 label: "Foo::Quux",
@@ -1314,6 +1444,7 @@ mod tests {
                 kind: EnumVariant,
                 lookup: "Quux",
                 detail: "()",
+                score: TypeMatch,
             },
             CompletionItem {
                 label: "main()",
@@ -1351,6 +1482,7 @@ mod tests {
                 kind: Function,
                 lookup: "f",
                 detail: "fn f() -> m::E",
+                score: TypeMatch,
             },
             CompletionItem {
                 label: "m",
@@ -1367,6 +1499,7 @@ mod tests {
                 kind: EnumVariant,
                 lookup: "V",
                 detail: "()",
+                score: TypeMatch,
             },
         ]
         "###
@@ -1386,4 +1519,88 @@ mod tests {
             @r###"[]"###
         )
     }
+
+    #[test]
+    fn completes_items_to_import() {
+        let completions = do_completion(
+            r"
+            //- /main.rs
+            fn main() {
+                let _ = HashM<|>
+            }
+            //- /std/lib.rs
+            pub mod collections {
+                pub struct HashMap;
+            }
+            ",
+            CompletionKind::Magic,
+        );
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].label(), "HashMap (use std::collections::HashMap)");
+        assert_eq!(completions[0].lookup(), "HashMap");
+        assert_eq!(completions[0].import_path(), Some("std::collections::HashMap"));
+        let indels = completions[0].text_edit().as_indels();
+        assert_eq!(indels.len(), 1);
+        assert_eq!(indels[0].insert, "HashMap");
+        let import_indels = completions[0].import_edit().unwrap().as_indels();
+        assert_eq!(import_indels.len(), 1);
+        assert!(import_indels[0].insert.starts_with("use std::collections::HashMap;"));
+    }
+
+    #[test]
+    fn leaves_details_and_imports_to_resolve() {
+        let (analysis, position) = analysis_and_position(
+            r"
+            //- /main.rs
+            /// Makes a map.
+            fn make_map() -> u32 { 0 }
+            fn main() {
+                let _ = HashM<|>
+            }
+            //- /std/lib.rs
+            pub mod collections {
+                pub struct HashMap;
+            }
+            ",
+        );
+        let config = CompletionConfig { resolve_lazily: true, ..CompletionConfig::default() };
+        let items = analysis.completions(position, &config).unwrap().unwrap();
+        let make_map = items.iter().find(|it| it.label() == "make_map").unwrap();
+        assert_eq!(make_map.detail(), None);
+        assert!(make_map.documentation().is_none());
+        let hash_map = items
+            .iter()
+            .find(|it| it.label() == "HashMap (use std::collections::HashMap)")
+            .unwrap();
+        assert!(hash_map.import_edit().is_none());
+
+        let make_map = analysis.resolve_completion(position, &config, "make_map").unwrap().unwrap();
+        assert_eq!(make_map.detail(), Some("fn make_map() -> u32"));
+        assert_eq!(make_map.documentation().unwrap().as_str(), "Makes a map.");
+        let hash_map = analysis
+            .resolve_completion(position, &config, "HashMap (use std::collections::HashMap)")
+            .unwrap()
+            .unwrap();
+        let import_indels = hash_map.import_edit().unwrap().as_indels();
+        assert!(import_indels[0].insert.starts_with("use std::collections::HashMap;"));
+    }
+
+    #[test]
+    fn does_not_complete_imported_items_to_import() {
+        let completions = do_completion(
+            r"
+            //- /main.rs
+            use std::collections::HashMap;
+            fn main() {
+                let _ = HashM<|>
+            }
+            //- /std/lib.rs
+            pub mod collections {
+                pub struct HashMap;
+            }
+            ",
+            CompletionKind::Magic,
+        );
+        assert!(completions.is_empty());
+    }
 }
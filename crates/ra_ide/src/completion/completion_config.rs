@@ -7,9 +7,14 @@
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CompletionConfig {
     pub enable_postfix_completions: bool,
+    pub enable_autoimport_completions: bool,
     pub add_call_parenthesis: bool,
     pub add_call_argument_snippets: bool,
     pub snippet_cap: Option<SnippetCap>,
+    /// Leave the detail, documentation and `use` edit of the items to
+    /// `Analysis::resolve_completion`, for clients which resolve the item they
+    /// pick.
+    pub resolve_lazily: bool,
 }
 
 impl CompletionConfig {
@@ -27,9 +32,11 @@ impl Default for CompletionConfig {
     fn default() -> Self {
         CompletionConfig {
             enable_postfix_completions: true,
+            enable_autoimport_completions: true,
             add_call_parenthesis: true,
             add_call_argument_snippets: true,
             snippet_cap: Some(SnippetCap { _private: () }),
+            resolve_lazily: false,
         }
     }
 }
@@ -1,15 +1,17 @@
 //! FIXME: write short doc here
 
-use hir::{Semantics, SemanticsScope, Type};
+use hir::{CallableDef, EnumVariant, Field, Function, Semantics, SemanticsScope, Struct, Type};
 use ra_db::SourceDatabase;
 use ra_ide_db::RootDatabase;
 use ra_syntax::{
     algo::{find_covering_element, find_node_at_offset},
-    ast, match_ast, AstNode,
+    ast::{self, NameOwner, TypeAscriptionOwner},
+    match_ast, AstNode,
     SyntaxKind::*,
     SyntaxNode, SyntaxToken, TextRange, TextSize,
 };
 use ra_text_edit::Indel;
+use test_utils::tested_by;
 
 use crate::{call_info::ActiveParameter, completion::CompletionConfig, FilePosition};
 
@@ -34,8 +36,10 @@ pub(crate) struct CompletionContext<'a> {
     pub(super) record_pat_syntax: Option<ast::RecordPat>,
     pub(super) record_field_syntax: Option<ast::RecordField>,
     pub(super) impl_def: Option<ast::ImplDef>,
-    /// FIXME: `ActiveParameter` is string-based, which is very wrong
     pub(super) active_parameter: Option<ActiveParameter>,
+    /// The field, parameter or binding the completed expression is assigned
+    /// to, used to rank the completions.
+    pub(super) expected_value: Option<ExpectedValue>,
     pub(super) is_param: bool,
     /// If a name-binding or reference to a const in a pattern.
     /// Irrefutable patterns (like let) are excluded.
@@ -103,6 +107,7 @@ impl<'a> CompletionContext<'a> {
             record_field_syntax: None,
             impl_def: None,
             active_parameter: ActiveParameter::at(db, position),
+            expected_value: None,
             is_param: false,
             is_pat_binding_or_const: false,
             is_trivial_path: false,
@@ -160,6 +165,7 @@ impl<'a> CompletionContext<'a> {
         }
 
         ctx.fill(&original_file, hypothetical_file, offset);
+        ctx.expected_value = ctx.compute_expected_value();
         Some(ctx)
     }
 
@@ -174,7 +180,142 @@ impl<'a> CompletionContext<'a> {
     }
 
     pub(crate) fn scope(&self) -> SemanticsScope<'_, RootDatabase> {
-        self.sema.scope_at_offset(&self.token.parent(), self.offset)
+        self.sema.scope_at_offset(&self.token.parent(), self.expanded_offset())
+    }
+
+    /// The offset of the cursor in the file `self.token` comes from, which is
+    /// a macro expansion if the cursor is inside a macro call.
+    fn expanded_offset(&self) -> TextSize {
+        let original_range = self.original_token.text_range();
+        let range = self.token.text_range();
+        let offset = range.start() + (self.offset - original_range.start());
+        offset.min(range.end())
+    }
+
+    fn compute_expected_value(&self) -> Option<ExpectedValue> {
+        if let Some(record_field) = &self.record_field_syntax {
+            tested_by!(test_struct_field_completion_in_record_lit);
+            let (field, _local) = self.sema.resolve_record_field(record_field)?;
+            let name = Some(field.name(self.db).to_string());
+            return ExpectedValue::new(name, field.signature_ty(self.db));
+        }
+        if let Some(active_parameter) = &self.active_parameter {
+            tested_by!(test_struct_field_completion_in_func_call);
+            let ty = self.active_parameter_type()?;
+            return ExpectedValue::new(Some(active_parameter.name.clone()), ty);
+        }
+
+        // Go up through the nodes the completed expression is the value of,
+        // until the one which says what the value is for.
+        let token_range = self.token.text_range();
+        let after = |token: Option<SyntaxToken>| {
+            token.map_or(false, |it| it.text_range().end() <= token_range.end())
+        };
+        for node in self.token.parent().ancestors() {
+            match_ast! {
+                match node {
+                    ast::LetStmt(it) => {
+                        it.ascribed_type()?;
+                        if !after(it.eq_token()) {
+                            return None;
+                        }
+                        let pat = it.pat()?;
+                        let name = match &pat {
+                            ast::Pat::BindPat(it) => it.name().map(|it| it.text().to_string()),
+                            _ => None,
+                        };
+                        return ExpectedValue::new(name, self.sema.type_of_pat(&pat)?);
+                    },
+                    ast::ReturnExpr(_) => return self.expected_return_value(&node),
+                    ast::FnDef(_) => return self.expected_return_value(&node),
+                    ast::BlockExpr(it) => {
+                        // Only the tail expression is the value of the block.
+                        let in_tail = it
+                            .statements()
+                            .all(|stmt| stmt.syntax().text_range().end() <= token_range.start());
+                        if !in_tail {
+                            return None;
+                        }
+                    },
+                    ast::MatchArm(it) => if !after(it.fat_arrow_token()) { return None },
+                    ast::FieldExpr(it) => if !after(it.dot_token()) { return None },
+                    ast::MethodCallExpr(it) => if !after(it.dot_token()) { return None },
+                    ast::MatchExpr(it) => {
+                        let arms = it.match_arm_list()?;
+                        if !arms.syntax().text_range().contains_range(token_range) {
+                            return None;
+                        }
+                    },
+                    ast::PathExpr(_) => (),
+                    ast::Path(_) => (),
+                    ast::PathSegment(_) => (),
+                    ast::NameRef(_) => (),
+                    ast::ParenExpr(_) => (),
+                    ast::IfExpr(it) => {
+                        // Only the branches are the value of the `if`, the
+                        // condition is a `bool`.
+                        let in_condition = it.then_branch().map_or(true, |it| {
+                            token_range.end() <= it.syntax().text_range().start()
+                        });
+                        if in_condition {
+                            return None;
+                        }
+                    },
+                    ast::MatchArmList(_) => (),
+                    _ => return None,
+                }
+            }
+        }
+        None
+    }
+
+    /// The return value of the function or closure containing `node`.
+    fn expected_return_value(&self, node: &SyntaxNode) -> Option<ExpectedValue> {
+        let fn_def = node.ancestors().find_map(|it| {
+            match_ast! {
+                match it {
+                    ast::FnDef(it) => Some(Some(it)),
+                    ast::LambdaExpr(_) => Some(None),
+                    _ => None,
+                }
+            }
+        })??;
+        let function = self.sema.to_def(&fn_def)?;
+        ExpectedValue::new(None, function.ret_type(self.db))
+    }
+
+    /// The type of the parameter the argument at the cursor is passed to.
+    fn active_parameter_type(&self) -> Option<Type> {
+        let token_range = self.token.text_range();
+        let arg_list = self.token.parent().ancestors().find_map(ast::ArgList::cast)?;
+        let idx = arg_list
+            .args()
+            .take_while(|arg| arg.syntax().text_range().end() < token_range.start())
+            .count();
+        let call = arg_list.syntax().parent()?;
+        let params = match_ast! {
+            match call {
+                ast::CallExpr(it) => match self.sema.type_of_expr(&it.expr()?)?.as_callable()? {
+                    CallableDef::FunctionId(it) => Function::from(it).param_types(self.db),
+                    CallableDef::StructId(it) => {
+                        field_types(self.db, Struct::from(it).fields(self.db))
+                    }
+                    CallableDef::EnumVariantId(it) => {
+                        field_types(self.db, EnumVariant::from(it).fields(self.db))
+                    }
+                },
+                // The receiver is passed as `self`.
+                ast::MethodCallExpr(it) => {
+                    let mut params = self.sema.resolve_method_call(&it)?.param_types(self.db);
+                    if !params.is_empty() {
+                        params.remove(0);
+                    }
+                    params
+                },
+                _ => return None,
+            }
+        };
+        params.into_iter().nth(idx)
     }
 
     fn fill(
@@ -395,6 +536,28 @@ impl<'a> CompletionContext<'a> {
     }
 }
 
+/// What the completed expression is assigned to.
+#[derive(Debug)]
+pub(crate) struct ExpectedValue {
+    /// The name of the field, parameter or binding, if any.
+    pub(crate) name: Option<String>,
+    pub(crate) ty: Type,
+}
+
+impl ExpectedValue {
+    fn new(name: Option<String>, ty: Type) -> Option<ExpectedValue> {
+        // Everything can be the last expression of a block returning `()`.
+        if ty.is_unknown() || ty.is_unit() {
+            return None;
+        }
+        Some(ExpectedValue { name, ty })
+    }
+}
+
+fn field_types(db: &RootDatabase, fields: Vec<Field>) -> Vec<Type> {
+    fields.into_iter().map(|it| it.signature_ty(db)).collect()
+}
+
 fn find_node_with_range<N: AstNode>(syntax: &SyntaxNode, range: TextRange) -> Option<N> {
     find_covering_element(syntax, range).ancestors().find_map(N::cast)
 }
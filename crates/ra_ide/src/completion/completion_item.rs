@@ -2,11 +2,14 @@
 
 use std::fmt;
 
-use super::completion_config::SnippetCap;
-use hir::Documentation;
+use super::{complete_unqualified_path::import_edit, completion_config::SnippetCap};
+use hir::{AttrDef, Documentation, Type};
+use ra_ide_db::RootDatabase;
 use ra_syntax::TextRange;
 use ra_text_edit::TextEdit;
 
+use crate::FilePosition;
+
 /// `CompletionItem` describes a single completion variant in the editor pop-up.
 /// It is basically a POD with various properties. To construct a
 /// `CompletionItem`, use `new` method and the `Builder` struct.
@@ -45,6 +48,8 @@ pub struct CompletionItem {
     /// Additional info to show in the UI pop up.
     detail: Option<String>,
     documentation: Option<Documentation>,
+    /// Where the detail and documentation come from, until they are computed.
+    details: Option<CompletionDetails>,
 
     /// Whether this item is marked as deprecated
     deprecated: bool,
@@ -55,6 +60,27 @@ pub struct CompletionItem {
 
     /// Score is useful to pre select or display in better order completion items
     score: Option<CompletionScore>,
+
+    /// For an item not in scope yet, the path of the `use` inserted for it.
+    import_path: Option<String>,
+    /// The edit inserting that `use`, which goes with `text_edit`.
+    import_edit: Option<TextEdit>,
+}
+
+/// The definition an item completes, which its detail and documentation are
+/// computed from. This is deferred so that clients which resolve the items
+/// they pick don't pay for the others.
+#[derive(Debug, Clone)]
+pub(crate) enum CompletionDetails {
+    /// Only the documentation of a definition.
+    Docs(AttrDef),
+    /// A type, with the documentation of the definition it belongs to.
+    Type(Type, Option<AttrDef>),
+    Function(hir::Function),
+    Macro(hir::MacroDef),
+    Const(hir::Const),
+    TypeAlias(hir::TypeAlias),
+    EnumVariant(hir::EnumVariant),
 }
 
 // We use custom debug for CompletionItem to make `insta`'s diffs more readable.
@@ -159,6 +185,7 @@ impl CompletionItem {
             insert_text_format: InsertTextFormat::PlainText,
             detail: None,
             documentation: None,
+            details: None,
             lookup: None,
             kind: None,
             text_edit: None,
@@ -211,6 +238,47 @@ impl CompletionItem {
     pub fn trigger_call_info(&self) -> bool {
         self.trigger_call_info
     }
+
+    /// The path of the `use` inserted for an item which is not in scope yet.
+    pub fn import_path(&self) -> Option<&str> {
+        self.import_path.as_deref()
+    }
+
+    /// The edit inserting the `use` for an item which is not in scope yet.
+    pub fn import_edit(&self) -> Option<&TextEdit> {
+        self.import_edit.as_ref()
+    }
+
+    /// Makes the completion of an item which is not in scope yet also insert
+    /// the `use` for it, once the item is resolved.
+    pub(crate) fn with_import(mut self, import_path: &str) -> Self {
+        if self.lookup.is_none() {
+            self.lookup = Some(self.label.clone());
+        }
+        self.label = format!("{} (use {})", self.label, import_path);
+        self.completion_kind = CompletionKind::Magic;
+        self.import_path = Some(import_path.to_string());
+        self
+    }
+
+    /// Computes the detail, the documentation and the edit inserting the `use`,
+    /// which are left out when the item is made. Returns `false` if the `use`
+    /// can't be inserted without touching the completed text.
+    pub(crate) fn resolve(&mut self, db: &RootDatabase, position: FilePosition) -> bool {
+        if let Some(details) = self.details.take() {
+            let (detail, documentation) = details.compute(db);
+            self.detail = self.detail.take().or(detail);
+            self.documentation = self.documentation.take().or(documentation);
+        }
+        if let (Some(import_path), None) = (&self.import_path, &self.import_edit) {
+            let source_range = self.source_range;
+            self.import_edit = import_edit(db, position, import_path).filter(|edit| {
+                edit.as_indels().iter().all(|it| it.delete.intersect(source_range).is_none())
+            });
+            return self.import_edit.is_some();
+        }
+        true
+    }
 }
 
 /// A helper to make `CompletionItem`s.
@@ -223,6 +291,7 @@ pub(crate) struct Builder {
     insert_text_format: InsertTextFormat,
     detail: Option<String>,
     documentation: Option<Documentation>,
+    details: Option<CompletionDetails>,
     lookup: Option<String>,
     kind: Option<CompletionItemKind>,
     text_edit: Option<TextEdit>,
@@ -253,12 +322,15 @@ impl Builder {
             text_edit,
             detail: self.detail,
             documentation: self.documentation,
+            details: self.details,
             lookup: self.lookup,
             kind: self.kind,
             completion_kind: self.completion_kind,
             deprecated: self.deprecated.unwrap_or(false),
             trigger_call_info: self.trigger_call_info.unwrap_or(false),
             score: self.score,
+            import_path: None,
+            import_edit: None,
         }
     }
     pub(crate) fn lookup_by(mut self, lookup: impl Into<String>) -> Builder {
@@ -309,6 +381,12 @@ impl Builder {
         self.documentation = docs.map(Into::into);
         self
     }
+    /// Sets where the detail and documentation come from, see
+    /// `CompletionItem::resolve`.
+    pub(crate) fn details(mut self, details: CompletionDetails) -> Builder {
+        self.details = Some(details);
+        self
+    }
     pub(crate) fn set_deprecated(mut self, deprecated: bool) -> Builder {
         self.deprecated = Some(deprecated);
         self
@@ -346,6 +424,13 @@ impl Completions {
     {
         items.into_iter().for_each(|item| self.add(item.into()))
     }
+    /// Resolves all the items, dropping those whose `use` can't be inserted.
+    pub(crate) fn resolve_all(&mut self, db: &RootDatabase, position: FilePosition) {
+        self.buf = std::mem::take(&mut self.buf)
+            .into_iter()
+            .filter_map(|mut item| if item.resolve(db, position) { Some(item) } else { None })
+            .collect();
+    }
 }
 
 impl Into<Vec<CompletionItem>> for Completions {
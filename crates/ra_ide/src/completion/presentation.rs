@@ -1,14 +1,16 @@
 //! This modules takes care of rendering various definitions as completion items.
 
-use hir::{Docs, HasAttrs, HasSource, HirDisplay, ModPath, ScopeDef, StructKind, Type};
+use hir::{
+    Docs, Documentation, HasAttrs, HasSource, HirDisplay, ModPath, ScopeDef, StructKind, Type,
+};
 use ra_syntax::ast::NameOwner;
 use stdx::SepBy;
 use test_utils::tested_by;
 
 use crate::{
     completion::{
-        completion_item::Builder, CompletionContext, CompletionItem, CompletionItemKind,
-        CompletionKind, Completions,
+        completion_item::{Builder, CompletionDetails},
+        CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
     },
     display::{const_label, macro_label, type_label, FunctionSignature},
     CompletionScore, RootDatabase,
@@ -17,16 +19,14 @@ use crate::{
 impl Completions {
     pub(crate) fn add_field(&mut self, ctx: &CompletionContext, field: hir::Field, ty: &Type) {
         let is_deprecated = is_deprecated(field, ctx.db);
-        let ty = ty.display(ctx.db).to_string();
         let name = field.name(ctx.db);
         let mut completion_item =
             CompletionItem::new(CompletionKind::Reference, ctx.source_range(), name.to_string())
                 .kind(CompletionItemKind::Field)
-                .detail(ty.clone())
-                .set_documentation(field.docs(ctx.db))
+                .details(CompletionDetails::Type(ty.clone(), Some(field.into())))
                 .set_deprecated(is_deprecated);
 
-        if let Some(score) = compute_score(ctx, &ty, &name.to_string()) {
+        if let Some(score) = compute_score(ctx, ty, &name.to_string()) {
             completion_item = completion_item.set_score(score);
         }
 
@@ -36,7 +36,7 @@ impl Completions {
     pub(crate) fn add_tuple_field(&mut self, ctx: &CompletionContext, field: usize, ty: &Type) {
         CompletionItem::new(CompletionKind::Reference, ctx.source_range(), field.to_string())
             .kind(CompletionItemKind::Field)
-            .detail(ty.display(ctx.db).to_string())
+            .details(CompletionDetails::Type(ty.clone(), None))
             .add_to(self);
     }
 
@@ -87,14 +87,14 @@ impl Completions {
             }
         };
 
-        let docs = match resolution {
-            ScopeDef::ModuleDef(Module(it)) => it.docs(ctx.db),
-            ScopeDef::ModuleDef(Adt(it)) => it.docs(ctx.db),
-            ScopeDef::ModuleDef(EnumVariant(it)) => it.docs(ctx.db),
-            ScopeDef::ModuleDef(Const(it)) => it.docs(ctx.db),
-            ScopeDef::ModuleDef(Static(it)) => it.docs(ctx.db),
-            ScopeDef::ModuleDef(Trait(it)) => it.docs(ctx.db),
-            ScopeDef::ModuleDef(TypeAlias(it)) => it.docs(ctx.db),
+        let docs_def: Option<hir::AttrDef> = match resolution {
+            ScopeDef::ModuleDef(Module(it)) => Some((*it).into()),
+            ScopeDef::ModuleDef(Adt(it)) => Some((*it).into()),
+            ScopeDef::ModuleDef(EnumVariant(it)) => Some((*it).into()),
+            ScopeDef::ModuleDef(Const(it)) => Some((*it).into()),
+            ScopeDef::ModuleDef(Static(it)) => Some((*it).into()),
+            ScopeDef::ModuleDef(Trait(it)) => Some((*it).into()),
+            ScopeDef::ModuleDef(TypeAlias(it)) => Some((*it).into()),
             _ => None,
         };
 
@@ -103,7 +103,10 @@ impl Completions {
         if let ScopeDef::Local(local) = resolution {
             let ty = local.ty(ctx.db);
             if !ty.is_unknown() {
-                completion_item = completion_item.detail(ty.display(ctx.db).to_string());
+                if let Some(score) = compute_score(ctx, &ty, &local_name) {
+                    completion_item = completion_item.set_score(score);
+                }
+                completion_item = completion_item.details(CompletionDetails::Type(ty, None));
             }
         };
 
@@ -125,7 +128,10 @@ impl Completions {
             }
         }
 
-        completion_item.kind(kind).set_documentation(docs).add_to(self)
+        if let Some(def) = docs_def {
+            completion_item = completion_item.details(CompletionDetails::Docs(def));
+        }
+        completion_item.kind(kind).add_to(self)
     }
 
     pub(crate) fn add_macro(
@@ -145,24 +151,19 @@ impl Completions {
             None => return,
         };
 
-        let ast_node = macro_.source(ctx.db).value;
-        let detail = macro_label(&ast_node);
-
-        let docs = macro_.docs(ctx.db);
-
         let mut builder = CompletionItem::new(
             CompletionKind::Reference,
             ctx.source_range(),
             &format!("{}!", name),
         )
         .kind(CompletionItemKind::Macro)
-        .set_documentation(docs.clone())
         .set_deprecated(is_deprecated(macro_, ctx.db))
-        .detail(detail);
+        .details(CompletionDetails::Macro(macro_));
 
         let needs_bang = ctx.use_item_syntax.is_none() && !ctx.is_macro_call;
         builder = match ctx.config.snippet_cap {
             Some(cap) if needs_bang => {
+                let docs = macro_.docs(ctx.db);
                 let docs = docs.as_ref().map_or("", |s| s.as_str());
                 let (bra, ket) = guess_macro_braces(&name, docs);
                 builder
@@ -198,9 +199,8 @@ impl Completions {
                 } else {
                     CompletionItemKind::Function
                 })
-                .set_documentation(func.docs(ctx.db))
                 .set_deprecated(is_deprecated(func, ctx.db))
-                .detail(function_signature.to_string());
+                .details(CompletionDetails::Function(func));
 
         let params = function_signature
             .parameter_names
@@ -209,6 +209,10 @@ impl Completions {
             .cloned()
             .collect();
 
+        if let Some(score) = compute_score(ctx, &func.ret_type(ctx.db), &name) {
+            builder = builder.set_score(score);
+        }
+
         builder = builder.add_call_parens(ctx, name, Params::Named(params));
 
         self.add(builder)
@@ -220,13 +224,11 @@ impl Completions {
             Some(name) => name,
             _ => return,
         };
-        let detail = const_label(&ast_node);
 
         CompletionItem::new(CompletionKind::Reference, ctx.source_range(), name.text().to_string())
             .kind(CompletionItemKind::Const)
-            .set_documentation(constant.docs(ctx.db))
             .set_deprecated(is_deprecated(constant, ctx.db))
-            .detail(detail)
+            .details(CompletionDetails::Const(constant))
             .add_to(self);
     }
 
@@ -236,13 +238,11 @@ impl Completions {
             Some(name) => name,
             _ => return,
         };
-        let detail = type_label(&type_def);
 
         CompletionItem::new(CompletionKind::Reference, ctx.source_range(), name.text().to_string())
             .kind(CompletionItemKind::TypeAlias)
-            .set_documentation(type_alias.docs(ctx.db))
             .set_deprecated(is_deprecated(type_alias, ctx.db))
-            .detail(detail)
+            .details(CompletionDetails::TypeAlias(type_alias))
             .add_to(self);
     }
 
@@ -277,32 +277,20 @@ impl Completions {
             Some(it) => it.to_string(),
             None => name.to_string(),
         };
-        let detail_types = variant
-            .fields(ctx.db)
-            .into_iter()
-            .map(|field| (field.name(ctx.db), field.signature_ty(ctx.db)));
         let variant_kind = variant.kind(ctx.db);
-        let detail = match variant_kind {
-            StructKind::Tuple | StructKind::Unit => detail_types
-                .map(|(_, t)| t.display(ctx.db).to_string())
-                .sep_by(", ")
-                .surround_with("(", ")")
-                .to_string(),
-            StructKind::Record => detail_types
-                .map(|(n, t)| format!("{}: {}", n, t.display(ctx.db).to_string()))
-                .sep_by(", ")
-                .surround_with("{ ", " }")
-                .to_string(),
-        };
         let mut res = CompletionItem::new(
             CompletionKind::Reference,
             ctx.source_range(),
             qualified_name.clone(),
         )
         .kind(CompletionItemKind::EnumVariant)
-        .set_documentation(variant.docs(ctx.db))
         .set_deprecated(is_deprecated)
-        .detail(detail);
+        .details(CompletionDetails::EnumVariant(variant));
+
+        let enum_type = hir::Adt::from(variant.parent_enum(ctx.db)).ty(ctx.db);
+        if let Some(score) = compute_score(ctx, &enum_type, &name) {
+            res = res.set_score(score);
+        }
 
         if path.is_some() {
             res = res.lookup_by(name);
@@ -317,36 +305,63 @@ impl Completions {
     }
 }
 
+impl CompletionDetails {
+    /// Computes the detail and the documentation of an item.
+    pub(crate) fn compute(&self, db: &RootDatabase) -> (Option<String>, Option<Documentation>) {
+        match self {
+            CompletionDetails::Docs(def) => (None, def.docs(db)),
+            CompletionDetails::Type(ty, def) => {
+                (Some(ty.display(db).to_string()), def.and_then(|it| it.docs(db)))
+            }
+            CompletionDetails::Function(it) => {
+                let signature = FunctionSignature::from(&it.source(db).value);
+                (Some(signature.to_string()), it.docs(db))
+            }
+            CompletionDetails::Macro(it) => (Some(macro_label(&it.source(db).value)), it.docs(db)),
+            CompletionDetails::Const(it) => (Some(const_label(&it.source(db).value)), it.docs(db)),
+            CompletionDetails::TypeAlias(it) => {
+                (Some(type_label(&it.source(db).value)), it.docs(db))
+            }
+            CompletionDetails::EnumVariant(it) => (Some(variant_detail(db, *it)), it.docs(db)),
+        }
+    }
+}
+
+fn variant_detail(db: &RootDatabase, variant: hir::EnumVariant) -> String {
+    let detail_types =
+        variant.fields(db).into_iter().map(|field| (field.name(db), field.signature_ty(db)));
+    match variant.kind(db) {
+        StructKind::Tuple | StructKind::Unit => detail_types
+            .map(|(_, t)| t.display(db).to_string())
+            .sep_by(", ")
+            .surround_with("(", ")")
+            .to_string(),
+        StructKind::Record => detail_types
+            .map(|(n, t)| format!("{}: {}", n, t.display(db).to_string()))
+            .sep_by(", ")
+            .surround_with("{ ", " }")
+            .to_string(),
+    }
+}
+
 pub(crate) fn compute_score(
     ctx: &CompletionContext,
     // FIXME: this definitely should be a `Type`
-    ty: &str,
+    ty: &Type,
     name: &str,
 ) -> Option<CompletionScore> {
-    let (active_name, active_type) = if let Some(record_field) = &ctx.record_field_syntax {
-        tested_by!(test_struct_field_completion_in_record_lit);
-        let (struct_field, _local) = ctx.sema.resolve_record_field(record_field)?;
-        (
-            struct_field.name(ctx.db).to_string(),
-            struct_field.signature_ty(ctx.db).display(ctx.db).to_string(),
-        )
-    } else if let Some(active_parameter) = &ctx.active_parameter {
-        tested_by!(test_struct_field_completion_in_func_call);
-        (active_parameter.name.clone(), active_parameter.ty.clone())
-    } else {
-        return None;
-    };
+    let expected = ctx.expected_value.as_ref()?;
 
     // Compute score
     // For the same type
-    if &active_type != ty {
+    if !expected.ty.is_same_type(ty) {
         return None;
     }
 
     let mut res = CompletionScore::TypeMatch;
 
     // If same type + same name then go top position
-    if active_name == name {
+    if expected.name.as_deref() == Some(name) {
         res = CompletionScore::TypeAndNameMatch
     }
 
@@ -1405,4 +1420,71 @@ mod tests {
         "###
         );
     }
+
+    fn check_scores(ra_fixture: &str, expected: &[&str]) {
+        let completions = do_reference_completion(ra_fixture);
+        let actual = completions
+            .iter()
+            .filter_map(|it| Some(format!("{} {:?}", it.label(), it.score()?)))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn scores_items_of_let_type() {
+        check_scores(
+            r"
+            struct Foo;
+            fn make_foo() -> Foo { Foo }
+            fn main() {
+                let foo = Foo;
+                let bar = 92;
+                let foo: Foo = <|>
+            }
+            ",
+            &["foo TypeAndNameMatch", "make_foo() TypeMatch"],
+        );
+    }
+
+    #[test]
+    fn scores_items_of_return_type() {
+        check_scores(
+            r"
+            fn zero() -> u32 { 0 }
+            fn f(n: u32, s: &str) -> u32 {
+                if true { <|> } else { 0 }
+            }
+            ",
+            &["f(…) TypeMatch", "n TypeMatch", "zero() TypeMatch"],
+        );
+    }
+
+    #[test]
+    fn scores_items_of_same_type_not_same_name() {
+        check_scores(
+            r"
+            mod a { pub struct S; }
+            mod b { pub struct S; }
+            fn make_a() -> a::S { a::S }
+            fn make_b() -> b::S { b::S }
+            fn main() {
+                let s: a::S = <|>
+            }
+            ",
+            &["make_a() TypeMatch"],
+        );
+    }
+
+    #[test]
+    fn no_scores_in_if_condition() {
+        check_scores(
+            r"
+            fn is_ok() -> bool { true }
+            fn f(b: bool) -> bool {
+                if <|> { true } else { false }
+            }
+            ",
+            &[],
+        );
+    }
 }
@@ -6,22 +6,34 @@
 
 use std::cell::RefCell;
 
+use either::Either;
 use hir::{
     diagnostics::{AstDiagnostic, Diagnostic as _, DiagnosticSink},
-    Semantics,
+    AsAssocItem, AssocItemContainer, MethodViolationCode, ModuleDef, ObjectSafetyViolation,
+    PathResolution, Semantics,
 };
 use itertools::Itertools;
+use ra_assists::utils::insert_use_statement;
 use ra_db::{RelativePath, SourceDatabase, SourceDatabaseExt};
-use ra_ide_db::RootDatabase;
+use ra_ide_db::{
+    doc_links::{block_doc_links, doc_blocks, documented_def, resolve_doc_path},
+    imports_locator::ImportsLocator,
+    RootDatabase,
+};
 use ra_prof::profile;
 use ra_syntax::{
     algo,
-    ast::{self, make, AstNode},
+    ast::{self, make, AstNode, TypeBoundsOwner},
     SyntaxNode, TextRange, T,
 };
 use ra_text_edit::{TextEdit, TextEditBuilder};
+use rustc_hash::FxHashSet;
+use stdx::format_to;
 
-use crate::{Diagnostic, FileId, FileSystemEdit, SourceChange, SourceFileEdit};
+use crate::{
+    display::ToNav, Diagnostic, FileId, FileSystemEdit, NavigationTarget, SourceChange,
+    SourceFileEdit,
+};
 
 #[derive(Debug, Copy, Clone)]
 pub enum Severity {
@@ -39,12 +51,17 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
         range: err.range(),
         message: format!("Syntax Error: {}", err),
         severity: Severity::Error,
+        related: Vec::new(),
         fix: None,
     }));
 
     for node in parse.tree().syntax().descendants() {
         check_unnecessary_braces_in_use_statement(&mut res, file_id, &node);
         check_struct_shorthand_initialization(&mut res, file_id, &node);
+        check_object_safety(&mut res, &sema, &node);
+    }
+    for block in doc_blocks(parse.tree().syntax()) {
+        check_doc_links(&mut res, &sema, &block);
     }
     let res = RefCell::new(res);
     let mut sink = DiagnosticSink::new(|d| {
@@ -52,6 +69,7 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             message: d.message(),
             range: sema.diagnostics_range(d).range,
             severity: Severity::Error,
+            related: Vec::new(),
             fix: None,
         })
     })
@@ -69,6 +87,7 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             range: sema.diagnostics_range(d).range,
             message: d.message(),
             severity: Severity::Error,
+            related: Vec::new(),
             fix: Some(fix),
         })
     })
@@ -102,6 +121,7 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             range: sema.diagnostics_range(d).range,
             message: d.message(),
             severity: Severity::Error,
+            related: Vec::new(),
             fix,
         })
     })
@@ -110,6 +130,7 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             range: sema.diagnostics_range(d).range,
             message: d.message(),
             severity: Severity::Error,
+            related: Vec::new(),
             fix: None,
         })
     })
@@ -122,8 +143,63 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             range: sema.diagnostics_range(d).range,
             message: d.message(),
             severity: Severity::Error,
+            related: Vec::new(),
             fix: Some(fix),
         })
+    })
+    .on::<hir::diagnostics::ConflictingImpls, _>(|d| {
+        let conflicting = NavigationTarget::from_ast_ptr(db, d.conflicting.clone(), "impl".into());
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            severity: Severity::Error,
+            related: vec![(conflicting, "first implementation here".to_string())],
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::OrphanImpl, _>(|d| {
+        let trait_name = d.trait_name.to_string();
+        let trait_ =
+            NavigationTarget::from_ast_ptr(db, d.trait_.clone(), trait_name.as_str().into());
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            severity: Severity::Error,
+            related: vec![(trait_, format!("`{}` is defined here", trait_name))],
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::UnreachableCode, _>(|d| {
+        // Only highlight the dead statements themselves when the block is
+        // written directly in the file, not produced by a macro.
+        let range = if d.file == file_id.into() {
+            unreachable_code_range(&d.ast(db), d.first_stmt)
+        } else {
+            None
+        };
+        res.borrow_mut().push(Diagnostic {
+            range: range.unwrap_or_else(|| sema.diagnostics_range(d).range),
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            related: Vec::new(),
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::UnresolvedMethodCall, _>(|d| {
+        // We can only compute the fix for calls written directly in the file.
+        let fix = if d.file == file_id.into() {
+            let call = d.call.to_node(sema.parse(file_id).syntax());
+            import_trait_for_method_fix(&sema, file_id, &call)
+        } else {
+            None
+        };
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            related: Vec::new(),
+            fix,
+        })
     });
     if let Some(m) = sema.to_module_def(file_id) {
         m.diagnostics(db, &mut sink);
@@ -132,6 +208,63 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
     res.into_inner()
 }
 
+/// Range from the first unreachable statement (or the tail expression) of
+/// `block` to its end.
+fn unreachable_code_range(block: &ast::BlockExpr, first_stmt: usize) -> Option<TextRange> {
+    let mut dead = block
+        .statements()
+        .skip(first_stmt)
+        .map(|it| it.syntax().text_range())
+        .chain(block.expr().map(|it| it.syntax().text_range()));
+    let first = dead.next()?;
+    Some(dead.fold(first, |acc, it| acc.cover(it)))
+}
+
+/// Offers to import a trait that would make the unresolved method `call`
+/// resolve.
+fn import_trait_for_method_fix(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    call: &ast::MethodCallExpr,
+) -> Option<SourceChange> {
+    let db = sema.db;
+    let receiver = sema.type_of_expr(&call.expr()?)?;
+    let name = call.name_ref()?.text().clone();
+    let module = sema.scope(call.syntax()).module()?;
+    let krate = module.krate();
+
+    let trait_path = ImportsLocator::new(db)
+        .find_imports(&name)
+        .into_iter()
+        .filter_map(|candidate| match candidate {
+            Either::Left(ModuleDef::Function(f)) => match f.as_assoc_item(db)?.container(db) {
+                AssocItemContainer::Trait(it) => Some(it),
+                AssocItemContainer::ImplDef(_) => None,
+            },
+            _ => None,
+        })
+        .filter(|&trait_| {
+            let mut traits_in_scope = FxHashSet::default();
+            traits_in_scope.insert(trait_.into());
+            receiver
+                .iterate_method_candidates(db, krate, &traits_in_scope, None, |_, f| {
+                    match f.as_assoc_item(db)?.container(db) {
+                        AssocItemContainer::Trait(it) if it == trait_ => Some(()),
+                        _ => None,
+                    }
+                })
+                .is_some()
+        })
+        .filter_map(|trait_| module.find_use_path(db, ModuleDef::Trait(trait_)))
+        .filter(|path| !path.segments.is_empty())
+        .min_by_key(|path| path.to_string())?;
+
+    let mut builder = TextEditBuilder::default();
+    insert_use_statement(call.syntax(), &trait_path, sema, &mut builder);
+    let label = format!("Import `{}`", trait_path);
+    Some(SourceChange::source_file_edit_from(label, file_id, builder.finish()))
+}
+
 fn check_unnecessary_braces_in_use_statement(
     acc: &mut Vec<Diagnostic>,
     file_id: FileId,
@@ -154,6 +287,7 @@ fn check_unnecessary_braces_in_use_statement(
             range,
             message: "Unnecessary braces in use statement".to_string(),
             severity: Severity::WeakWarning,
+            related: Vec::new(),
             fix: Some(SourceChange::source_file_edit(
                 "Remove unnecessary braces",
                 SourceFileEdit { file_id, edit },
@@ -198,6 +332,7 @@ fn check_struct_shorthand_initialization(
                     range: record_field.syntax().text_range(),
                     message: "Shorthand struct initialization".to_string(),
                     severity: Severity::WeakWarning,
+                    related: Vec::new(),
                     fix: Some(SourceChange::source_file_edit(
                         "Use struct shorthand initialization",
                         SourceFileEdit { file_id, edit },
@@ -209,6 +344,103 @@ fn check_struct_shorthand_initialization(
     Some(())
 }
 
+fn check_object_safety(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let dyn_trait = ast::DynTraitType::cast(node.clone())?;
+    for bound in dyn_trait.type_bound_list()?.bounds() {
+        let path = match bound.type_ref() {
+            Some(ast::TypeRef::PathType(it)) => it.path(),
+            _ => None,
+        };
+        let trait_ = match path.and_then(|it| sema.resolve_path(&it)) {
+            Some(PathResolution::Def(ModuleDef::Trait(it))) => it,
+            _ => continue,
+        };
+        let violations = trait_.object_safety_violations(sema.db);
+        if violations.is_empty() {
+            continue;
+        }
+
+        let related: Vec<(NavigationTarget, String)> = violations
+            .into_iter()
+            .map(|violation| object_safety_violation_note(sema.db, violation))
+            .collect();
+        let mut message =
+            format!("the trait `{}` cannot be made into an object", trait_.name(sema.db));
+        for (_, note) in related.iter() {
+            format_to!(message, "\n- {}", note);
+        }
+        acc.push(Diagnostic {
+            range: dyn_trait.syntax().text_range(),
+            message,
+            severity: Severity::Error,
+            related,
+            fix: None,
+        });
+    }
+    Some(())
+}
+
+fn check_doc_links(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantics<RootDatabase>,
+    block: &[ast::Comment],
+) -> Option<()> {
+    let def = documented_def(sema, block.first()?)?;
+    for link in block_doc_links(block) {
+        if resolve_doc_path(sema.db, &def, &link.path).is_none() {
+            acc.push(Diagnostic {
+                range: link.range,
+                message: format!("unresolved link to `{}`", link.path),
+                severity: Severity::WeakWarning,
+                related: Vec::new(),
+                fix: None,
+            });
+        }
+    }
+    Some(())
+}
+
+fn object_safety_violation_note(
+    db: &RootDatabase,
+    violation: ObjectSafetyViolation,
+) -> (NavigationTarget, String) {
+    match violation {
+        ObjectSafetyViolation::SizedSelf(it) => {
+            (it.to_nav(db), format!("`{}` requires `Self: Sized`", it.name(db)))
+        }
+        ObjectSafetyViolation::SupertraitSelf(it) => (
+            it.to_nav(db),
+            format!("`{}` uses `Self` as a type parameter in a supertrait bound", it.name(db)),
+        ),
+        ObjectSafetyViolation::Method(it, code) => {
+            let name = it.name(db);
+            let note = match code {
+                MethodViolationCode::StaticMethod => {
+                    format!("associated function `{}` has no `self` parameter", name)
+                }
+                MethodViolationCode::ReferencesSelfInput => {
+                    format!("method `{}` references the `Self` type in its parameters", name)
+                }
+                MethodViolationCode::ReferencesSelfOutput => {
+                    format!("method `{}` references the `Self` type in its return type", name)
+                }
+                MethodViolationCode::Generic => {
+                    format!("method `{}` has generic type parameters", name)
+                }
+            };
+            (it.to_nav(db), note)
+        }
+        ObjectSafetyViolation::AssocConst(it) => {
+            let name = it.name(db).map(|it| it.to_string()).unwrap_or_default();
+            (it.to_nav(db), format!("it contains the associated const `{}`", name))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
@@ -632,6 +864,7 @@ mod tests {
                     },
                 ),
                 severity: Error,
+                related: [],
             },
         ]
         "###);
@@ -688,6 +921,7 @@ mod tests {
                     },
                 ),
                 severity: Error,
+                related: [],
             },
         ]
         "###);
@@ -791,4 +1025,238 @@ fn main() {
             check_struct_shorthand_initialization,
         );
     }
+
+    #[test]
+    fn test_object_safety_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r#"
+            #[lang = "sized"]
+            trait Sized {}
+
+            trait Foo {
+                const N: usize;
+                fn new() -> Self;
+                fn foo<T>(&self, t: T);
+                fn eq(&self, other: &Self) -> bool;
+                fn ok(&self) -> u32;
+                fn make() -> Self where Self: Sized;
+            }
+
+            fn f(_: &dyn Foo) {}
+        "#,
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq_text!(
+            "the trait `Foo` cannot be made into an object
+- it contains the associated const `N`
+- associated function `new` has no `self` parameter
+- method `foo` has generic type parameters
+- method `eq` references the `Self` type in its parameters",
+            diagnostics[0].message.as_str()
+        );
+        let related: Vec<String> =
+            diagnostics[0].related.iter().map(|(nav, _)| nav.name().to_string()).collect();
+        assert_eq!(related, ["N", "new", "foo", "eq"]);
+    }
+
+    #[test]
+    fn test_unresolved_doc_link_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r#"
+            mod foo {
+                pub struct Foo;
+            }
+            /// Uses [`foo::Foo`], [Bar] and [`Missing`].
+            struct Bar;
+        "#,
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "unresolved link to `Missing`");
+    }
+
+    #[test]
+    fn test_no_doc_link_diagnostic_in_doc_test() {
+        check_no_diagnostic(
+            r#"
+            //! Tests:
+            //!
+            //! ```
+            //! let x = v[Idx];
+            //! ```
+            mod foo {
+                //! [`Foo`] is in scope here.
+                pub struct Foo;
+            }
+            /// Indexes with `v[i]`:
+            ///
+            /// ```
+            /// # let (v, i) = (vec![1], 0);
+            /// assert_eq!(v[i], 1);
+            /// ```
+            struct Bar;
+        "#,
+        );
+    }
+
+    #[test]
+    fn test_object_safety_no_diagnostic() {
+        check_no_diagnostic(
+            r"
+            trait Foo {
+                type Item;
+                fn foo(&self, item: Self::Item);
+            }
+
+            fn f(_: &dyn Foo<Item = u32>) {}
+        ",
+        );
+    }
+
+    #[test]
+    fn test_conflicting_impls_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r#"
+            trait Foo {}
+            struct S;
+            impl Foo for S {}
+            impl<T> Foo for T {}
+        "#,
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq_text!(
+            "conflicting implementations of trait `Foo`",
+            diagnostics[0].message.as_str()
+        );
+        let related: Vec<String> =
+            diagnostics[0].related.iter().map(|(nav, _)| nav.name().to_string()).collect();
+        assert_eq!(related, ["impl"]);
+    }
+
+    #[test]
+    fn test_conflicting_impls_no_diagnostic() {
+        check_no_diagnostic(
+            r"
+            trait Foo {}
+            trait Bar {}
+            struct S;
+            impl Foo for S {}
+            impl<T: Bar> Foo for T {}
+            impl Foo for &S {}
+        ",
+        );
+    }
+
+    #[test]
+    fn test_unreachable_code_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r#"
+            fn f() -> u32 {
+                return 1;
+                let x = 92;
+                x
+            }
+        "#,
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq_text!("unreachable code", diagnostics[0].message.as_str());
+        assert!(matches!(diagnostics[0].severity, Severity::WeakWarning));
+        let text = analysis.file_text(file_id).unwrap();
+        assert_eq_text!("let x = 92;\n                x", &text[diagnostics[0].range]);
+    }
+
+    #[test]
+    fn test_unreachable_code_no_diagnostic() {
+        check_no_diagnostic(
+            r"
+            fn f(b: bool) -> u32 {
+                if b {
+                    return 1;
+                }
+                let x = loop {
+                    if b {
+                        break 2;
+                    }
+                };
+                x
+            }
+        ",
+        );
+    }
+
+    #[test]
+    fn test_unresolved_method_call_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r"
+            struct S;
+            fn f() {
+                S.foo();
+            }
+        ",
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq_text!(
+            "no method named `foo` found for type `S`",
+            diagnostics[0].message.as_str()
+        );
+        assert!(matches!(diagnostics[0].severity, Severity::WeakWarning));
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    #[test]
+    fn test_unresolved_method_call_imports_trait() {
+        check_apply_diagnostic_fix_from_position(
+            r"
+            //- /lib.rs
+            mod tr {
+                pub trait Foo {
+                    fn foo(&self);
+                }
+                impl Foo for u32 {
+                    fn foo(&self) {}
+                }
+            }
+            fn f() {
+                1u32.<|>foo();
+            }
+        ",
+            r"
+            use tr::Foo;
+
+            mod tr {
+                pub trait Foo {
+                    fn foo(&self);
+                }
+                impl Foo for u32 {
+                    fn foo(&self) {}
+                }
+            }
+            fn f() {
+                1u32.foo();
+            }
+        ",
+        );
+    }
+
+    #[test]
+    fn test_unresolved_method_call_no_diagnostic() {
+        check_no_diagnostic(
+            r"
+            trait Foo {
+                fn foo(&self);
+            }
+            impl Foo for u32 {
+                fn foo(&self) {}
+            }
+            fn f(x: u32) {
+                x.foo();
+                unknown.foo();
+            }
+        ",
+        );
+    }
 }
@@ -5,9 +5,14 @@ use std::{
     fmt::{self, Display},
 };
 
-use hir::{Docs, Documentation, HasSource, HirDisplay};
+use hir::{Adt, Docs, Documentation, HasSource, HirDisplay, ModuleDef, VariantDef};
 use ra_ide_db::RootDatabase;
-use ra_syntax::ast::{self, AstNode, NameOwner, VisibilityOwner};
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, TypeParamsOwner, VisibilityOwner},
+    SyntaxElement,
+    SyntaxKind::{TOKEN_TREE, WHITESPACE},
+    TextRange, T,
+};
 use stdx::SepBy;
 
 use crate::display::{generic_parameters, where_predicates};
@@ -18,6 +23,10 @@ pub enum CallableKind {
     StructConstructor,
     VariantConstructor,
     Macro,
+    /// The generic parameters of an item, for a generic argument list.
+    GenericParams,
+    /// The fields of a struct or variant, for a record literal or pattern.
+    RecordFields,
 }
 
 /// Contains information about a function signature
@@ -46,6 +55,8 @@ pub struct FunctionSignature {
     pub where_predicates: Vec<String>,
     /// Self param presence
     pub has_self_param: bool,
+    /// The pattern of a macro rule, shown instead of the parameters
+    pub macro_pattern: Option<String>,
 }
 
 #[derive(Debug, Default)]
@@ -106,6 +117,7 @@ impl FunctionSignature {
                 where_predicates: where_predicates(&node),
                 doc: None,
                 has_self_param: false,
+                macro_pattern: None,
             }
             .with_doc_opt(st.docs(db)),
         )
@@ -153,15 +165,26 @@ impl FunctionSignature {
                 where_predicates: vec![],
                 doc: None,
                 has_self_param: false,
+                macro_pattern: None,
             }
             .with_doc_opt(variant.docs(db)),
         )
     }
 
-    pub(crate) fn from_macro(db: &RootDatabase, macro_def: hir::MacroDef) -> Option<Self> {
+    /// The signature of a macro call matching `rule`, the index of a rule of
+    /// the macro. The parameters are the top-level metavariables and
+    /// repetitions of the rule's pattern.
+    pub(crate) fn from_macro(
+        db: &RootDatabase,
+        macro_def: hir::MacroDef,
+        rule: Option<usize>,
+    ) -> Option<Self> {
         let node: ast::MacroCall = macro_def.source(db).value;
 
-        let params = vec![];
+        let (pattern, params) = match rule.and_then(|rule| macro_rule_params(&node, rule)) {
+            Some((pattern, params)) => (Some(pattern), params),
+            None => (None, vec![]),
+        };
 
         Some(
             FunctionSignature {
@@ -177,10 +200,137 @@ impl FunctionSignature {
                 where_predicates: vec![],
                 doc: None,
                 has_self_param: false,
+                macro_pattern: pattern,
             }
             .with_doc_opt(macro_def.docs(db)),
         )
     }
+
+    pub(crate) fn from_generic_def(db: &RootDatabase, def: ModuleDef) -> Option<Self> {
+        let signature = match def {
+            ModuleDef::Adt(Adt::Struct(it)) => generic_signature(&it.source(db).value),
+            ModuleDef::Adt(Adt::Union(it)) => generic_signature(&it.source(db).value),
+            ModuleDef::Adt(Adt::Enum(it)) => generic_signature(&it.source(db).value),
+            ModuleDef::Function(it) => generic_signature(&it.source(db).value),
+            ModuleDef::Trait(it) => generic_signature(&it.source(db).value),
+            ModuleDef::TypeAlias(it) => generic_signature(&it.source(db).value),
+            _ => return None,
+        };
+        let doc = match def {
+            ModuleDef::Adt(it) => it.docs(db),
+            ModuleDef::Function(it) => it.docs(db),
+            ModuleDef::Trait(it) => it.docs(db),
+            ModuleDef::TypeAlias(it) => it.docs(db),
+            _ => None,
+        };
+        Some(signature.with_doc_opt(doc))
+    }
+
+    /// The signature of a record literal or pattern, listing `fields`.
+    pub(crate) fn from_record_fields(
+        db: &RootDatabase,
+        variant: VariantDef,
+        fields: &[hir::Field],
+    ) -> Self {
+        let (name, doc) = match variant {
+            VariantDef::Struct(it) => (it.name(db).to_string(), it.docs(db)),
+            VariantDef::Union(it) => (it.name(db).to_string(), it.docs(db)),
+            VariantDef::EnumVariant(it) => {
+                (format!("{}::{}", it.parent_enum(db).name(db), it.name(db)), it.docs(db))
+            }
+        };
+        let parameter_names =
+            fields.iter().map(|field| field.name(db).to_string()).collect::<Vec<_>>();
+        let parameter_types = fields
+            .iter()
+            .map(|field| field.signature_ty(db).display(db).to_string())
+            .collect::<Vec<_>>();
+        let parameters = parameter_names
+            .iter()
+            .zip(parameter_types.iter())
+            .map(|(name, ty)| format!("{}: {}", name, ty))
+            .collect();
+
+        FunctionSignature {
+            kind: CallableKind::RecordFields,
+            visibility: None,
+            qualifier: Default::default(),
+            name: Some(name),
+            ret_type: None,
+            parameters,
+            parameter_names,
+            parameter_types,
+            generic_parameters: vec![],
+            where_predicates: vec![],
+            doc,
+            has_self_param: false,
+            macro_pattern: None,
+        }
+    }
+}
+
+fn generic_signature<N: NameOwner + TypeParamsOwner>(node: &N) -> FunctionSignature {
+    let parameters = generic_parameters(node);
+    FunctionSignature {
+        kind: CallableKind::GenericParams,
+        visibility: None,
+        qualifier: Default::default(),
+        name: node.name().map(|n| n.text().to_string()),
+        ret_type: None,
+        parameters,
+        parameter_names: vec![],
+        parameter_types: vec![],
+        generic_parameters: vec![],
+        where_predicates: where_predicates(node),
+        doc: None,
+        has_self_param: false,
+        macro_pattern: None,
+    }
+}
+
+/// The pattern of the `rule`-th rule of a `macro_rules!` definition, without
+/// its delimiters, and its top-level metavariables and repetitions.
+fn macro_rule_params(node: &ast::MacroCall, rule: usize) -> Option<(String, Vec<String>)> {
+    // The body of `macro_rules!` alternates the pattern and the expansion of
+    // each rule.
+    let pattern = node.token_tree()?.syntax().children().nth(2 * rule)?;
+    let elements =
+        pattern.children_with_tokens().filter(|it| it.kind() != WHITESPACE).collect::<Vec<_>>();
+    // Skip the delimiters.
+    let elements = elements.get(1..elements.len().checked_sub(1)?)?;
+    let text = |first: &SyntaxElement, last: &SyntaxElement| {
+        let range = TextRange::new(first.text_range().start(), last.text_range().end());
+        pattern.text().slice(range - pattern.text_range().start()).to_string()
+    };
+
+    let mut params = vec![];
+    let mut i = 0;
+    while i < elements.len() {
+        if elements[i].kind() != T![$] {
+            i += 1;
+            continue;
+        }
+        let end = match elements.get(i + 1).map(|it| it.kind()) {
+            // `$(...)*`, `$(...),*`: up to the repetition operator.
+            Some(TOKEN_TREE) => (i + 2..elements.len())
+                .take(2)
+                .find(|&j| matches!(elements[j].kind(), T![*] | T![+] | T![?]))
+                .unwrap_or(i + 1),
+            // `$name:kind` or `$name`
+            Some(_) if elements.get(i + 2).map(|it| it.kind()) == Some(T![:]) => {
+                (i + 3).min(elements.len() - 1)
+            }
+            Some(_) => i + 1,
+            None => break,
+        };
+        params.push(text(&elements[i], &elements[end]));
+        i = end + 1;
+    }
+    let pattern_text = match (elements.first(), elements.last()) {
+        (Some(first), Some(last)) => text(first, last),
+        _ => String::new(),
+    };
+    Some((pattern_text, params))
 }
 
 impl From<&'_ ast::FnDef> for FunctionSignature {
@@ -259,6 +409,7 @@ impl From<&'_ ast::FnDef> for FunctionSignature {
             // docs are processed separately
             doc: None,
             has_self_param,
+            macro_pattern: None,
         }
     }
 }
@@ -292,6 +443,7 @@ impl Display for FunctionSignature {
                 CallableKind::StructConstructor => write!(f, "struct {}", name)?,
                 CallableKind::VariantConstructor => write!(f, "{}", name)?,
                 CallableKind::Macro => write!(f, "{}!", name)?,
+                CallableKind::GenericParams | CallableKind::RecordFields => write!(f, "{}", name)?,
             }
         }
 
@@ -299,7 +451,20 @@ impl Display for FunctionSignature {
             write!(f, "{}", self.generic_parameters.iter().sep_by(", ").surround_with("<", ">"))?;
         }
 
-        write!(f, "{}", self.parameters.iter().sep_by(", ").surround_with("(", ")"))?;
+        match self.kind {
+            CallableKind::GenericParams => {
+                write!(f, "{}", self.parameters.iter().sep_by(", ").surround_with("<", ">"))?
+            }
+            CallableKind::RecordFields if self.parameters.is_empty() => write!(f, " {{}}")?,
+            CallableKind::RecordFields => {
+                write!(f, "{}", self.parameters.iter().sep_by(", ").surround_with(" { ", " }"))?
+            }
+            CallableKind::Macro => match &self.macro_pattern {
+                Some(pattern) => write!(f, "({})", pattern)?,
+                None => write!(f, "()")?,
+            },
+            _ => write!(f, "{}", self.parameters.iter().sep_by(", ").surround_with("(", ")"))?,
+        }
 
         if let Some(t) = &self.ret_type {
             write!(f, " -> {}", t)?;
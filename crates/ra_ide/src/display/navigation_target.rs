@@ -1,12 +1,14 @@
 //! FIXME: write short doc here
 
 use either::Either;
-use hir::{original_range, AssocItem, FieldSource, HasSource, InFile, ModuleSource};
+use hir::{
+    db::AstDatabase, original_range, AssocItem, FieldSource, HasSource, InFile, ModuleSource,
+};
 use ra_db::{FileId, SourceDatabase};
 use ra_ide_db::{defs::Definition, RootDatabase};
 use ra_syntax::{
     ast::{self, DocCommentsOwner, NameOwner},
-    match_ast, AstNode, SmolStr,
+    match_ast, AstNode, AstPtr, SmolStr,
     SyntaxKind::{self, BIND_PAT, TYPE_PARAM},
     TextRange,
 };
@@ -100,6 +102,45 @@ impl NavigationTarget {
         module.to_nav(db)
     }
 
+    /// Navigates to a `use` tree, the declaration of the name it imports.
+    pub(crate) fn from_use_tree(
+        db: &RootDatabase,
+        name: SmolStr,
+        tree: InFile<&ast::UseTree>,
+    ) -> NavigationTarget {
+        let frange = original_range(db, tree.map(|it| it.syntax()));
+        NavigationTarget::from_syntax(
+            frange.file_id,
+            name,
+            None,
+            frange.range,
+            tree.value.syntax().kind(),
+            None,
+            None,
+        )
+    }
+
+    /// Navigates to the node `ptr` points to, which diagnostics use to refer
+    /// to other items.
+    pub(crate) fn from_ast_ptr<N: AstNode>(
+        db: &RootDatabase,
+        ptr: InFile<AstPtr<N>>,
+        name: SmolStr,
+    ) -> NavigationTarget {
+        let root = db.parse_or_expand(ptr.file_id).unwrap();
+        let node = ptr.value.to_node(&root);
+        let frange = original_range(db, ptr.with_value(node.syntax()));
+        NavigationTarget::from_syntax(
+            frange.file_id,
+            name,
+            None,
+            frange.range,
+            node.syntax().kind(),
+            None,
+            None,
+        )
+    }
+
     #[cfg(test)]
     pub(crate) fn assert_match(&self, expected: &str) {
         let actual = self.debug_render();
@@ -0,0 +1,292 @@
+//! Links to documentation: rustdoc URLs of items, and intra-doc links in
+//! hover rewritten to point at the linked item.
+
+use std::ops::Range;
+
+use hir::{Adt, AsAssocItem, AssocItemContainer, Module, ModuleDef, Semantics, VariantDef};
+use ra_db::CrateId;
+use ra_ide_db::{
+    defs::{classify_name, classify_name_ref, Definition},
+    doc_links::{markdown_links, resolve_doc_path},
+    RootDatabase,
+};
+use ra_syntax::{ast, match_ast, AstNode, SyntaxKind::IDENT, TextRange, TextSize};
+use stdx::format_to;
+
+use crate::{display::TryToNav, FilePosition, NavigationTarget};
+
+/// Crates whose docs are published with the Rust distribution rather than on
+/// docs.rs.
+const SYSROOT_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+pub(crate) fn external_docs(db: &RootDatabase, position: FilePosition) -> Option<String> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id).syntax().clone();
+    let token = file.token_at_offset(position.offset).find(|it| it.kind() == IDENT)?;
+    let token = sema.descend_into_macros(token);
+
+    let def = match_ast! {
+        match (token.parent()) {
+            ast::NameRef(name_ref) => classify_name_ref(&sema, &name_ref)?.definition(),
+            ast::Name(name) => classify_name(&sema, &name)?.definition(),
+            _ => return None,
+        }
+    };
+    docs_url(db, &def)
+}
+
+/// The URL of the rustdoc page documenting `def`: docs.rs for crates from
+/// crates.io, doc.rust-lang.org for the standard library.
+pub(crate) fn docs_url(db: &RootDatabase, def: &Definition) -> Option<String> {
+    let (page, anchor): (ModuleDef, Option<String>) = match *def {
+        Definition::ModuleDef(ModuleDef::EnumVariant(it)) => {
+            (Adt::Enum(it.parent_enum(db)).into(), Some(format!("variant.{}", it.name(db))))
+        }
+        Definition::ModuleDef(it) => match assoc_item_anchor(db, it) {
+            Some((container, anchor)) => (container, Some(anchor)),
+            None => (it, None),
+        },
+        Definition::Field(it) => match it.parent_def(db) {
+            VariantDef::Struct(s) => {
+                (Adt::Struct(s).into(), Some(format!("structfield.{}", it.name(db))))
+            }
+            VariantDef::Union(u) => {
+                (Adt::Union(u).into(), Some(format!("structfield.{}", it.name(db))))
+            }
+            VariantDef::EnumVariant(v) => (
+                Adt::Enum(v.parent_enum(db)).into(),
+                Some(format!("variant.{}.field.{}", v.name(db), it.name(db))),
+            ),
+        },
+        Definition::Macro(it) => {
+            let mut url = docs_dir_url(db, it.module(db)?)?;
+            url.push_str(&format!("macro.{}.html", it.name(db)?));
+            return Some(url);
+        }
+        Definition::SelfType(_) | Definition::Local(_) | Definition::TypeParam(_) => return None,
+    };
+
+    let mut url = match page {
+        ModuleDef::BuiltinType(it) => {
+            let version = std_crate(db)
+                .map_or_else(|| "stable".to_string(), |it| sysroot_docs_version(db, it));
+            format!("https://doc.rust-lang.org/{}/std/primitive.{}.html", version, it)
+        }
+        ModuleDef::Module(it) => format!("{}index.html", docs_dir_url(db, it)?),
+        _ => {
+            let mut url = docs_dir_url(db, page.module(db)?)?;
+            url.push_str(&page_file_name(db, page)?);
+            url
+        }
+    };
+    if let Some(anchor) = anchor {
+        url.push('#');
+        url.push_str(&anchor);
+    }
+    Some(url)
+}
+
+/// Rewrites the intra-doc links in `markdown`, the docs of `def`. Links to
+/// items in libraries point at their rustdoc pages. Links to items in the
+/// workspace keep the path as their target and are returned with the range of
+/// that target in the result, so that the client can point them at the source.
+pub(crate) fn rewrite_links(
+    db: &RootDatabase,
+    markdown: &str,
+    def: &Definition,
+) -> (String, Vec<(TextRange, NavigationTarget)>) {
+    let mut res = String::new();
+    let mut local_links = Vec::new();
+    let mut last = 0;
+    for link in markdown_links(markdown) {
+        let target = match resolve_doc_path(db, def, &link.path) {
+            Some(it) => it,
+            None => continue,
+        };
+        // Builtin types are documented in `std`.
+        let is_builtin = matches!(target, Definition::ModuleDef(ModuleDef::BuiltinType(_)));
+        let (dest, nav) = if is_builtin || target.is_in_library(db) {
+            match docs_url(db, &target) {
+                Some(it) => (it, None),
+                None => continue,
+            }
+        } else {
+            match target.try_to_nav(db) {
+                Some(nav) => (link.path.clone(), Some(nav)),
+                None => continue,
+            }
+        };
+        let range = Range::<usize>::from(link.full_range);
+        res.push_str(&markdown[last..range.start]);
+        format_to!(res, "[{}](", link.label);
+        if let Some(nav) = nav {
+            local_links.push((TextRange::at(TextSize::of(&res), TextSize::of(&dest)), nav));
+        }
+        res.push_str(&dest);
+        res.push(')');
+        last = range.end;
+    }
+    res.push_str(&markdown[last..]);
+    (res, local_links)
+}
+
+/// The URL of the directory with the docs of the items in `module`, ending
+/// with a slash.
+fn docs_dir_url(db: &RootDatabase, module: Module) -> Option<String> {
+    let krate = module.krate();
+    let name = krate.display_name(db)?.to_string();
+    let mut url = if SYSROOT_CRATES.contains(&name.as_str()) {
+        format!("https://doc.rust-lang.org/{}/{}/", sysroot_docs_version(db, krate.into()), name)
+    } else {
+        let env = &db.crate_graph()[krate.into()].env;
+        let package = env.get("CARGO_PKG_NAME").unwrap_or_else(|| name.clone());
+        let version = env.get("CARGO_PKG_VERSION").unwrap_or_else(|| "*".to_string());
+        format!("https://docs.rs/{}/{}/{}/", package, version, name)
+    };
+    for module in module.path_to_root(db).into_iter().rev() {
+        if let Some(name) = module.name(db) {
+            url.push_str(&format!("{}/", name));
+        }
+    }
+    Some(url)
+}
+
+/// The version of the docs on doc.rust-lang.org matching the toolchain the
+/// sysroot crate `krate` comes from: the release for stable toolchains, or
+/// `beta` or `nightly`. Falls back to the latest stable docs.
+fn sysroot_docs_version(db: &RootDatabase, krate: CrateId) -> String {
+    let env = &db.crate_graph()[krate].env;
+    match env.get("CFG_RELEASE_CHANNEL").as_deref() {
+        Some("stable") => env.get("CFG_RELEASE").unwrap_or_else(|| "stable".to_string()),
+        Some(channel) => channel.to_string(),
+        None => "stable".to_string(),
+    }
+}
+
+/// The `std` crate, whose docs document the primitive types.
+fn std_crate(db: &RootDatabase) -> Option<CrateId> {
+    let crate_graph = db.crate_graph();
+    crate_graph.iter().find(|&krate| {
+        crate_graph[krate].display_name.as_ref().map_or(false, |it| it.to_string() == "std")
+    })
+}
+
+/// For an associated item, the item whose page documents it and the anchor
+/// on that page.
+fn assoc_item_anchor(db: &RootDatabase, def: ModuleDef) -> Option<(ModuleDef, String)> {
+    let (assoc, kind) = match def {
+        ModuleDef::Function(it) => (it.as_assoc_item(db)?, "method"),
+        ModuleDef::Const(it) => (it.as_assoc_item(db)?, "associatedconstant"),
+        ModuleDef::TypeAlias(it) => (it.as_assoc_item(db)?, "associatedtype"),
+        _ => return None,
+    };
+    let container = match assoc.container(db) {
+        AssocItemContainer::Trait(it) => ModuleDef::Trait(it),
+        AssocItemContainer::ImplDef(it) => ModuleDef::Adt(it.target_ty(db).as_adt()?),
+    };
+    Some((container, format!("{}.{}", kind, def.name(db)?)))
+}
+
+fn page_file_name(db: &RootDatabase, def: ModuleDef) -> Option<String> {
+    let kind = match def {
+        ModuleDef::Adt(Adt::Struct(_)) => "struct",
+        ModuleDef::Adt(Adt::Union(_)) => "union",
+        ModuleDef::Adt(Adt::Enum(_)) => "enum",
+        ModuleDef::Function(_) => "fn",
+        ModuleDef::Const(_) => "constant",
+        ModuleDef::Static(_) => "static",
+        ModuleDef::Trait(_) => "trait",
+        ModuleDef::TypeAlias(_) => "type",
+        ModuleDef::Module(_) | ModuleDef::EnumVariant(_) | ModuleDef::BuiltinType(_) => {
+            return None
+        }
+    };
+    Some(format!("{}.{}.html", kind, def.name(db)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::analysis_and_position;
+
+    fn check(fixture: &str, expected: &str) {
+        let (analysis, position) = analysis_and_position(fixture);
+        let url = analysis.external_docs(position).unwrap();
+        assert_eq!(url.as_deref(), Some(expected));
+    }
+
+    #[test]
+    fn external_docs_for_items() {
+        check(
+            "
+            //- /main.rs
+            use foo::bar::Ba<|>r;
+            //- /foo/lib.rs
+            pub mod bar {
+                pub struct Bar;
+            }
+            ",
+            "https://docs.rs/foo/*/foo/bar/struct.Bar.html",
+        );
+        check(
+            "
+            //- /main.rs
+            fn f() { foo::ba<|>r() }
+            //- /foo/lib.rs
+            pub fn bar() {}
+            ",
+            "https://docs.rs/foo/*/foo/fn.bar.html",
+        );
+        check(
+            "
+            //- /main.rs
+            use foo::ba<|>r;
+            //- /foo/lib.rs
+            pub mod bar {}
+            ",
+            "https://docs.rs/foo/*/foo/bar/index.html",
+        );
+    }
+
+    #[test]
+    fn external_docs_for_members() {
+        check(
+            "
+            //- /foo/lib.rs
+            pub struct Foo { pub fi<|>eld: u32 }
+            ",
+            "https://docs.rs/foo/*/foo/struct.Foo.html#structfield.field",
+        );
+        check(
+            "
+            //- /foo/lib.rs
+            pub struct Foo;
+            impl Foo {
+                pub fn metho<|>d(&self) {}
+            }
+            ",
+            "https://docs.rs/foo/*/foo/struct.Foo.html#method.method",
+        );
+        check(
+            "
+            //- /foo/lib.rs
+            pub enum E { V<|>a }
+            ",
+            "https://docs.rs/foo/*/foo/enum.E.html#variant.Va",
+        );
+    }
+
+    #[test]
+    fn external_docs_for_std() {
+        check(
+            "
+            //- /main.rs
+            use std::vec::Ve<|>c;
+            //- /std/lib.rs
+            pub mod vec {
+                pub struct Vec;
+            }
+            ",
+            "https://doc.rust-lang.org/stable/std/vec/struct.Vec.html",
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Goto declaration: for most items this is the same as the definition, but
+//! items in trait impls are declared in the trait, re-exported names by the
+//! `use` that re-exports them and modules by their `mod foo;` item.
+
+use hir::{AsAssocItem, AssocItemContainer, ModuleDef, ModuleSource, Semantics};
+use ra_ide_db::{
+    defs::{classify_name, classify_name_ref, Definition},
+    RootDatabase,
+};
+use ra_syntax::{
+    ast::{self, ModuleItemOwner},
+    match_ast, AstNode,
+    SyntaxKind::*,
+    SyntaxToken, TokenAtOffset,
+};
+
+use crate::{
+    display::ToNav, goto_definition::goto_definition, FilePosition, NavigationTarget, RangeInfo,
+};
+
+pub(crate) fn goto_declaration(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id).syntax().clone();
+    let original_token = pick_best(file.token_at_offset(position.offset))?;
+    let token = sema.descend_into_macros(original_token.clone());
+
+    let nav = match_ast! {
+        match (token.parent()) {
+            ast::NameRef(name_ref) => {
+                let def = classify_name_ref(&sema, &name_ref)?.definition();
+                trait_item_declaration(db, def).or_else(|| reexport_declaration(&sema, &name_ref, def))
+            },
+            ast::Name(name) => {
+                let def = classify_name(&sema, &name)?.definition();
+                trait_item_declaration(db, def)
+            },
+            _ => {
+                // Anywhere else in a module file, go to the `mod foo;` that
+                // declares it.
+                let module = sema.to_module_def(position.file_id)?;
+                module.declaration_source(db)?;
+                Some(NavigationTarget::from_module_to_decl(db, module))
+            },
+        }
+    };
+
+    match nav {
+        Some(nav) => Some(RangeInfo::new(original_token.text_range(), vec![nav])),
+        None => goto_definition(db, position),
+    }
+}
+
+fn pick_best(tokens: TokenAtOffset<SyntaxToken>) -> Option<SyntaxToken> {
+    return tokens.max_by_key(priority);
+    fn priority(n: &SyntaxToken) -> usize {
+        match n.kind() {
+            IDENT | INT_NUMBER => 2,
+            kind if kind.is_trivia() => 0,
+            _ => 1,
+        }
+    }
+}
+
+/// For an item in a trait impl, the corresponding item of the trait.
+fn trait_item_declaration(db: &RootDatabase, def: Definition) -> Option<NavigationTarget> {
+    let assoc = match def {
+        Definition::ModuleDef(ModuleDef::Function(it)) => it.as_assoc_item(db),
+        Definition::ModuleDef(ModuleDef::Const(it)) => it.as_assoc_item(db),
+        Definition::ModuleDef(ModuleDef::TypeAlias(it)) => it.as_assoc_item(db),
+        _ => None,
+    }?;
+    let trait_ = match assoc.container(db) {
+        AssocItemContainer::ImplDef(it) => it.target_trait_def(db)?,
+        AssocItemContainer::Trait(_) => return None,
+    };
+    let decl = trait_.items(db).into_iter().find(|&item| item.corresponds_to(db, assoc))?;
+    Some(decl.to_nav(db))
+}
+
+/// For a name that a module imports from elsewhere, the `use` in that module.
+fn reexport_declaration(
+    sema: &Semantics<RootDatabase>,
+    name_ref: &ast::NameRef,
+    def: Definition,
+) -> Option<NavigationTarget> {
+    let db = sema.db;
+    let path = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?.parent_path();
+    let module = match path.qualifier() {
+        Some(qualifier) => match sema.resolve_path(&qualifier)? {
+            hir::PathResolution::Def(ModuleDef::Module(it)) => it,
+            _ => return None,
+        },
+        None => sema.scope(path.syntax()).module()?,
+    };
+    let def_module = match def {
+        Definition::ModuleDef(it) => it.module(db)?,
+        _ => return None,
+    };
+    if def_module == module {
+        return None;
+    }
+
+    let name = name_ref.text();
+    let src = module.definition_source(db);
+    let items = match &src.value {
+        ModuleSource::SourceFile(it) => it.items().collect::<Vec<_>>(),
+        ModuleSource::Module(it) => it.item_list()?.items().collect(),
+    };
+    let tree = items
+        .into_iter()
+        .filter_map(|item| match item {
+            ast::ModuleItem::UseItem(it) => Some(it),
+            _ => None,
+        })
+        .flat_map(|it| it.syntax().descendants().filter_map(ast::UseTree::cast))
+        .find(|tree| {
+            if tree.use_tree_list().is_some() || tree.star_token().is_some() {
+                return false;
+            }
+            match tree.alias() {
+                Some(alias) => alias.name().map_or(false, |it| it.text() == name),
+                None => tree
+                    .path()
+                    .and_then(|it| it.segment())
+                    .and_then(|it| it.name_ref())
+                    .map_or(false, |it| it.text() == name),
+            }
+        })?;
+    Some(NavigationTarget::from_use_tree(db, name.clone(), src.with_value(&tree)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::analysis_and_position;
+
+    fn check_goto(fixture: &str, expected: &str) {
+        let (analysis, pos) = analysis_and_position(fixture);
+
+        let mut navs = analysis.goto_declaration(pos).unwrap().unwrap().info;
+        assert_eq!(navs.len(), 1);
+        let nav = navs.pop().unwrap();
+        nav.assert_match(expected);
+    }
+
+    #[test]
+    fn goto_declaration_of_impl_method() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait Foo { fn foo(&self); }
+            struct S;
+            impl Foo for S { fn fo<|>o(&self) {} }
+            ",
+            "foo FN_DEF FileId(1) 12..26 15..18",
+        );
+    }
+
+    #[test]
+    fn goto_declaration_of_impl_assoc_type_and_const() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait Foo { type Item; }
+            struct S;
+            impl Foo for S { type It<|>em = u32; }
+            ",
+            "Item TYPE_ALIAS_DEF FileId(1) 12..22 17..21",
+        );
+        check_goto(
+            "
+            //- /lib.rs
+            trait Foo { const C: u32; }
+            struct S;
+            impl Foo for S { const <|>C: u32 = 0; }
+            ",
+            "C CONST_DEF FileId(1) 12..25 18..19",
+        );
+    }
+
+    #[test]
+    fn goto_declaration_of_reexport() {
+        check_goto(
+            "
+            //- /lib.rs
+            mod foo;
+            use foo::Ba<|>r;
+            //- /foo.rs
+            mod inner { pub struct Bar; }
+            pub use inner::Bar;
+            ",
+            "Bar USE_TREE FileId(2) 38..48",
+        );
+    }
+
+    #[test]
+    fn goto_declaration_of_module_file() {
+        check_goto(
+            "
+            //- /lib.rs
+            mod foo;
+            //- /foo.rs
+            fn bar() {<|>}
+            ",
+            "foo MODULE FileId(1) 0..8",
+        );
+    }
+
+    #[test]
+    fn goto_declaration_falls_back_to_definition() {
+        check_goto(
+            "
+            //- /lib.rs
+            struct Foo;
+            fn bar(_: Fo<|>o) {}
+            ",
+            "Foo STRUCT_DEF FileId(1) 0..11 7..10",
+        );
+    }
+}
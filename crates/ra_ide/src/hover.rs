@@ -2,8 +2,8 @@
 //! source code items (e.g. function call, struct field, variable symbol...)
 
 use hir::{
-    Adt, AsAssocItem, AssocItemContainer, FieldSource, HasSource, HirDisplay, ModuleDef,
-    ModuleSource, Semantics,
+    Adt, AsAssocItem, AssocItemContainer, FieldSource, HasSource, HirDisplay, LayoutError,
+    ModuleDef, ModuleSource, Semantics, Type,
 };
 use ra_db::SourceDatabase;
 use ra_ide_db::{
@@ -14,20 +14,55 @@ use ra_syntax::{
     ast::{self, DocCommentsOwner},
     match_ast, AstNode,
     SyntaxKind::*,
-    SyntaxToken, TokenAtOffset,
+    SyntaxToken, TextRange, TokenAtOffset,
 };
 
 use crate::{
-    display::{macro_label, rust_code_markup, rust_code_markup_with_doc, ShortLabel},
-    FilePosition, RangeInfo,
+    display::{
+        macro_label, rust_code_markup, rust_code_markup_with_doc, ShortLabel, ToNav, TryToNav,
+    },
+    external_docs::rewrite_links,
+    runnables::runnable_fn,
+    FileId, FilePosition, NavigationTarget, RangeInfo, Runnable, RunnableKind,
 };
 use itertools::Itertools;
-use std::iter::once;
+use std::{iter::once, ops::Range};
 
 /// Contains the results when hovering over an item
 #[derive(Debug, Default)]
 pub struct HoverResult {
     results: Vec<String>,
+    links: Vec<HoverLink>,
+    actions: Vec<HoverAction>,
+}
+
+/// An intra-doc link in the hover docs to an item in the workspace.
+#[derive(Debug, Clone)]
+pub struct HoverLink {
+    /// The index of the result containing the link.
+    pub result: usize,
+    /// The range of the link target in the result, which is the item's path.
+    pub range: TextRange,
+    pub nav: NavigationTarget,
+}
+
+/// Something the user can do with the item under the cursor, shown as a
+/// command in the hover.
+#[derive(Debug, Clone)]
+pub enum HoverAction {
+    /// Show the implementations of the trait or type at the position.
+    Implementation(FilePosition),
+    /// Run or debug the test or `main` function defined in the file.
+    Runnable(FileId, Runnable),
+    /// Go to the definitions of the types the item refers to.
+    GoToType(Vec<HoverGotoTypeData>),
+}
+
+#[derive(Debug, Clone)]
+pub struct HoverGotoTypeData {
+    /// The path of the type, like `std::vec::Vec`.
+    pub mod_path: String,
+    pub nav: NavigationTarget,
 }
 
 impl HoverResult {
@@ -55,18 +90,54 @@ impl HoverResult {
         &self.results
     }
 
+    pub fn links(&self) -> &[HoverLink] {
+        &self.links
+    }
+
+    pub fn actions(&self) -> &[HoverAction] {
+        &self.actions
+    }
+
     /// Returns the results converted into markup
     /// for displaying in a UI
     pub fn to_markup(&self) -> String {
         self.results.join("\n\n---\n")
     }
+
+    /// Like `to_markup`, but with the targets of the intra-doc links to items
+    /// in the workspace replaced by `link_target`, unless it returns `None`.
+    pub fn to_markup_with_links(
+        &self,
+        mut link_target: impl FnMut(&NavigationTarget) -> Option<String>,
+    ) -> String {
+        let results = self.results.iter().enumerate().map(|(idx, result)| {
+            let mut res = String::new();
+            let mut last = 0;
+            for link in self.links.iter().filter(|it| it.result == idx) {
+                if let Some(target) = link_target(&link.nav) {
+                    let range = Range::<usize>::from(link.range);
+                    res.push_str(&result[last..range.start]);
+                    res.push_str(&target);
+                    last = range.end;
+                }
+            }
+            res.push_str(&result[last..]);
+            res
+        });
+        results.collect::<Vec<_>>().join("\n\n---\n")
+    }
 }
 
 fn hover_text(
     docs: Option<String>,
     desc: Option<String>,
     mod_path: Option<String>,
+    summary: Option<String>,
 ) -> Option<String> {
+    let docs = match (summary, docs) {
+        (Some(summary), Some(docs)) => Some(format!("{}\n\n{}", summary, docs)),
+        (summary, docs) => summary.or(docs),
+    };
     if let Some(desc) = desc {
         Some(rust_code_markup_with_doc(&desc, docs.as_deref(), mod_path.as_deref()))
     } else {
@@ -109,18 +180,29 @@ fn determine_mod_path(db: &RootDatabase, def: &Definition) -> Option<String> {
     mod_path
 }
 
-fn hover_text_from_name_kind(db: &RootDatabase, def: Definition) -> Option<String> {
+fn hover_text_from_name_kind(
+    db: &RootDatabase,
+    def: Definition,
+) -> Option<(String, Vec<(TextRange, NavigationTarget)>)> {
     let mod_path = determine_mod_path(db, &def);
-    return match def {
+    let text = match def {
         Definition::Macro(it) => {
             let src = it.source(db);
-            hover_text(src.value.doc_comment_text(), Some(macro_label(&src.value)), mod_path)
+            hover_text(src.value.doc_comment_text(), Some(macro_label(&src.value)), mod_path, None)
         }
-        Definition::Field(it) => {
-            let src = it.source(db);
+        Definition::Field(field) => {
+            let src = field.source(db);
             match src.value {
                 FieldSource::Named(it) => {
-                    hover_text(it.doc_comment_text(), it.short_label(), mod_path)
+                    let layout = match field.layout(db) {
+                        Ok(it) => Some(format!(
+                            "size = {}, align = {}, offset = {}",
+                            it.size, it.align, it.offset
+                        )),
+                        Err(LayoutError::UnknownArrayLength) => Some("size = ?".to_string()),
+                        Err(_) => None,
+                    };
+                    hover_text(it.doc_comment_text(), it.short_label(), mod_path, layout)
                 }
                 _ => None,
             }
@@ -128,19 +210,33 @@ fn hover_text_from_name_kind(db: &RootDatabase, def: Definition) -> Option<Strin
         Definition::ModuleDef(it) => match it {
             ModuleDef::Module(it) => match it.definition_source(db).value {
                 ModuleSource::Module(it) => {
-                    hover_text(it.doc_comment_text(), it.short_label(), mod_path)
+                    hover_text(it.doc_comment_text(), it.short_label(), mod_path, None)
                 }
                 _ => None,
             },
-            ModuleDef::Function(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Adt(Adt::Struct(it)) => from_def_source(db, it, mod_path),
-            ModuleDef::Adt(Adt::Union(it)) => from_def_source(db, it, mod_path),
-            ModuleDef::Adt(Adt::Enum(it)) => from_def_source(db, it, mod_path),
-            ModuleDef::EnumVariant(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Const(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Static(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Trait(it) => from_def_source(db, it, mod_path),
-            ModuleDef::TypeAlias(it) => from_def_source(db, it, mod_path),
+            ModuleDef::Function(it) => from_def_source(db, it, mod_path, None),
+            ModuleDef::Adt(adt) => {
+                let layout = match adt.layout(db) {
+                    Ok(it) => Some(format!("size = {}, align = {}", it.size, it.align)),
+                    // The length of some array isn't written as a literal.
+                    Err(LayoutError::UnknownArrayLength) => Some("size = ?".to_string()),
+                    Err(_) => None,
+                };
+                match adt {
+                    Adt::Struct(it) => from_def_source(db, it, mod_path, layout),
+                    Adt::Union(it) => from_def_source(db, it, mod_path, layout),
+                    Adt::Enum(it) => from_def_source(db, it, mod_path, layout),
+                }
+            }
+            ModuleDef::EnumVariant(it) => from_def_source(db, it, mod_path, None),
+            ModuleDef::Const(it) => from_def_source(db, it, mod_path, None),
+            ModuleDef::Static(it) => from_def_source(db, it, mod_path, None),
+            ModuleDef::Trait(it) => {
+                let object_safety =
+                    if it.is_object_safe(db) { "object safe" } else { "not object safe" };
+                from_def_source(db, it, mod_path, Some(object_safety.to_string()))
+            }
+            ModuleDef::TypeAlias(it) => from_def_source(db, it, mod_path, None),
             ModuleDef::BuiltinType(it) => Some(it.to_string()),
         },
         Definition::Local(it) => Some(rust_code_markup(&it.ty(db).display_truncated(db, None))),
@@ -149,14 +245,20 @@ fn hover_text_from_name_kind(db: &RootDatabase, def: Definition) -> Option<Strin
             None
         }
     };
-
-    fn from_def_source<A, D>(db: &RootDatabase, def: D, mod_path: Option<String>) -> Option<String>
+    return text.map(|it| rewrite_links(db, &it, &def));
+
+    fn from_def_source<A, D>(
+        db: &RootDatabase,
+        def: D,
+        mod_path: Option<String>,
+        summary: Option<String>,
+    ) -> Option<String>
     where
         D: HasSource<Ast = A>,
         A: ast::DocCommentsOwner + ast::NameOwner + ShortLabel,
     {
         let src = def.source(db);
-        hover_text(src.value.doc_comment_text(), src.value.short_label(), mod_path)
+        hover_text(src.value.doc_comment_text(), src.value.short_label(), mod_path, summary)
     }
 }
 
@@ -180,7 +282,21 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
         }
     } {
         let range = sema.original_range(&node).range;
-        res.extend(hover_text_from_name_kind(db, name_kind));
+        res.actions = hover_actions(&sema, &name_kind);
+        let hidden_type = match &name_kind {
+            Definition::Local(local) => hidden_type_markup(db, &local.ty(db)),
+            _ => None,
+        };
+        if let Some((text, links)) = hover_text_from_name_kind(db, name_kind) {
+            let result = res.results.len();
+            res.links.extend(links.into_iter().map(|(range, nav)| HoverLink {
+                result,
+                range,
+                nav,
+            }));
+            res.results.push(text);
+        }
+        res.extend(hidden_type);
 
         if !res.is_empty() {
             return Some(RangeInfo::new(range, res));
@@ -209,10 +325,102 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
     }?;
 
     res.extend(Some(rust_code_markup(&ty.display_truncated(db, None))));
+    res.extend(hidden_type_markup(db, &ty));
+    res.actions.extend(goto_type_action(db, &[ty]));
     let range = sema.original_range(&node).range;
     Some(RangeInfo::new(range, res))
 }
 
+fn hover_actions(sema: &Semantics<RootDatabase>, def: &Definition) -> Vec<HoverAction> {
+    let db = sema.db;
+    let mut res = Vec::new();
+    res.extend(implementation_action(db, def));
+    res.extend(runnable_action(sema, def));
+
+    let types = match *def {
+        Definition::Local(it) => vec![it.ty(db)],
+        Definition::Field(it) => vec![it.signature_ty(db)],
+        Definition::ModuleDef(ModuleDef::Function(it)) => {
+            let mut types = it.param_types(db);
+            types.push(it.ret_type(db));
+            types
+        }
+        Definition::ModuleDef(ModuleDef::TypeAlias(it)) => vec![it.ty(db)],
+        _ => Vec::new(),
+    };
+    res.extend(goto_type_action(db, &types));
+    res
+}
+
+fn implementation_action(db: &RootDatabase, def: &Definition) -> Option<HoverAction> {
+    let nav = match *def {
+        Definition::ModuleDef(ModuleDef::Trait(it)) => it.to_nav(db),
+        Definition::ModuleDef(ModuleDef::Adt(it)) => it.to_nav(db),
+        _ => return None,
+    };
+    let offset = nav.focus_range().unwrap_or_else(|| nav.full_range()).start();
+    Some(HoverAction::Implementation(FilePosition { file_id: nav.file_id(), offset }))
+}
+
+fn runnable_action(sema: &Semantics<RootDatabase>, def: &Definition) -> Option<HoverAction> {
+    let function = match *def {
+        Definition::ModuleDef(ModuleDef::Function(it)) => it,
+        _ => return None,
+    };
+    let src = function.source(sema.db);
+    if src.file_id.call_node(sema.db).is_some() {
+        // Functions defined by macros can't be run by name.
+        return None;
+    }
+    let file_id = src.file_id.original_file(sema.db);
+    let range = src.value.syntax().text_range();
+    let fn_def = sema
+        .parse(file_id)
+        .syntax()
+        .descendants()
+        .filter_map(ast::FnDef::cast)
+        .find(|it| it.syntax().text_range() == range)?;
+    let runnable = runnable_fn(sema, fn_def)?;
+    match runnable.kind {
+        RunnableKind::Test { .. } | RunnableKind::Bin => {
+            Some(HoverAction::Runnable(file_id, runnable))
+        }
+        _ => None,
+    }
+}
+
+fn goto_type_action(db: &RootDatabase, types: &[Type]) -> Option<HoverAction> {
+    let mut defs = Vec::new();
+    for def in types.iter().flat_map(|ty| ty.mentioned_defs()) {
+        if !defs.contains(&def) {
+            defs.push(def);
+        }
+    }
+    let targets: Vec<HoverGotoTypeData> = defs
+        .into_iter()
+        .filter_map(|it| {
+            let def = Definition::ModuleDef(it);
+            let nav = def.try_to_nav(db)?;
+            let name = def.name(db)?;
+            let mod_path = match determine_mod_path(db, &def) {
+                Some(path) if !path.is_empty() => format!("{}::{}", path, name),
+                _ => name.to_string(),
+            };
+            Some(HoverGotoTypeData { mod_path, nav })
+        })
+        .collect();
+    if targets.is_empty() {
+        return None;
+    }
+    Some(HoverAction::GoToType(targets))
+}
+
+/// Shows what the `impl Trait`s returned from functions stand for.
+fn hidden_type_markup(db: &RootDatabase, ty: &Type) -> Option<String> {
+    let hidden = ty.reveal_opaque_types(db)?;
+    Some(format!("Hidden type:\n{}", rust_code_markup(&hidden.display_truncated(db, None))))
+}
+
 fn pick_best(tokens: TokenAtOffset<SyntaxToken>) -> Option<SyntaxToken> {
     return tokens.max_by_key(priority);
     fn priority(n: &SyntaxToken) -> usize {
@@ -230,7 +438,10 @@ mod tests {
     use ra_db::FileLoader;
     use ra_syntax::TextRange;
 
-    use crate::mock_analysis::{analysis_and_position, single_file_with_position};
+    use crate::{
+        mock_analysis::{analysis_and_position, single_file_with_position},
+        HoverAction, RunnableKind,
+    };
 
     fn trim_markup(s: &str) -> &str {
         s.trim_start_matches("```rust\n").trim_end_matches("\n```")
@@ -364,7 +575,7 @@ mod tests {
                 };
             }
         "#,
-            &["Foo\nfield_a: u32"],
+            &["Foo\nfield_a: u32\n```\n\nsize = 4, align = 4, offset = 0"],
         );
 
         // Hovering over the field in the definition
@@ -381,7 +592,44 @@ mod tests {
                 };
             }
         "#,
-            &["Foo\nfield_a: u32"],
+            &["Foo\nfield_a: u32\n```\n\nsize = 4, align = 4, offset = 0"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_layout_of_struct() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            /// Docs
+            struct Fo<|>o {
+                a: u8,
+                b: u32,
+            }
+        "#,
+            &["struct Foo\n```\n\nsize = 8, align = 4\n\nDocs"],
+        );
+
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct Foo<T> {
+                a: T,
+                b<|>: u32,
+            }
+        "#,
+            &["Foo\nb: u32"],
+        );
+
+        check_hover_result(
+            r#"
+            //- /main.rs
+            const N: usize = 4;
+            struct Fo<|>o {
+                a: [u8; N],
+            }
+        "#,
+            &["struct Foo\n```\n\nsize = ?"],
         );
     }
 
@@ -877,7 +1125,149 @@ fn func(foo: i32) { if true { <|>foo; }; }
             //- /lib.rs
             unsafe trait foo<|>() {}
             ",
-            &["unsafe trait foo"],
+            &["unsafe trait foo\n```\n\nobject safe"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_object_safety_of_trait() {
+        check_hover_result(
+            r#"
+            //- /lib.rs
+            /// Docs
+            trait Fo<|>o {
+                fn foo<T>(&self, t: T);
+            }
+            "#,
+            &["trait Foo\n```\n\nnot object safe\n\nDocs"],
+        );
+        check_hover_result(
+            r#"
+            //- /lib.rs
+            /// Docs
+            trait Fo<|>o {
+                fn foo(&self);
+            }
+            "#,
+            &["trait Foo\n```\n\nobject safe\n\nDocs"],
         );
     }
+
+    #[test]
+    fn hover_shows_hidden_type_of_impl_trait() {
+        check_hover_result(
+            r#"
+            //- /lib.rs
+            trait Tr {}
+            struct S;
+            impl Tr for S {}
+            fn make() -> impl Tr { S }
+            fn main() {
+                let x<|> = make();
+            }
+            "#,
+            &["Hidden type:\n```rust\nS", "impl Tr"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_hidden_type_after_the_type() {
+        for fixture in &[
+            "trait Tr {} struct S; impl Tr for S {} fn make() -> impl Tr { S }
+             fn main() { let x<|> = make(); }",
+            "trait Tr {} struct S; impl Tr for S {} fn make() -> impl Tr { S }
+             fn main() { let x = make()<|>; }",
+        ] {
+            let (analysis, position) = single_file_with_position(fixture);
+            let hover = analysis.hover(position).unwrap().unwrap();
+            let results: Vec<&str> =
+                hover.info.results().iter().map(|it| trim_markup(it)).collect();
+            assert_eq!(results, vec!["impl Tr", "Hidden type:\n```rust\nS"]);
+        }
+    }
+
+    #[test]
+    fn hover_rewrites_intra_doc_links() {
+        let (analysis, position) = analysis_and_position(
+            r#"
+            //- /main.rs
+            /// Wraps a [`Bar`] or a [foo](foo::Foo), not a `[Bar]` or [Missing].
+            pub struct Ba<|>z;
+            pub struct Bar;
+            //- /foo/lib.rs
+            pub struct Foo;
+            "#,
+        );
+        let hover = analysis.hover(position).unwrap().unwrap().info;
+        let markup = hover.first().unwrap();
+        assert!(markup
+            .ends_with("Wraps a [`Bar`](Bar) or a [foo](foo::Foo), not a `[Bar]` or [Missing]."));
+        let links = hover.links();
+        assert_eq!(links.len(), 2);
+        assert_eq!(&markup[links[0].range], "Bar");
+        links[0].nav.assert_match("Bar STRUCT_DEF FileId(1) 86..101 97..100");
+        assert_eq!(&markup[links[1].range], "foo::Foo");
+        links[1].nav.assert_match("Foo STRUCT_DEF FileId(2) 0..15 11..14");
+        let markup = hover.to_markup_with_links(|nav| Some(format!("{}.rs", nav.name())));
+        assert!(markup
+            .ends_with("Wraps a [`Bar`](Bar.rs) or a [foo](Foo.rs), not a `[Bar]` or [Missing]."));
+    }
+
+    #[test]
+    fn hover_action_implementations() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /lib.rs
+            trait Tr<|>ait {}
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap().info;
+        match hover.actions() {
+            [HoverAction::Implementation(pos)] => {
+                assert_eq!(pos.file_id, position.file_id);
+                assert_eq!(pos.offset, 6.into());
+            }
+            actions => panic!("unexpected actions: {:?}", actions),
+        }
+    }
+
+    #[test]
+    fn hover_action_run_test() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /lib.rs
+            #[test]
+            fn foo_<|>test() {}
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap().info;
+        match hover.actions() {
+            [HoverAction::Runnable(file_id, runnable)] => {
+                assert_eq!(*file_id, position.file_id);
+                assert!(matches!(runnable.kind, RunnableKind::Test { .. }));
+            }
+            actions => panic!("unexpected actions: {:?}", actions),
+        }
+    }
+
+    #[test]
+    fn hover_action_go_to_types() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /main.rs
+            struct S;
+            struct Arg<T>(T);
+            fn main() { let x<|> = Arg(S); }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap().info;
+        match hover.actions() {
+            [HoverAction::GoToType(targets)] => {
+                let mut paths: Vec<&str> = targets.iter().map(|it| it.mod_path.as_str()).collect();
+                paths.sort();
+                assert_eq!(paths, ["Arg", "S"]);
+            }
+            actions => panic!("unexpected actions: {:?}", actions),
+        }
+    }
 }
@@ -1,8 +1,15 @@
 //! FIXME: write short doc here
 
-use hir::{Crate, ImplDef, Semantics};
-use ra_ide_db::RootDatabase;
-use ra_syntax::{algo::find_node_at_offset, ast, AstNode};
+use hir::{
+    AsAssocItem, AssocItem, AssocItemContainer, Crate, HasSource, ImplDef, ModuleDef, Semantics,
+};
+use ra_ide_db::{
+    defs::{classify_name, classify_name_ref, Definition},
+    RootDatabase,
+};
+use ra_syntax::{
+    algo::find_node_at_offset, ast, match_ast, AstNode, SyntaxKind::IDENT, SyntaxNode,
+};
 
 use crate::{display::ToNav, FilePosition, NavigationTarget, RangeInfo};
 
@@ -14,6 +21,10 @@ pub(crate) fn goto_implementation(
     let source_file = sema.parse(position.file_id);
     let syntax = source_file.syntax().clone();
 
+    if let Some(res) = impls_for_assoc_item(&sema, &syntax, position) {
+        return Some(res);
+    }
+
     let krate = sema.to_module_def(position.file_id)?.krate();
 
     if let Some(nominal_def) = find_node_at_offset::<ast::NominalDef>(&syntax, position.offset) {
@@ -31,6 +42,60 @@ pub(crate) fn goto_implementation(
     None
 }
 
+/// For an item declared in a trait (or a call of one, including through
+/// `dyn Trait`), the items that implement it in every crate that can see the
+/// trait. If some impl relies on the default, the default is listed as well.
+fn impls_for_assoc_item(
+    sema: &Semantics<RootDatabase>,
+    syntax: &SyntaxNode,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let db = sema.db;
+    let token = syntax.token_at_offset(position.offset).find(|it| it.kind() == IDENT)?;
+    let def = match_ast! {
+        match (token.parent()) {
+            ast::NameRef(name_ref) => classify_name_ref(sema, &name_ref)?.definition(),
+            ast::Name(name) => classify_name(sema, &name)?.definition(),
+            _ => return None,
+        }
+    };
+    let assoc = match def {
+        Definition::ModuleDef(ModuleDef::Function(it)) => it.as_assoc_item(db),
+        Definition::ModuleDef(ModuleDef::Const(it)) => it.as_assoc_item(db),
+        Definition::ModuleDef(ModuleDef::TypeAlias(it)) => it.as_assoc_item(db),
+        _ => None,
+    }?;
+    let trait_ = match assoc.container(db) {
+        AssocItemContainer::Trait(it) => it,
+        AssocItemContainer::ImplDef(_) => return None,
+    };
+
+    let mut navs = Vec::new();
+    let mut uses_default = false;
+    for krate in trait_.module(db).krate().transitive_reverse_dependencies(db) {
+        for impl_def in ImplDef::for_trait(db, krate, trait_) {
+            let item = impl_def.items(db).into_iter().find(|&item| item.corresponds_to(db, assoc));
+            match item {
+                Some(item) => navs.push(item.to_nav(db)),
+                None => uses_default = true,
+            }
+        }
+    }
+    if uses_default && has_default(db, assoc) {
+        navs.push(assoc.to_nav(db));
+    }
+
+    Some(RangeInfo::new(token.text_range(), navs))
+}
+
+fn has_default(db: &RootDatabase, item: AssocItem) -> bool {
+    match item {
+        AssocItem::Function(it) => it.source(db).value.body().is_some(),
+        AssocItem::Const(it) => it.source(db).value.body().is_some(),
+        AssocItem::TypeAlias(it) => it.source(db).value.type_ref().is_some(),
+    }
+}
+
 fn impls_for_def(
     sema: &Semantics<RootDatabase>,
     node: &ast::NominalDef,
@@ -198,4 +263,86 @@ mod tests {
             &["impl IMPL_DEF FileId(1) 0..15"],
         );
     }
+
+    #[test]
+    fn goto_implementation_for_trait_method() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T { fn f<|>oo(&self); }
+            struct A;
+            struct B;
+            impl T for A { fn foo(&self) {} }
+            impl T for B { fn foo(&self) {} }
+            ",
+            &["foo FN_DEF FileId(1) 62..78 65..68", "foo FN_DEF FileId(1) 96..112 99..102"],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_for_assoc_type_and_const() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T { type It<|>em; }
+            struct A;
+            impl T for A { type Item = u32; }
+            ",
+            &["Item TYPE_ALIAS_DEF FileId(1) 48..64 53..57"],
+        );
+        check_goto(
+            "
+            //- /lib.rs
+            trait T { const <|>C: u32; }
+            struct A;
+            impl T for A { const C: u32 = 0; }
+            ",
+            &["C CONST_DEF FileId(1) 51..68 57..58"],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_for_defaulted_trait_method() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T { fn f<|>oo(&self) {} }
+            struct A;
+            struct B;
+            impl T for A { fn foo(&self) {} }
+            impl T for B {}
+            ",
+            &["foo FN_DEF FileId(1) 10..26 13..16", "foo FN_DEF FileId(1) 64..80 67..70"],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_for_dyn_method_call() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T { fn foo(&self); }
+            struct A;
+            impl T for A { fn foo(&self) {} }
+            fn bar(t: &dyn T) { t.f<|>oo(); }
+            ",
+            &["foo FN_DEF FileId(1) 52..68 55..58"],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_for_trait_method_in_dependent_crate() {
+        check_goto(
+            "
+            //- /main.rs
+            struct A;
+            impl lib::T for A { fn foo(&self) {} }
+            //- /lib/lib.rs
+            pub trait T { fn f<|>oo(&self); }
+            pub struct B;
+            impl T for B { fn foo(&self) {} }
+            ",
+            &["foo FN_DEF FileId(1) 30..46 33..36", "foo FN_DEF FileId(2) 60..76 63..66"],
+        );
+    }
 }
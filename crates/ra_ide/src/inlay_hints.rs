@@ -1,6 +1,6 @@
 //! This module defines multiple types of inlay hints and their visibility
 
-use hir::{Adt, HirDisplay, Semantics, Type};
+use hir::{Adt, BindingMode, HirDisplay, Mutability, Semantics, Type};
 use ra_ide_db::RootDatabase;
 use ra_prof::profile;
 use ra_syntax::{
@@ -16,12 +16,19 @@ pub struct InlayHintsConfig {
     pub type_hints: bool,
     pub parameter_hints: bool,
     pub chaining_hints: bool,
+    pub binding_mode_hints: bool,
     pub max_length: Option<usize>,
 }
 
 impl Default for InlayHintsConfig {
     fn default() -> Self {
-        Self { type_hints: true, parameter_hints: true, chaining_hints: true, max_length: None }
+        Self {
+            type_hints: true,
+            parameter_hints: true,
+            chaining_hints: true,
+            binding_mode_hints: false,
+            max_length: None,
+        }
     }
 }
 
@@ -30,6 +37,7 @@ pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    BindingModeHint,
 }
 
 #[derive(Debug)]
@@ -58,7 +66,10 @@ pub(crate) fn inlay_hints(
             match node {
                 ast::CallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
                 ast::MethodCallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
-                ast::BindPat(it) => { get_bind_pat_hints(&mut res, &sema, config, it); },
+                ast::BindPat(it) => {
+                    get_binding_mode_hints(&mut res, &sema, config, &it);
+                    get_bind_pat_hints(&mut res, &sema, config, it);
+                },
                 _ => (),
             }
         }
@@ -180,6 +191,33 @@ fn get_bind_pat_hints(
     Some(())
 }
 
+fn get_binding_mode_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    pat: &ast::BindPat,
+) -> Option<()> {
+    if !config.binding_mode_hints {
+        return None;
+    }
+    // Explicit `ref` bindings already say how they bind.
+    if pat.ref_token().is_some() {
+        return None;
+    }
+
+    let label = match sema.binding_mode_of_pat(pat)? {
+        BindingMode::Move => return None,
+        BindingMode::Ref(Mutability::Shared) => "ref",
+        BindingMode::Ref(Mutability::Mut) => "ref mut",
+    };
+    acc.push(InlayHint {
+        range: pat.syntax().text_range(),
+        kind: InlayKind::BindingModeHint,
+        label: label.into(),
+    });
+    Some(())
+}
+
 fn pat_is_enum_variant(db: &RootDatabase, bind_pat: &ast::BindPat, pat_ty: &Type) -> bool {
     if let Some(Adt::Enum(enum_data)) = pat_ty.as_adt() {
         let pat_text = bind_pat.to_string();
@@ -343,7 +381,7 @@ mod tests {
                 let _x = foo(4, 4);
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: true, type_hints: false, chaining_hints: false, max_length: None}).unwrap(), @r###"
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: true, type_hints: false, chaining_hints: false, binding_mode_hints: false, max_length: None}).unwrap(), @r###"
         [
             InlayHint {
                 range: 106..107,
@@ -367,7 +405,7 @@ mod tests {
                 let _x = foo(4, 4);
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ type_hints: false, parameter_hints: false, chaining_hints: false, max_length: None}).unwrap(), @r###"[]"###);
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ type_hints: false, parameter_hints: false, chaining_hints: false, binding_mode_hints: false, max_length: None}).unwrap(), @r###"[]"###);
     }
 
     #[test]
@@ -379,7 +417,7 @@ mod tests {
                 let _x = foo(4, 4);
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ type_hints: true, parameter_hints: false, chaining_hints: false, max_length: None}).unwrap(), @r###"
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ type_hints: true, parameter_hints: false, chaining_hints: false, binding_mode_hints: false, max_length: None}).unwrap(), @r###"
         [
             InlayHint {
                 range: 97..99,
@@ -1219,7 +1257,7 @@ fn main() {
                     .into_c();
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, max_length: None}).unwrap(), @r###"
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, binding_mode_hints: false, max_length: None}).unwrap(), @r###"
         [
             InlayHint {
                 range: 232..269,
@@ -1248,7 +1286,7 @@ fn main() {
                 let c = A(B(C)).into_b().into_c();
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, max_length: None}).unwrap(), @r###"[]"###);
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, binding_mode_hints: false, max_length: None}).unwrap(), @r###"[]"###);
     }
 
     #[test]
@@ -1273,7 +1311,7 @@ fn main() {
                     .foo();
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, max_length: None}).unwrap(), @r###"
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, binding_mode_hints: false, max_length: None}).unwrap(), @r###"
         [
             InlayHint {
                 range: 252..323,
@@ -1311,7 +1349,7 @@ fn main() {
                     .into_c();
             }"#,
         );
-        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, max_length: None}).unwrap(), @r###"
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: true, binding_mode_hints: false, max_length: None}).unwrap(), @r###"
         [
             InlayHint {
                 range: 403..452,
@@ -1325,4 +1363,36 @@ fn main() {
             },
         ]"###);
     }
+
+    #[test]
+    fn binding_mode_hints() {
+        let (analysis, file_id) = single_file(
+            r#"
+            struct S { a: u32 }
+            fn main() {
+                let s = S { a: 1 };
+                let S { a } = &s;
+                let S { ref a } = &s;
+                let (x, y) = &mut (1, 2);
+            }"#,
+        );
+        assert_debug_snapshot!(analysis.inlay_hints(file_id, &InlayHintsConfig{ parameter_hints: false, type_hints: false, chaining_hints: false, binding_mode_hints: true, max_length: None}).unwrap(), @r###"
+        [
+            InlayHint {
+                range: 117..118,
+                kind: BindingModeHint,
+                label: "ref",
+            },
+            InlayHint {
+                range: 186..187,
+                kind: BindingModeHint,
+                label: "ref mut",
+            },
+            InlayHint {
+                range: 189..190,
+                kind: BindingModeHint,
+                label: "ref mut",
+            },
+        ]"###);
+    }
 }
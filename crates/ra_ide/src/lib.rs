@@ -22,10 +22,13 @@ mod status;
 mod completion;
 mod runnables;
 mod goto_definition;
+mod goto_declaration;
 mod goto_type_definition;
 mod extend_selection;
 mod hover;
+mod external_docs;
 mod call_hierarchy;
+mod type_hierarchy;
 mod call_info;
 mod syntax_highlighting;
 mod parent_module;
@@ -41,6 +44,8 @@ mod matching_brace;
 mod display;
 mod inlay_hints;
 mod expand_macro;
+mod reveal_type;
+mod trace_trait_solve;
 mod ssr;
 
 #[cfg(test)]
@@ -53,7 +58,8 @@ use std::sync::Arc;
 use ra_cfg::CfgOptions;
 use ra_db::{
     salsa::{self, ParallelDatabase},
-    CheckCanceled, Env, FileLoader, SourceDatabase,
+    CheckCanceled, Env, FileLoader, RelativePath, RelativePathBuf, SourceDatabase,
+    SourceDatabaseExt,
 };
 use ra_ide_db::{
     symbol_index::{self, FileSymbol},
@@ -73,17 +79,19 @@ pub use crate::{
     display::{file_structure, FunctionSignature, NavigationTarget, StructureNode},
     expand_macro::ExpandedMacro,
     folding_ranges::{Fold, FoldKind},
-    hover::HoverResult,
+    hover::{HoverAction, HoverGotoTypeData, HoverLink, HoverResult},
     inlay_hints::{InlayHint, InlayHintsConfig, InlayKind},
     references::{Declaration, Reference, ReferenceAccess, ReferenceKind, ReferenceSearchResult},
+    reveal_type::RevealedType,
     runnables::{Runnable, RunnableKind, TestId},
     ssr::SsrError,
     syntax_highlighting::{
         Highlight, HighlightModifier, HighlightModifiers, HighlightTag, HighlightedRange,
     },
+    type_hierarchy::{CrateTraits, TypeHierarchy},
 };
 
-pub use hir::Documentation;
+pub use hir::{Documentation, TraitSolverConfig};
 pub use ra_db::{
     Canceled, CrateGraph, CrateId, Edition, FileId, FilePosition, FileRange, SourceRootId,
 };
@@ -105,6 +113,8 @@ pub struct Diagnostic {
     pub range: TextRange,
     pub fix: Option<SourceChange>,
     pub severity: Severity,
+    /// Other items this diagnostic is about, with a note on each.
+    pub related: Vec<(NavigationTarget, String)>,
 }
 
 /// Info associated with a text range.
@@ -149,6 +159,10 @@ impl AnalysisHost {
         self.db.update_lru_capacity(lru_capacity);
     }
 
+    pub fn update_trait_solver_config(&mut self, config: TraitSolverConfig) {
+        self.db.update_trait_solver_config(config);
+    }
+
     /// Returns a snapshot of the current state, which you can query for
     /// semantic information.
     pub fn analysis(&self) -> Analysis {
@@ -286,6 +300,21 @@ impl Analysis {
         self.with_db(|db| expand_macro::expand_macro(db, position))
     }
 
+    /// Returns the type at `position` with each `impl Trait` returned from a
+    /// function replaced by the concrete type it hides.
+    pub fn reveal_type(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Option<RangeInfo<RevealedType>>> {
+        self.with_db(|db| reveal_type::reveal_type(db, position))
+    }
+
+    /// Returns what the trait solver does for the method call at `position`,
+    /// for debug purposes.
+    pub fn trace_trait_solve(&self, position: FilePosition) -> Cancelable<Option<String>> {
+        self.with_db(|db| trace_trait_solve::trace_trait_solve(db, position))
+    }
+
     /// Returns an edit to remove all newlines in the range, cleaning up minor
     /// stuff like trailing commas.
     pub fn join_lines(&self, frange: FileRange) -> Cancelable<SourceChange> {
@@ -359,6 +388,14 @@ impl Analysis {
         self.with_db(|db| goto_definition::goto_definition(db, position))
     }
 
+    /// Returns the declarations from the symbol at `position`.
+    pub fn goto_declaration(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Option<RangeInfo<Vec<NavigationTarget>>>> {
+        self.with_db(|db| goto_declaration::goto_declaration(db, position))
+    }
+
     /// Returns the impls from the symbol at `position`.
     pub fn goto_implementation(
         &self,
@@ -389,6 +426,11 @@ impl Analysis {
         self.with_db(|db| hover::hover(db, position))
     }
 
+    /// Returns the URL of the rustdoc page of the item at the given position.
+    pub fn external_docs(&self, position: FilePosition) -> Cancelable<Option<String>> {
+        self.with_db(|db| external_docs::external_docs(db, position))
+    }
+
     /// Computes parameter information for the given call expression.
     pub fn call_info(&self, position: FilePosition) -> Cancelable<Option<CallInfo>> {
         self.with_db(|db| call_info::call_info(db, position))
@@ -412,6 +454,14 @@ impl Analysis {
         self.with_db(|db| call_hierarchy::outgoing_calls(db, position))
     }
 
+    /// Computes the type hierarchy of the trait or type at the given file position.
+    pub fn type_hierarchy(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Option<RangeInfo<TypeHierarchy>>> {
+        self.with_db(|db| type_hierarchy::type_hierarchy(db, position))
+    }
+
     /// Returns a `mod name;` declaration which created the current module.
     pub fn parent_module(&self, position: FilePosition) -> Cancelable<Vec<NavigationTarget>> {
         self.with_db(|db| parent_module::parent_module(db, position))
@@ -461,6 +511,18 @@ impl Analysis {
         self.with_db(|db| completion::completions(db, position, config).map(Into::into))
     }
 
+    /// Computes the detail, documentation and `use` edit of a completion item,
+    /// when the completions are made with `CompletionConfig::resolve_lazily`.
+    /// The item is found by its label.
+    pub fn resolve_completion(
+        &self,
+        position: FilePosition,
+        config: &CompletionConfig,
+        label: &str,
+    ) -> Cancelable<Option<CompletionItem>> {
+        self.with_db(|db| completion::resolve_completion(db, position, config, label))
+    }
+
     /// Computes assists (aka code actions aka intentions) for the given
     /// position.
     pub fn assists(&self, frange: FileRange) -> Cancelable<Vec<Assist>> {
@@ -482,6 +544,32 @@ impl Analysis {
         self.with_db(|db| references::rename(db, position, new_name))
     }
 
+    /// Returns the edits to `mod` declarations and paths required when the file
+    /// of a module is renamed so that the module is called `new_name`.
+    pub fn will_rename_file(
+        &self,
+        file_id: FileId,
+        new_name: &str,
+    ) -> Cancelable<Option<SourceChange>> {
+        self.with_db(|db| references::will_rename_file(db, file_id, new_name))
+    }
+
+    /// Computes the edits to `mod` declarations and paths when the file of a
+    /// module is moved to `new_path`, relative to its source root, possibly into
+    /// another directory. The files of its submodules move along.
+    pub fn will_move_file(
+        &self,
+        file_id: FileId,
+        new_path: &RelativePath,
+    ) -> Cancelable<Option<SourceChange>> {
+        self.with_db(|db| references::will_move_file(db, file_id, new_path))
+    }
+
+    /// The path of the file, relative to its source root.
+    pub fn file_relative_path(&self, file_id: FileId) -> Cancelable<RelativePathBuf> {
+        self.with_db(|db| db.file_relative_path(file_id))
+    }
+
     pub fn structural_search_replace(
         &self,
         query: &str,
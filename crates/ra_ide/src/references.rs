@@ -14,6 +14,7 @@ mod rename;
 use hir::Semantics;
 use ra_ide_db::{
     defs::{classify_name, classify_name_ref, Definition},
+    doc_links::{doc_links, resolve_doc_link},
     search::SearchScope,
     RootDatabase,
 };
@@ -26,7 +27,7 @@ use ra_syntax::{
 
 use crate::{display::TryToNav, FilePosition, FileRange, NavigationTarget, RangeInfo};
 
-pub(crate) use self::rename::rename;
+pub(crate) use self::rename::{rename, will_move_file, will_rename_file};
 
 pub use ra_ide_db::search::{Reference, ReferenceAccess, ReferenceKind};
 
@@ -136,12 +137,27 @@ fn find_name(
         return Some(RangeInfo::new(range, def));
     }
     let name_ref =
-        sema.find_node_at_offset_with_descend::<ast::NameRef>(&syntax, position.offset)?;
+        match sema.find_node_at_offset_with_descend::<ast::NameRef>(&syntax, position.offset) {
+            Some(it) => it,
+            None => return find_doc_link_target(sema, syntax, position),
+        };
     let def = classify_name_ref(sema, &name_ref)?.definition();
     let range = name_ref.syntax().text_range();
     Some(RangeInfo::new(range, def))
 }
 
+/// The definition an intra-doc link at `position` points to.
+fn find_doc_link_target(
+    sema: &Semantics<RootDatabase>,
+    syntax: &SyntaxNode,
+    position: FilePosition,
+) -> Option<RangeInfo<Definition>> {
+    let comment = syntax.token_at_offset(position.offset).find_map(ast::Comment::cast)?;
+    let link = doc_links(&comment).into_iter().find_map(|it| it.prefix_at(position.offset))?;
+    let def = resolve_doc_link(sema, &comment, &link)?;
+    Some(RangeInfo::new(link.name_range(), def))
+}
+
 fn decl_access(def: &Definition, syntax: &SyntaxNode, range: TextRange) -> Option<ReferenceAccess> {
     match def {
         Definition::Local(_) | Definition::Field(_) => {}
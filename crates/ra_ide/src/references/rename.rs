@@ -1,10 +1,15 @@
 //! FIXME: write short doc here
 
-use hir::{ModuleSource, Semantics};
-use ra_db::{RelativePath, RelativePathBuf, SourceDatabaseExt};
-use ra_ide_db::RootDatabase;
+use hir::{ModuleDef, ModuleSource, Semantics};
+use ra_db::{FileId, RelativePath, RelativePathBuf, SourceDatabaseExt, SourceRootId};
+use ra_ide_db::{
+    defs::{classify_name_ref, Definition},
+    RootDatabase,
+};
 use ra_syntax::{
-    algo::find_node_at_offset, ast, lex_single_valid_syntax_kind, AstNode, SyntaxKind, SyntaxNode,
+    algo::find_node_at_offset,
+    ast::{self, AttrsOwner, ModuleItemOwner},
+    lex_single_valid_syntax_kind, AstNode, NodeOrToken, SyntaxKind, SyntaxNode, TextSize,
 };
 use ra_text_edit::TextEdit;
 use test_utils::tested_by;
@@ -19,9 +24,8 @@ pub(crate) fn rename(
     position: FilePosition,
     new_name: &str,
 ) -> Option<RangeInfo<SourceChange>> {
-    match lex_single_valid_syntax_kind(new_name)? {
-        SyntaxKind::IDENT | SyntaxKind::UNDERSCORE => (),
-        _ => return None,
+    if !is_valid_name(new_name) {
+        return None;
     }
 
     let sema = Semantics::new(db);
@@ -30,13 +34,316 @@ pub(crate) fn rename(
         find_name_and_module_at_offset(source_file.syntax(), position)
     {
         let range = ast_name.syntax().text_range();
-        rename_mod(&sema, &ast_name, &ast_module, position, new_name)
+        rename_mod(&sema, &ast_name, &ast_module, position, new_name, true)
             .map(|info| RangeInfo::new(range, info))
+    } else if let Some(name_ref) =
+        find_node_at_offset::<ast::NameRef>(source_file.syntax(), position.offset)
+    {
+        let range = name_ref.syntax().text_range();
+        match module_decl_position(&sema, &name_ref) {
+            Some(decl_position) => rename_mod_at(&sema, decl_position, new_name, true)
+                .map(|info| RangeInfo::new(range, info)),
+            None => rename_reference(sema.db, position, new_name),
+        }
     } else {
         rename_reference(sema.db, position, new_name)
     }
 }
 
+/// Computes the edits to `mod` declarations and paths when the file of the
+/// module in `file_id` is renamed by the user, so that the module is now called
+/// `new_name`. The file itself is already being moved, so there are no file
+/// system edits.
+pub(crate) fn will_rename_file(
+    db: &RootDatabase,
+    file_id: FileId,
+    new_name: &str,
+) -> Option<SourceChange> {
+    if !is_valid_name(new_name) {
+        return None;
+    }
+
+    let sema = Semantics::new(db);
+    let module = sema.to_module_def(file_id)?;
+    let decl = module.declaration_source(db)?;
+    let name = decl.value.name()?;
+    let frange = hir::original_range(db, decl.with_value(name.syntax()));
+    let position = FilePosition { file_id: frange.file_id, offset: frange.range.start() };
+    rename_mod_at(&sema, position, new_name, false)
+}
+
+/// Computes the edits when the file of the module in `file_id` is moved by the
+/// user to `new_path`, in the same source root. Within the same directory this
+/// is a rename; otherwise the `mod` declaration moves from the old parent module
+/// to the new one, the paths to the module are rewritten and the files of its
+/// submodules move along. A `#[path]` attribute is updated instead if it has one.
+pub(crate) fn will_move_file(
+    db: &RootDatabase,
+    file_id: FileId,
+    new_path: &RelativePath,
+) -> Option<SourceChange> {
+    let old_path = db.file_relative_path(file_id);
+    let (old_dir, old_name) = module_dir_and_name(&old_path)?;
+    let (new_dir, new_name) = module_dir_and_name(new_path)?;
+    if old_dir == new_dir {
+        if old_name == new_name {
+            return None;
+        }
+        let mut change = will_rename_file(db, file_id, &new_name)?;
+        change.file_system_edits = dir_file_moves(
+            db,
+            db.file_source_root(file_id),
+            &old_dir.join(&old_name),
+            &new_dir.join(&new_name),
+            file_id,
+        );
+        return Some(change);
+    }
+    if !is_valid_name(&new_name) {
+        return None;
+    }
+
+    let sema = Semantics::new(db);
+    let module = sema.to_module_def(file_id)?;
+    let decl = module.declaration_source(db)?;
+    let decl_file = decl.file_id.original_file(db);
+    if decl.file_id != decl_file.into() {
+        // declared by a macro
+        return None;
+    }
+    let decl = decl.value;
+    // Declarations nested in inline modules resolve relative to other
+    // directories; leave those alone.
+    decl.syntax().parent().and_then(ast::SourceFile::cast)?;
+
+    if let Some(path_literal) = path_attr_literal(&decl) {
+        let decl_path = db.file_relative_path(decl_file);
+        let decl_dir = decl_path.parent().unwrap_or_else(|| RelativePath::new(""));
+        let new_attr_path = relative_path_between(decl_dir, new_path);
+        let edit =
+            TextEdit::replace(path_literal.syntax().text_range(), format!("\"{}\"", new_attr_path));
+        return Some(SourceChange::source_file_edits(
+            "Move module",
+            vec![SourceFileEdit { file_id: decl_file, edit }],
+        ));
+    }
+
+    let source_root = db.file_source_root(file_id);
+    let new_parent = dir_owner(&sema, source_root, &new_dir)?;
+
+    // Keep attributes, doc comments and visibility, only the name may change.
+    let decl_range = decl.syntax().text_range();
+    let name_range = decl.name()?.syntax().text_range() - decl_range.start();
+    let mut decl_text = decl.syntax().text().to_string();
+    decl_text.replace_range(std::ops::Range::<usize>::from(name_range), &new_name);
+
+    let removal = TextEdit::delete(extend_over_newline(&decl));
+    let new_parent_file = sema.parse(new_parent);
+    let insertion = match new_parent_file
+        .items()
+        .filter_map(|it| match it {
+            ast::ModuleItem::Module(it) => Some(it),
+            _ => None,
+        })
+        .last()
+    {
+        Some(last_mod) => {
+            TextEdit::insert(last_mod.syntax().text_range().end(), format!("\n{}", decl_text))
+        }
+        None => match inner_attrs_end(&new_parent_file) {
+            Some(offset) => TextEdit::insert(offset, format!("\n{}", decl_text)),
+            None => TextEdit::insert(0.into(), format!("{}\n", decl_text)),
+        },
+    };
+
+    let new_parent_module = sema.to_module_def(new_parent)?;
+    let mut new_mod_path = vec!["crate".to_string()];
+    new_mod_path.extend(
+        new_parent_module
+            .path_to_root(db)
+            .into_iter()
+            .rev()
+            .filter_map(|it| it.name(db))
+            .map(|it| it.to_string()),
+    );
+    new_mod_path.push(new_name.clone());
+
+    let mut source_file_edits = vec![
+        SourceFileEdit { file_id: decl_file, edit: removal },
+        SourceFileEdit { file_id: new_parent, edit: insertion },
+    ];
+    source_file_edits.extend(rewrite_module_paths(&sema, module, &new_mod_path.join("::")));
+    let file_system_edits = dir_file_moves(
+        db,
+        source_root,
+        &old_dir.join(&old_name),
+        &new_dir.join(&new_name),
+        file_id,
+    );
+    Some(SourceChange::from_edits("Move module", source_file_edits, file_system_edits))
+}
+
+/// The end of the inner attributes and doc comments at the start of `file`.
+fn inner_attrs_end(file: &ast::SourceFile) -> Option<TextSize> {
+    file.syntax()
+        .children_with_tokens()
+        .take_while(|it| match it {
+            NodeOrToken::Node(node) => {
+                ast::Attr::cast(node.clone()).map_or(false, |it| it.kind() == ast::AttrKind::Inner)
+            }
+            NodeOrToken::Token(token) => match ast::Comment::cast(token.clone()) {
+                Some(comment) => comment.kind().doc == Some(ast::CommentPlacement::Inner),
+                None => token.kind() == SyntaxKind::WHITESPACE,
+            },
+        })
+        .filter(|it| it.kind() != SyntaxKind::WHITESPACE)
+        .last()
+        .map(|it| it.text_range().end())
+}
+
+/// Replaces the paths to `module` in its crate with `new_path`, written from
+/// the crate root. Paths nested in a use tree list, like `a::foo` in
+/// `use crate::{a::foo}`, can't be written that way and are left alone.
+fn rewrite_module_paths(
+    sema: &Semantics<RootDatabase>,
+    module: hir::Module,
+    new_path: &str,
+) -> Vec<SourceFileEdit> {
+    let krate = module.krate();
+    Definition::ModuleDef(ModuleDef::Module(module))
+        .find_usages(sema.db, None)
+        .into_iter()
+        .filter_map(|reference| {
+            let file_id = reference.file_range.file_id;
+            if sema.to_module_def(file_id)?.krate() != krate {
+                return None;
+            }
+            let source_file = sema.parse(file_id);
+            let name_ref = find_node_at_offset::<ast::NameRef>(
+                source_file.syntax(),
+                reference.file_range.range.start(),
+            )?;
+            let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?;
+            let path = segment.syntax().parent().and_then(ast::Path::cast)?;
+            let in_use_tree_list = path
+                .syntax()
+                .ancestors()
+                .find_map(ast::UseTree::cast)
+                .and_then(|tree| tree.syntax().parent())
+                .map_or(false, |it| ast::UseTreeList::can_cast(it.kind()));
+            if in_use_tree_list {
+                return None;
+            }
+            let edit = TextEdit::replace(path.syntax().text_range(), new_path.to_string());
+            Some(SourceFileEdit { file_id, edit })
+        })
+        .collect()
+}
+
+/// For the file of a module, the directory of the module's parent and the name
+/// of the module: `a/foo.rs` and `a/foo/mod.rs` both give `a` and `foo`.
+fn module_dir_and_name(path: &RelativePath) -> Option<(RelativePathBuf, String)> {
+    if path.extension() != Some("rs") {
+        return None;
+    }
+    let parent = path.parent().unwrap_or_else(|| RelativePath::new(""));
+    match path.file_stem()? {
+        "mod" => {
+            let dir = parent.parent().unwrap_or_else(|| RelativePath::new(""));
+            Some((dir.to_relative_path_buf(), parent.file_name()?.to_string()))
+        }
+        stem => Some((parent.to_relative_path_buf(), stem.to_string())),
+    }
+}
+
+/// The file of the module whose submodules live in `dir`: `dir/mod.rs`,
+/// `dir.rs` or a crate root in `dir`.
+fn dir_owner(
+    sema: &Semantics<RootDatabase>,
+    source_root: SourceRootId,
+    dir: &RelativePath,
+) -> Option<FileId> {
+    let source_root = sema.db.source_root(source_root);
+    // `lib.rs` and `main.rs` only own their directory as crate roots.
+    let mut candidates =
+        vec![(dir.join("mod.rs"), false), (dir.join("lib.rs"), true), (dir.join("main.rs"), true)];
+    if let Some(name) = dir.file_name() {
+        candidates.push((dir.with_file_name(format!("{}.rs", name)), false));
+    }
+    candidates.into_iter().find_map(|(path, must_be_root)| {
+        let file_id = source_root.file_by_relative_path(&path)?;
+        let module = sema.to_module_def(file_id)?;
+        if must_be_root && module.parent(sema.db).is_some() {
+            return None;
+        }
+        Some(file_id)
+    })
+}
+
+fn path_attr_literal(decl: &ast::Module) -> Option<ast::Literal> {
+    decl.attrs().find_map(|attr| {
+        if attr.simple_name()? != "path" {
+            return None;
+        }
+        attr.syntax().descendants().find_map(ast::Literal::cast)
+    })
+}
+
+/// The path of `to` as seen from `from_dir`, both relative to the same root.
+fn relative_path_between(from_dir: &RelativePath, to: &RelativePath) -> String {
+    let from: Vec<_> = from_dir.as_str().split('/').filter(|it| !it.is_empty()).collect();
+    let to: Vec<_> = to.as_str().split('/').filter(|it| !it.is_empty()).collect();
+    let common = from.iter().zip(to.iter()).take_while(|(a, b)| a == b).count();
+    let mut res = vec![".."; from.len() - common];
+    res.extend_from_slice(&to[common..]);
+    res.join("/")
+}
+
+/// The range of `decl`, including the line break after it.
+fn extend_over_newline(decl: &ast::Module) -> TextRange {
+    let range = decl.syntax().text_range();
+    match decl.syntax().next_sibling_or_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE && ws.to_string().starts_with('\n') => {
+            TextRange::new(range.start(), range.end() + TextSize::of('\n'))
+        }
+        _ => range,
+    }
+}
+
+fn is_valid_name(new_name: &str) -> bool {
+    match lex_single_valid_syntax_kind(new_name) {
+        Some(SyntaxKind::IDENT) | Some(SyntaxKind::UNDERSCORE) => true,
+        _ => false,
+    }
+}
+
+/// If `name_ref` refers to a module declared with `mod foo;` or `mod foo {}`,
+/// the position of that declaration's name.
+fn module_decl_position(
+    sema: &Semantics<RootDatabase>,
+    name_ref: &ast::NameRef,
+) -> Option<FilePosition> {
+    let module = match classify_name_ref(sema, name_ref)?.definition() {
+        Definition::ModuleDef(ModuleDef::Module(it)) => it,
+        _ => return None,
+    };
+    let decl = module.declaration_source(sema.db)?;
+    let name = decl.value.name()?;
+    let frange = hir::original_range(sema.db, decl.with_value(name.syntax()));
+    Some(FilePosition { file_id: frange.file_id, offset: frange.range.start() })
+}
+
+fn rename_mod_at(
+    sema: &Semantics<RootDatabase>,
+    position: FilePosition,
+    new_name: &str,
+    move_files: bool,
+) -> Option<SourceChange> {
+    let source_file = sema.parse(position.file_id);
+    let (ast_name, ast_module) = find_name_and_module_at_offset(source_file.syntax(), position)?;
+    rename_mod(sema, &ast_name, &ast_module, position, new_name, move_files)
+}
+
 fn find_name_and_module_at_offset(
     syntax: &SyntaxNode,
     position: FilePosition,
@@ -76,35 +383,13 @@ fn rename_mod(
     ast_module: &ast::Module,
     position: FilePosition,
     new_name: &str,
+    move_files: bool,
 ) -> Option<SourceChange> {
     let mut source_file_edits = Vec::new();
     let mut file_system_edits = Vec::new();
-    if let Some(module) = sema.to_def(ast_module) {
-        let src = module.definition_source(sema.db);
-        let file_id = src.file_id.original_file(sema.db);
-        match src.value {
-            ModuleSource::SourceFile(..) => {
-                let mod_path: RelativePathBuf = sema.db.file_relative_path(file_id);
-                // mod is defined in path/to/dir/mod.rs
-                let dst_path = if mod_path.file_stem() == Some("mod") {
-                    mod_path
-                        .parent()
-                        .and_then(|p| p.parent())
-                        .or_else(|| Some(RelativePath::new("")))
-                        .map(|p| p.join(new_name).join("mod.rs"))
-                } else {
-                    Some(mod_path.with_file_name(new_name).with_extension("rs"))
-                };
-                if let Some(path) = dst_path {
-                    let move_file = FileSystemEdit::MoveFile {
-                        src: file_id,
-                        dst_source_root: sema.db.file_source_root(position.file_id),
-                        dst_path: path,
-                    };
-                    file_system_edits.push(move_file);
-                }
-            }
-            ModuleSource::Module(..) => {}
+    if move_files {
+        if let Some(module) = sema.to_def(ast_module) {
+            file_system_edits = module_file_moves(sema.db, module, new_name);
         }
     }
 
@@ -125,6 +410,78 @@ fn rename_mod(
     Some(SourceChange::from_edits("Rename", source_file_edits, file_system_edits))
 }
 
+/// Moves the file of `module`, and the files of its submodules, so that they
+/// still match the module tree once `module` is called `new_name`.
+fn module_file_moves(
+    db: &RootDatabase,
+    module: hir::Module,
+    new_name: &str,
+) -> Vec<FileSystemEdit> {
+    let src = module.definition_source(db);
+    let file_id = match src.value {
+        ModuleSource::SourceFile(..) => src.file_id.original_file(db),
+        ModuleSource::Module(..) => return Vec::new(),
+    };
+    let source_root = db.file_source_root(file_id);
+    let mod_path: RelativePathBuf = db.file_relative_path(file_id);
+
+    let mut res = Vec::new();
+    // The directory holding the submodules, and where it ends up.
+    let (dir, new_dir) = if mod_path.file_stem() == Some("mod") {
+        // mod is defined in path/to/dir/mod.rs, the whole directory moves
+        let dir = match mod_path.parent() {
+            Some(it) => it.to_relative_path_buf(),
+            None => return res,
+        };
+        let new_dir = dir.parent().unwrap_or_else(|| RelativePath::new("")).join(new_name);
+        (dir, new_dir)
+    } else {
+        // mod is defined in path/to/dir/foo.rs, submodules live in path/to/dir/foo/
+        let stem = match mod_path.file_stem() {
+            Some(it) => it,
+            None => return res,
+        };
+        res.push(FileSystemEdit::MoveFile {
+            src: file_id,
+            dst_source_root: source_root,
+            dst_path: mod_path.with_file_name(new_name).with_extension("rs"),
+        });
+        (mod_path.with_file_name(stem), mod_path.with_file_name(new_name))
+    };
+
+    res.extend(dir_file_moves(db, source_root, &dir, &new_dir, file_id));
+    res
+}
+
+/// Moves the files under `dir` to `new_dir`, except for `skip`, which the
+/// caller moves itself.
+fn dir_file_moves(
+    db: &RootDatabase,
+    source_root: SourceRootId,
+    dir: &RelativePath,
+    new_dir: &RelativePath,
+    skip: FileId,
+) -> Vec<FileSystemEdit> {
+    let prefix = format!("{}/", dir.as_str());
+    let mut files = db.source_root(source_root).walk().collect::<Vec<_>>();
+    files.sort();
+    files
+        .into_iter()
+        .filter(|&file| file != skip)
+        .filter_map(|file| {
+            let path = db.file_relative_path(file);
+            if !path.as_str().starts_with(&prefix) {
+                return None;
+            }
+            Some(FileSystemEdit::MoveFile {
+                src: file,
+                dst_source_root: source_root,
+                dst_path: new_dir.join(&path.as_str()[prefix.len()..]),
+            })
+        })
+        .collect()
+}
+
 fn rename_reference(
     db: &RootDatabase,
     position: FilePosition,
@@ -150,8 +507,11 @@ mod tests {
     use ra_text_edit::TextEditBuilder;
     use test_utils::{assert_eq_text, covers};
 
+    use ra_db::RelativePath;
+
     use crate::{
-        mock_analysis::analysis_and_position, mock_analysis::single_file_with_position, FileId,
+        mock_analysis::analysis_and_position, mock_analysis::single_file_with_position,
+        mock_analysis::MockAnalysis, Analysis, FileId, FileSystemEdit, SourceChange,
     };
 
     #[test]
@@ -712,6 +1072,326 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_rename_mod_moves_submodule_files() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /lib.rs
+            mod fo<|>o;
+            //- /foo.rs
+            mod bar;
+            //- /foo/bar.rs
+            // empty
+            ",
+        );
+        let source_change = analysis.rename(position, "foo2").unwrap().unwrap();
+        assert_debug_snapshot!(&source_change.info.file_system_edits,
+        @r###"
+        [
+            MoveFile {
+                src: FileId(
+                    2,
+                ),
+                dst_source_root: SourceRootId(
+                    0,
+                ),
+                dst_path: "foo2.rs",
+            },
+            MoveFile {
+                src: FileId(
+                    3,
+                ),
+                dst_source_root: SourceRootId(
+                    0,
+                ),
+                dst_path: "foo2/bar.rs",
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_rename_mod_from_path() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /lib.rs
+            mod foo;
+            fn f() { fo<|>o::bar() }
+            //- /foo.rs
+            pub fn bar() {}
+            ",
+        );
+        let source_change = analysis.rename(position, "foo2").unwrap();
+        assert_debug_snapshot!(&source_change,
+        @r###"
+        Some(
+            RangeInfo {
+                range: 18..21,
+                info: SourceChange {
+                    label: "Rename",
+                    source_file_edits: [
+                        SourceFileEdit {
+                            file_id: FileId(
+                                1,
+                            ),
+                            edit: TextEdit {
+                                indels: [
+                                    Indel {
+                                        insert: "foo2",
+                                        delete: 4..7,
+                                    },
+                                ],
+                            },
+                        },
+                        SourceFileEdit {
+                            file_id: FileId(
+                                1,
+                            ),
+                            edit: TextEdit {
+                                indels: [
+                                    Indel {
+                                        insert: "foo2",
+                                        delete: 18..21,
+                                    },
+                                ],
+                            },
+                        },
+                    ],
+                    file_system_edits: [
+                        MoveFile {
+                            src: FileId(
+                                2,
+                            ),
+                            dst_source_root: SourceRootId(
+                                0,
+                            ),
+                            dst_path: "foo2.rs",
+                        },
+                    ],
+                    cursor_position: None,
+                },
+            },
+        )
+        "###);
+    }
+
+    #[test]
+    fn test_will_rename_file() {
+        let mock = MockAnalysis::with_files(
+            "
+            //- /lib.rs
+            mod foo;
+            use foo::bar;
+            //- /foo.rs
+            pub fn bar() {}
+            ",
+        );
+        let file_id = mock.id_of("/foo.rs");
+        let analysis = mock.analysis();
+        let source_change = analysis.will_rename_file(file_id, "baz").unwrap().unwrap();
+        assert!(source_change.file_system_edits.is_empty());
+        let mut text = analysis.file_text(FileId(1)).unwrap().to_string();
+        let mut builder = TextEditBuilder::default();
+        for edit in source_change.source_file_edits {
+            assert_eq!(edit.file_id, FileId(1));
+            for indel in edit.edit.as_indels() {
+                builder.replace(indel.delete, indel.insert.clone());
+            }
+        }
+        builder.finish().apply(&mut text);
+        assert_eq_text!("mod baz;\nuse baz::bar;\n", &*text);
+    }
+
+    #[test]
+    fn test_will_move_file_to_other_directory() {
+        let mock = MockAnalysis::with_files(
+            "
+            //- /lib.rs
+            mod a;
+            mod b;
+            //- /a.rs
+            /// The foo module.
+            pub mod foo;
+            //- /a/foo.rs
+            pub fn f() {}
+            //- /b.rs
+            mod bar;
+            //- /b/bar.rs
+            pub fn g() {}
+            ",
+        );
+        let file_id = mock.id_of("/a/foo.rs");
+        let analysis = mock.analysis();
+        let source_change =
+            analysis.will_move_file(file_id, RelativePath::new("b/baz.rs")).unwrap().unwrap();
+        assert!(source_change.file_system_edits.is_empty());
+        assert_eq!(source_change.source_file_edits.len(), 2);
+        assert_eq_text!("", &*apply_edits(&analysis, &source_change, mock.id_of("/a.rs")));
+        assert_eq_text!(
+            "mod bar;\n/// The foo module.\npub mod baz;\n",
+            &*apply_edits(&analysis, &source_change, mock.id_of("/b.rs"))
+        );
+    }
+
+    #[test]
+    fn test_will_move_file_updates_paths_and_submodules() {
+        let mock = MockAnalysis::with_files(
+            "
+            //- /lib.rs
+            mod a;
+            mod b;
+            use a::foo::f;
+            fn main() { a::foo::f(); }
+            //- /a.rs
+            pub mod foo;
+            //- /a/foo.rs
+            pub mod bar;
+            pub fn f() {}
+            //- /a/foo/bar.rs
+            pub fn g() {}
+            //- /b.rs
+            fn h() {}
+            ",
+        );
+        let file_id = mock.id_of("/a/foo.rs");
+        let analysis = mock.analysis();
+        let source_change =
+            analysis.will_move_file(file_id, RelativePath::new("b/foo.rs")).unwrap().unwrap();
+        assert_eq_text!(
+            "mod a;\nmod b;\nuse crate::b::foo::f;\nfn main() { crate::b::foo::f(); }\n",
+            &*apply_edits(&analysis, &source_change, mock.id_of("/lib.rs"))
+        );
+        match source_change.file_system_edits.as_slice() {
+            [FileSystemEdit::MoveFile { src, dst_path, .. }] => {
+                assert_eq!(*src, mock.id_of("/a/foo/bar.rs"));
+                assert_eq!(dst_path.as_str(), "b/foo/bar.rs");
+            }
+            edits => panic!("unexpected file system edits: {:?}", edits),
+        }
+    }
+
+    #[test]
+    fn test_will_move_file_inserts_after_inner_attributes() {
+        let mock = MockAnalysis::with_files(
+            "
+            //- /lib.rs
+            mod a;
+            mod b;
+            //- /a.rs
+            pub mod foo;
+            //- /a/foo.rs
+            pub fn f() {}
+            //- /b.rs
+            //! The b module.
+            #![allow(unused)]
+
+            fn h() {}
+            ",
+        );
+        let file_id = mock.id_of("/a/foo.rs");
+        let analysis = mock.analysis();
+        let source_change =
+            analysis.will_move_file(file_id, RelativePath::new("b/foo.rs")).unwrap().unwrap();
+        assert_eq_text!(
+            "//! The b module.\n#![allow(unused)]\npub mod foo;\n\nfn h() {}\n",
+            &*apply_edits(&analysis, &source_change, mock.id_of("/b.rs"))
+        );
+    }
+
+    #[test]
+    fn test_will_move_file_with_path_attr() {
+        let mock = MockAnalysis::with_files(
+            r#"
+            //- /lib.rs
+            #[path = "a/foo.rs"]
+            mod foo;
+            //- /a/foo.rs
+            pub fn f() {}
+            "#,
+        );
+        let file_id = mock.id_of("/a/foo.rs");
+        let analysis = mock.analysis();
+        let source_change =
+            analysis.will_move_file(file_id, RelativePath::new("b/c/foo.rs")).unwrap().unwrap();
+        assert_eq_text!(
+            "#[path = \"b/c/foo.rs\"]\nmod foo;\n",
+            &*apply_edits(&analysis, &source_change, mock.id_of("/lib.rs"))
+        );
+    }
+
+    fn apply_edits(analysis: &Analysis, source_change: &SourceChange, file_id: FileId) -> String {
+        let mut text = analysis.file_text(file_id).unwrap().to_string();
+        let mut builder = TextEditBuilder::default();
+        for edit in source_change.source_file_edits.iter().filter(|it| it.file_id == file_id) {
+            for indel in edit.edit.as_indels() {
+                builder.replace(indel.delete, indel.insert.clone());
+            }
+        }
+        builder.finish().apply(&mut text);
+        text
+    }
+
+    #[test]
+    fn test_rename_in_macro_call_args() {
+        test_rename(
+            r#"
+    macro_rules! m { ($($t:tt)*) => { $($t)* } }
+    struct Foo<|>;
+    m! {
+        fn f(_: Foo) -> Foo { Foo }
+    }"#,
+            "Bar",
+            r#"
+    macro_rules! m { ($($t:tt)*) => { $($t)* } }
+    struct Bar;
+    m! {
+        fn f(_: Bar) -> Bar { Bar }
+    }"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_updates_intra_doc_links() {
+        test_rename(
+            r#"
+    /// Make one with [`Foo::new`], see also [the type](Foo) and [`struct@Foo`].
+    struct Foo<|>;
+    impl Foo {
+        /// Returns a new [`Foo`].
+        fn new() -> Foo { Foo }
+    }"#,
+            "Bar",
+            r#"
+    /// Make one with [`Bar::new`], see also [the type](Bar) and [`struct@Bar`].
+    struct Bar;
+    impl Bar {
+        /// Returns a new [`Bar`].
+        fn new() -> Bar { Bar }
+    }"#,
+        );
+    }
+
+    #[test]
+    fn test_rename_from_intra_doc_link() {
+        test_rename(
+            r#"
+    /// See [`foo`].
+    fn bar() {}
+    /// Does things.
+    fn foo() {}
+    /// Like [`fo<|>o`], but better.
+    fn foo2() { foo() }"#,
+            "baz",
+            r#"
+    /// See [`baz`].
+    fn bar() {}
+    /// Does things.
+    fn baz() {}
+    /// Like [`baz`], but better.
+    fn foo2() { baz() }"#,
+        );
+    }
+
     fn test_rename(text: &str, new_name: &str, expected: &str) {
         let (analysis, position) = single_file_with_position(text);
         let source_change = analysis.rename(position, new_name).unwrap();
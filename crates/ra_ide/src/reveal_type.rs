@@ -0,0 +1,102 @@
+//! Shows the concrete types hidden behind `impl Trait`s returned from
+//! functions, like `fn f() -> impl Iterator<Item = u32>`.
+
+use hir::{HirDisplay, Semantics};
+use ra_ide_db::RootDatabase;
+use ra_syntax::{ast, AstNode};
+
+use crate::{FilePosition, RangeInfo};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RevealedType {
+    /// The type as seen by the caller, e.g. `impl Iterator<Item = u32>`.
+    pub opaque: String,
+    /// The type with each `impl Trait` replaced by the type it hides.
+    pub hidden: String,
+}
+
+pub(crate) fn reveal_type(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<RevealedType>> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let token = file.syntax().token_at_offset(position.offset).left_biased()?;
+    let token = sema.descend_into_macros(token);
+    let node = token
+        .ancestors()
+        .find(|n| ast::Expr::cast(n.clone()).is_some() || ast::Pat::cast(n.clone()).is_some())?;
+    let ty = match ast::Expr::cast(node.clone()) {
+        Some(expr) => sema.type_of_expr(&expr)?,
+        None => sema.type_of_pat(&ast::Pat::cast(node.clone())?)?,
+    };
+    let hidden = ty.reveal_opaque_types(db)?;
+    let range = sema.original_range(&node).range;
+    let revealed =
+        RevealedType { opaque: ty.display(db).to_string(), hidden: hidden.display(db).to_string() };
+    Some(RangeInfo::new(range, revealed))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::analysis_and_position;
+
+    fn check_reveal_type(ra_fixture: &str, opaque: &str, hidden: &str) {
+        let (analysis, position) = analysis_and_position(ra_fixture);
+        let revealed = analysis.reveal_type(position).unwrap().unwrap().info;
+        assert_eq!(revealed.opaque, opaque);
+        assert_eq!(revealed.hidden, hidden);
+    }
+
+    #[test]
+    fn reveals_impl_trait_of_call() {
+        check_reveal_type(
+            r#"
+            //- /lib.rs
+            trait Tr {}
+            struct S;
+            impl Tr for S {}
+            fn make() -> impl Tr { S }
+            fn main() {
+                let x = make();
+                x<|>;
+            }
+            "#,
+            "impl Tr",
+            "S",
+        );
+    }
+
+    #[test]
+    fn reveals_impl_trait_nested_in_other_types() {
+        check_reveal_type(
+            r#"
+            //- /lib.rs
+            trait Tr {}
+            struct S<T>(T);
+            impl<T> Tr for S<T> {}
+            fn make<T>(t: T) -> Option<impl Tr> { Option::Some(S(t)) }
+            enum Option<T> { Some(T), None }
+            fn main() {
+                let x<|> = make(0u8);
+            }
+            "#,
+            "Option<impl Tr>",
+            "Option<S<u8>>",
+        );
+    }
+
+    #[test]
+    fn nothing_to_reveal() {
+        let (analysis, position) = analysis_and_position(
+            r#"
+            //- /lib.rs
+            fn main() {
+                let x = 92u32;
+                x<|>;
+            }
+            "#,
+        );
+        assert!(analysis.reveal_type(position).unwrap().is_none());
+    }
+}
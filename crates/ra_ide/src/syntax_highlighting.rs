@@ -5,7 +5,7 @@ mod html;
 #[cfg(test)]
 mod tests;
 
-use hir::{Name, Semantics};
+use hir::{AsAssocItem, Local, Name, Semantics};
 use ra_ide_db::{
     defs::{classify_name, classify_name_ref, Definition, NameClass, NameRefClass},
     RootDatabase,
@@ -361,7 +361,10 @@ fn highlight_element(
                                 binding_hash = Some(calc_binding_hash(&name, *shadow_count))
                             }
                         };
-                        highlight_name(db, def)
+                        match def {
+                            Definition::Local(local) => highlight_local_use(sema, &name_ref, local),
+                            def => highlight_name(db, def),
+                        }
                     }
                     NameRefClass::FieldShorthand { .. } => HighlightTag::Field.into(),
                 },
@@ -370,7 +373,18 @@ fn highlight_element(
         }
 
         // Simple token-based highlighting
-        COMMENT => HighlightTag::Comment.into(),
+        COMMENT => {
+            let h = Highlight::new(HighlightTag::Comment);
+            let is_doc = element
+                .into_token()
+                .and_then(ast::Comment::cast)
+                .map_or(false, |it| it.kind().doc.is_some());
+            if is_doc {
+                h | HighlightModifier::Documentation
+            } else {
+                h
+            }
+        }
         STRING | RAW_STRING | RAW_BYTE_STRING | BYTE_STRING => HighlightTag::StringLiteral.into(),
         ATTR => HighlightTag::Attribute.into(),
         INT_NUMBER | FLOAT_NUMBER => HighlightTag::NumericLiteral.into(),
@@ -420,34 +434,117 @@ fn highlight_element(
 }
 
 fn highlight_name(db: &RootDatabase, def: Definition) -> Highlight {
-    match def {
-        Definition::Macro(_) => HighlightTag::Macro,
-        Definition::Field(_) => HighlightTag::Field,
+    let mut h: Highlight = match def {
+        Definition::Macro(_) => HighlightTag::Macro.into(),
+        Definition::Field(_) => HighlightTag::Field.into(),
         Definition::ModuleDef(def) => match def {
-            hir::ModuleDef::Module(_) => HighlightTag::Module,
-            hir::ModuleDef::Function(_) => HighlightTag::Function,
-            hir::ModuleDef::Adt(hir::Adt::Struct(_)) => HighlightTag::Struct,
-            hir::ModuleDef::Adt(hir::Adt::Enum(_)) => HighlightTag::Enum,
-            hir::ModuleDef::Adt(hir::Adt::Union(_)) => HighlightTag::Union,
-            hir::ModuleDef::EnumVariant(_) => HighlightTag::EnumVariant,
-            hir::ModuleDef::Const(_) => HighlightTag::Constant,
-            hir::ModuleDef::Static(_) => HighlightTag::Static,
-            hir::ModuleDef::Trait(_) => HighlightTag::Trait,
-            hir::ModuleDef::TypeAlias(_) => HighlightTag::TypeAlias,
-            hir::ModuleDef::BuiltinType(_) => HighlightTag::BuiltinType,
+            hir::ModuleDef::Module(_) => HighlightTag::Module.into(),
+            hir::ModuleDef::Function(func) => {
+                let mut h = Highlight::new(HighlightTag::Function);
+                if func.as_assoc_item(db).is_some() {
+                    h |= HighlightModifier::Associated;
+                    if !func.has_self_param(db) {
+                        h |= HighlightModifier::Static;
+                    }
+                }
+                h
+            }
+            hir::ModuleDef::Adt(hir::Adt::Struct(_)) => HighlightTag::Struct.into(),
+            hir::ModuleDef::Adt(hir::Adt::Enum(_)) => HighlightTag::Enum.into(),
+            hir::ModuleDef::Adt(hir::Adt::Union(_)) => HighlightTag::Union.into(),
+            hir::ModuleDef::EnumVariant(_) => HighlightTag::EnumVariant.into(),
+            hir::ModuleDef::Const(konst) => {
+                let h = Highlight::new(HighlightTag::Constant);
+                if konst.as_assoc_item(db).is_some() {
+                    h | HighlightModifier::Associated
+                } else {
+                    h
+                }
+            }
+            hir::ModuleDef::Static(_) => HighlightTag::Static.into(),
+            hir::ModuleDef::Trait(_) => HighlightTag::Trait.into(),
+            hir::ModuleDef::TypeAlias(type_alias) => {
+                let h = Highlight::new(HighlightTag::TypeAlias);
+                if type_alias.as_assoc_item(db).is_some() {
+                    h | HighlightModifier::Associated
+                } else {
+                    h
+                }
+            }
+            hir::ModuleDef::BuiltinType(_) => HighlightTag::BuiltinType.into(),
         },
-        Definition::SelfType(_) => HighlightTag::SelfType,
-        Definition::TypeParam(_) => HighlightTag::TypeParam,
+        Definition::SelfType(_) => HighlightTag::SelfType.into(),
+        Definition::TypeParam(_) => HighlightTag::TypeParam.into(),
         // FIXME: distinguish between locals and parameters
         Definition::Local(local) => {
+            let ty = local.ty(db);
             let mut h = Highlight::new(HighlightTag::Local);
-            if local.is_mut(db) || local.ty(db).is_mutable_reference() {
+            if local.is_mut(db) || ty.is_mutable_reference() {
                 h |= HighlightModifier::Mutable;
             }
-            return h;
+            if ty.is_fn() || ty.is_closure() {
+                h |= HighlightModifier::Callable;
+            }
+            h
+        }
+    };
+    if def.is_in_library(db) {
+        h |= HighlightModifier::Library;
+    }
+    h
+}
+
+/// Highlights a use of `local`, which can move or mutably borrow it.
+fn highlight_local_use(
+    sema: &Semantics<RootDatabase>,
+    name_ref: &ast::NameRef,
+    local: Local,
+) -> Highlight {
+    let db = sema.db;
+    let mut h = highlight_name(db, Definition::Local(local));
+
+    let expr = match name_ref
+        .syntax()
+        .ancestors()
+        .take_while(|it| it.text_range() == name_ref.syntax().text_range())
+        .find_map(ast::PathExpr::cast)
+    {
+        Some(it) => it,
+        None => return h,
+    };
+    let parent = match expr.syntax().parent() {
+        Some(it) => it,
+        None => return h,
+    };
+    if let Some(ref_expr) = ast::RefExpr::cast(parent.clone()) {
+        if ref_expr.mut_token().is_some() {
+            h |= HighlightModifier::MutableReference;
+        }
+    } else if let Some(method_call) = ast::MethodCallExpr::cast(parent.clone()) {
+        // The receiver, which is passed as `self`, `&self` or `&mut self`.
+        let self_ty = sema
+            .resolve_method_call(&method_call)
+            .filter(|func| func.has_self_param(db))
+            .and_then(|func| func.param_types(db).into_iter().next());
+        if let Some(self_ty) = self_ty {
+            if self_ty.is_mutable_reference() {
+                h |= HighlightModifier::MutableReference;
+            } else if !self_ty.is_reference() && is_moved(db, local) {
+                h |= HighlightModifier::Consuming;
+            }
         }
+    } else if ast::ArgList::can_cast(parent.kind()) && is_moved(db, local) {
+        h |= HighlightModifier::Consuming;
     }
-    .into()
+    h
+}
+
+/// Checks whether passing `local` by value moves it. Shared references are
+/// `Copy` and mutable ones are reborrowed.
+fn is_moved(db: &RootDatabase, local: Local) -> bool {
+    let ty = local.ty(db);
+    // Without a `Copy` trait, we can't tell moves from copies.
+    !ty.is_unknown() && !ty.is_reference() && ty.is_copy(db) == Some(false)
 }
 
 fn highlight_name_by_syntax(name: ast::Name) -> Highlight {
@@ -52,6 +52,21 @@ pub enum HighlightModifier {
     Definition,
     Mutable,
     Unsafe,
+    /// Used with locals passed by value to a parameter, which moves them
+    /// unless their type is `Copy`.
+    Consuming,
+    /// Used with locals which are borrowed mutably, like `x` in `&mut x`.
+    MutableReference,
+    /// Used with locals of function or closure type.
+    Callable,
+    /// Used with items declared in an `impl` or a `trait`.
+    Associated,
+    /// Used with associated functions which don't take `self`.
+    Static,
+    /// Used with doc comments.
+    Documentation,
+    /// Used with items from other crates.
+    Library,
 }
 
 impl HighlightTag {
@@ -99,6 +114,13 @@ impl HighlightModifier {
         HighlightModifier::Definition,
         HighlightModifier::Mutable,
         HighlightModifier::Unsafe,
+        HighlightModifier::Consuming,
+        HighlightModifier::MutableReference,
+        HighlightModifier::Callable,
+        HighlightModifier::Associated,
+        HighlightModifier::Static,
+        HighlightModifier::Documentation,
+        HighlightModifier::Library,
     ];
 
     fn as_str(self) -> &'static str {
@@ -107,6 +129,13 @@ impl HighlightModifier {
             HighlightModifier::Definition => "declaration",
             HighlightModifier::Mutable => "mutable",
             HighlightModifier::Unsafe => "unsafe",
+            HighlightModifier::Consuming => "consuming",
+            HighlightModifier::MutableReference => "mutable_reference",
+            HighlightModifier::Callable => "callable",
+            HighlightModifier::Associated => "associated",
+            HighlightModifier::Static => "static",
+            HighlightModifier::Documentation => "documentation",
+            HighlightModifier::Library => "library",
         }
     }
 
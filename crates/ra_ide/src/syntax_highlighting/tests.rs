@@ -1,10 +1,14 @@
-use std::fs;
+use std::{fs, sync::Arc};
 
+use ra_cfg::CfgOptions;
+use ra_db::{CrateName, Env, RelativePathBuf};
 use test_utils::{assert_eq_text, project_dir, read_text};
 
 use crate::{
     mock_analysis::{single_file, MockAnalysis},
-    FileRange, TextRange,
+    AnalysisChange, AnalysisHost, CrateGraph,
+    Edition::Edition2018,
+    FileId, FileRange, SourceRootId, TextRange,
 };
 
 #[test]
@@ -132,6 +136,135 @@ fn test_ranges() {
     assert_eq!(&highlights[0].highlight.to_string(), "field.declaration");
 }
 
+#[test]
+fn test_highlight_modifiers() {
+    let (analysis, file_id) = single_file(
+        r#"
+#[lang = "copy"]
+pub trait Copy {}
+
+/// A struct.
+struct Foo;
+
+impl Foo {
+    fn new() -> Foo { Foo }
+    fn consume(self) {}
+    fn mutate(&mut self) {}
+}
+
+fn take(foo: Foo) {}
+
+fn main() {
+    let mut foo = Foo::new();
+    foo.mutate();
+    let f = take;
+    f(foo);
+}
+"#
+        .trim(),
+    );
+
+    let text = analysis.file_text(file_id).unwrap();
+    let highlights: Vec<String> = analysis
+        .highlight(file_id)
+        .unwrap()
+        .into_iter()
+        .map(|it| format!("{} {}", &text[it.range], it.highlight))
+        .collect();
+    for expected in &[
+        "/// A struct. comment.documentation",
+        "new function.declaration.associated.static",
+        "consume function.declaration.associated",
+        "new function.associated.static",
+        "foo variable.mutable.mutable_reference",
+        "f variable.declaration.callable",
+        "foo variable.mutable.consuming",
+    ] {
+        assert!(
+            highlights.iter().any(|it| it == expected),
+            "{:?} not in {:#?}",
+            expected,
+            highlights
+        );
+    }
+}
+
+#[test]
+fn test_no_consuming_modifier_without_copy_trait() {
+    let (analysis, file_id) = single_file(
+        r#"
+fn take(x: i32) {}
+
+fn main() {
+    let x = 1;
+    take(x);
+}
+"#
+        .trim(),
+    );
+
+    let text = analysis.file_text(file_id).unwrap();
+    let highlights: Vec<String> = analysis
+        .highlight(file_id)
+        .unwrap()
+        .into_iter()
+        .filter(|it| &text[it.range] == "x")
+        .map(|it| it.highlight.to_string())
+        .collect();
+    assert!(highlights.iter().all(|it| !it.contains("consuming")), "{:#?}", highlights);
+}
+
+#[test]
+fn test_highlight_library_items() {
+    let files = [
+        (SourceRootId(0), "main.rs", "fn main() { dep::dep_fn(); lib::lib_fn(); }"),
+        (SourceRootId(1), "dep/lib.rs", "pub fn dep_fn() {}"),
+        (SourceRootId(2), "lib/lib.rs", "pub fn lib_fn() {}"),
+    ];
+    let mut change = AnalysisChange::new();
+    change.add_root(SourceRootId(0), true);
+    change.add_root(SourceRootId(1), true);
+    change.add_root(SourceRootId(2), false);
+    let mut crate_graph = CrateGraph::default();
+    let mut crates = Vec::new();
+    for (i, &(root, path, text)) in files.iter().enumerate() {
+        let file_id = FileId(i as u32 + 1);
+        change.add_file(root, file_id, RelativePathBuf::from(path), Arc::new(text.to_string()));
+        crates.push(crate_graph.add_crate_root(
+            file_id,
+            Edition2018,
+            None,
+            CfgOptions::default(),
+            Env::default(),
+            Default::default(),
+            Default::default(),
+        ));
+    }
+    crate_graph.add_dep(crates[0], CrateName::new("dep").unwrap(), crates[1]).unwrap();
+    crate_graph.add_dep(crates[0], CrateName::new("lib").unwrap(), crates[2]).unwrap();
+    change.set_crate_graph(crate_graph);
+    let mut host = AnalysisHost::default();
+    host.apply_change(change);
+
+    let analysis = host.analysis();
+    let file_id = FileId(1);
+    let text = analysis.file_text(file_id).unwrap();
+    let highlights: Vec<String> = analysis
+        .highlight(file_id)
+        .unwrap()
+        .into_iter()
+        .map(|it| format!("{} {}", &text[it.range], it.highlight))
+        .collect();
+    for expected in &["dep_fn function", "lib_fn function.library"] {
+        assert!(
+            highlights.iter().any(|it| it == expected),
+            "{:?} not in {:#?}",
+            expected,
+            highlights
+        );
+    }
+}
+
 #[test]
 fn test_flattening() {
     let (analysis, file_id) = single_file(
@@ -0,0 +1,283 @@
+//! Shows what the trait solver does for the obligation under the cursor, to
+//! help figure out why a trait method or operator impl is (not) found. This
+//! covers method calls, calls, operators, indexing, `?` and `.await`.
+
+use hir::{AsAssocItem, AssocItem, AssocItemContainer, Semantics, SolverTrace, Trait, Type};
+use ra_ide_db::RootDatabase;
+use ra_syntax::{
+    ast::{self, BinOp, PrefixOp},
+    match_ast, AstNode, SyntaxNode,
+};
+use stdx::format_to;
+
+use crate::FilePosition;
+
+pub(crate) fn trace_trait_solve(db: &RootDatabase, position: FilePosition) -> Option<String> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id);
+    let token = file.syntax().token_at_offset(position.offset).left_biased()?;
+    let token = sema.descend_into_macros(token);
+    // Trace the innermost expression under the cursor that involves the trait
+    // solver.
+    let traces = token.ancestors().find_map(|node| {
+        match_ast! {
+            match node {
+                ast::MethodCallExpr(it) => trace_method_call(&sema, &it),
+                ast::CallExpr(it) => trace_call(&sema, &it),
+                ast::BinExpr(it) => {
+                    let lang = bin_op_lang_item(it.op_kind()?)?;
+                    let rhs = sema.type_of_expr(&it.rhs()?)?;
+                    trace_lang_trait(&sema, it.syntax(), &it.lhs()?, lang, &[rhs])
+                },
+                ast::PrefixExpr(it) => {
+                    let lang = match it.op_kind()? {
+                        PrefixOp::Deref => "deref",
+                        PrefixOp::Not => "not",
+                        PrefixOp::Neg => "neg",
+                    };
+                    trace_lang_trait(&sema, it.syntax(), &it.expr()?, lang, &[])
+                },
+                ast::IndexExpr(it) => {
+                    let index = sema.type_of_expr(&it.index()?)?;
+                    trace_lang_trait(&sema, it.syntax(), &it.base()?, "index", &[index])
+                },
+                ast::TryExpr(it) => trace_lang_trait(&sema, it.syntax(), &it.expr()?, "try", &[]),
+                ast::AwaitExpr(it) => {
+                    trace_lang_trait(&sema, it.syntax(), &it.expr()?, "future_trait", &[])
+                },
+                _ => None,
+            }
+        }
+    })?;
+
+    let mut buf = String::new();
+    for trace in traces {
+        format_to!(buf, "{}\n", trace);
+    }
+    Some(buf)
+}
+
+fn trace_method_call(
+    sema: &Semantics<RootDatabase>,
+    call: &ast::MethodCallExpr,
+) -> Option<Vec<SolverTrace>> {
+    let db = sema.db;
+    let receiver = sema.type_of_expr(&call.expr()?)?;
+
+    let traits = match sema.resolve_method_call(call) {
+        Some(func) => match func.as_assoc_item(db)?.container(db) {
+            AssocItemContainer::Trait(it) => vec![it],
+            // inherent methods don't involve the trait solver
+            AssocItemContainer::ImplDef(_) => return None,
+        },
+        // If the method isn't found, trace all traits in scope that have a
+        // method of that name.
+        None => {
+            let name = call.name_ref()?.text().clone();
+            let mut traits: Vec<Trait> = sema
+                .scope(call.syntax())
+                .traits_in_scope()
+                .into_iter()
+                .map(Trait::from)
+                .filter(|trait_| {
+                    trait_.items(db).into_iter().any(|item| match item {
+                        AssocItem::Function(f) => f.name(db).to_string() == name.as_str(),
+                        _ => false,
+                    })
+                })
+                .collect();
+            traits.sort_by_key(|trait_| trait_.name(db).to_string());
+            traits
+        }
+    };
+    if traits.is_empty() {
+        return None;
+    }
+
+    let mut traces = Vec::new();
+    for trait_ in traits {
+        // Method resolution tries the receiver type and everything it derefs
+        // to, so we do the same.
+        for ty in receiver.autoderef(db) {
+            let trace = ty.trace_impls_trait(db, trait_, &[]);
+            let solved = trace.root.solution.is_some();
+            traces.push(trace);
+            if solved {
+                break;
+            }
+        }
+    }
+    Some(traces)
+}
+
+fn trace_call(sema: &Semantics<RootDatabase>, call: &ast::CallExpr) -> Option<Vec<SolverTrace>> {
+    let callee = call.expr()?;
+    let callee_ty = sema.type_of_expr(&callee)?;
+    // Calling a function item requires its where clauses to hold; anything
+    // else is called through the `Fn*` traits.
+    match callee_ty.trace_callable_where_clauses(sema.db) {
+        Some(traces) if traces.is_empty() => None,
+        Some(traces) => Some(traces),
+        None => trace_lang_trait(sema, call.syntax(), &callee, "fn_once", &[]),
+    }
+}
+
+fn trace_lang_trait(
+    sema: &Semantics<RootDatabase>,
+    node: &SyntaxNode,
+    self_expr: &ast::Expr,
+    lang: &str,
+    args: &[Type],
+) -> Option<Vec<SolverTrace>> {
+    let krate = sema.scope(node).module()?.krate();
+    let trait_ = Trait::lang(sema.db, krate, lang)?;
+    let self_ty = sema.type_of_expr(self_expr)?;
+    Some(vec![self_ty.trace_impls_trait(sema.db, trait_, args)])
+}
+
+fn bin_op_lang_item(op: BinOp) -> Option<&'static str> {
+    let lang = match op {
+        BinOp::BooleanOr | BinOp::BooleanAnd | BinOp::Assignment => return None,
+        BinOp::EqualityTest | BinOp::NegatedEqualityTest => "eq",
+        BinOp::LesserEqualTest
+        | BinOp::GreaterEqualTest
+        | BinOp::LesserTest
+        | BinOp::GreaterTest => "partial_ord",
+        BinOp::Addition => "add",
+        BinOp::Multiplication => "mul",
+        BinOp::Subtraction => "sub",
+        BinOp::Division => "div",
+        BinOp::Remainder => "rem",
+        BinOp::LeftShift => "shl",
+        BinOp::RightShift => "shr",
+        BinOp::BitwiseXor => "bitxor",
+        BinOp::BitwiseOr => "bitor",
+        BinOp::BitwiseAnd => "bitand",
+        BinOp::AddAssign => "add_assign",
+        BinOp::DivAssign => "div_assign",
+        BinOp::MulAssign => "mul_assign",
+        BinOp::RemAssign => "rem_assign",
+        BinOp::ShrAssign => "shr_assign",
+        BinOp::ShlAssign => "shl_assign",
+        BinOp::SubAssign => "sub_assign",
+        BinOp::BitOrAssign => "bitor_assign",
+        BinOp::BitAndAssign => "bitand_assign",
+        BinOp::BitXorAssign => "bitxor_assign",
+    };
+    Some(lang)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::analysis_and_position;
+
+    fn trace(ra_fixture: &str) -> String {
+        let (analysis, position) = analysis_and_position(ra_fixture);
+        analysis.trace_trait_solve(position).unwrap().unwrap()
+    }
+
+    #[test]
+    fn traces_resolved_trait_method() {
+        let trace = trace(
+            r#"
+            //- /lib.rs
+            trait Foo { fn foo(&self); }
+            struct S;
+            impl Foo for S { fn foo(&self) {} }
+            fn main() {
+                S.fo<|>o();
+            }
+            "#,
+        );
+        assert!(trace.starts_with("Implements(S: Foo)\n  solution: unique []\n"), "{}", trace);
+        assert!(trace.contains("\n  impl S: Foo\n"), "{}", trace);
+    }
+
+    #[test]
+    fn traces_unresolved_method() {
+        let trace = trace(
+            r#"
+            //- /lib.rs
+            trait Foo { fn foo(&self); }
+            trait Bar { fn bar(&self); }
+            struct S<T>(T);
+            impl<T: Bar> Foo for S<T> { fn foo(&self) {} }
+            fn main() {
+                S(1u32).fo<|>o();
+            }
+            "#,
+        );
+        assert!(trace.starts_with("Implements(S<u32>: Foo)\n  solution: none\n"), "{}", trace);
+        assert!(
+            trace.contains("\n  impl S<T>: Foo\n    Implements(u32: Bar)\n      solution: none\n"),
+            "{}",
+            trace
+        );
+        assert!(trace.contains("\n      no impls\n"), "{}", trace);
+    }
+
+    #[test]
+    fn traces_where_clauses_of_call() {
+        let trace = trace(
+            r#"
+            //- /lib.rs
+            trait Foo {}
+            struct S;
+            fn foo<T: Foo>(t: T) {}
+            fn main() {
+                fo<|>o(S);
+            }
+            "#,
+        );
+        assert!(trace.starts_with("Implements(S: Foo)\n  solution: none\n"), "{}", trace);
+    }
+
+    #[test]
+    fn traces_operator() {
+        let trace = trace(
+            r#"
+            //- /lib.rs
+            #[lang = "add"]
+            trait Add<Rhs> { type Output; }
+            struct S;
+            impl Add<u32> for S { type Output = S; }
+            fn main() {
+                S +<|> 1u32;
+            }
+            "#,
+        );
+        assert!(trace.starts_with("Implements(S: Add<u32>)\n  solution: unique []\n"), "{}", trace);
+        assert!(trace.contains("\n  impl S: Add<u32>\n"), "{}", trace);
+    }
+
+    #[test]
+    fn traces_try() {
+        let trace = trace(
+            r#"
+            //- /lib.rs
+            #[lang = "try"]
+            trait Try {}
+            struct S;
+            fn main() {
+                S<|>?;
+            }
+            "#,
+        );
+        assert!(trace.starts_with("Implements(S: Try)\n  solution: none\n"), "{}", trace);
+    }
+
+    #[test]
+    fn no_trace_for_inherent_method() {
+        let (analysis, position) = analysis_and_position(
+            r#"
+            //- /lib.rs
+            struct S;
+            impl S { fn foo(&self) {} }
+            fn main() {
+                S.fo<|>o();
+            }
+            "#,
+        );
+        assert_eq!(analysis.trace_trait_solve(position).unwrap(), None);
+    }
+}
@@ -0,0 +1,220 @@
+//! Entry point for type-hierarchy: the traits above and below a trait, the
+//! types implementing it, and the traits implemented by a type.
+
+use hir::{Adt, ImplDef, ModuleDef, Semantics, Trait};
+use ra_db::FileId;
+use ra_ide_db::{
+    defs::{classify_name, classify_name_ref, Definition},
+    RootDatabase,
+};
+use ra_syntax::{ast, match_ast, AstNode, SyntaxKind::IDENT};
+use rustc_hash::FxHashSet;
+
+use crate::{display::ToNav, FilePosition, NavigationTarget, RangeInfo};
+
+#[derive(Debug, Clone)]
+pub struct TypeHierarchy {
+    pub item: NavigationTarget,
+    /// For a trait, the traits it directly extends.
+    pub supertraits: Vec<NavigationTarget>,
+    /// For a trait, the traits directly extending it.
+    pub subtraits: Vec<NavigationTarget>,
+    /// For a trait, the types implementing it (or the impls, if the type is
+    /// not an ADT).
+    pub implementors: Vec<NavigationTarget>,
+    /// For a type, the traits it implements, grouped by the crate containing
+    /// the impl.
+    pub implemented_traits: Vec<CrateTraits>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CrateTraits {
+    pub crate_root: FileId,
+    pub crate_name: Option<String>,
+    pub traits: Vec<NavigationTarget>,
+}
+
+pub(crate) fn type_hierarchy(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<TypeHierarchy>> {
+    let sema = Semantics::new(db);
+    let file = sema.parse(position.file_id).syntax().clone();
+    let token = file.token_at_offset(position.offset).find(|it| it.kind() == IDENT)?;
+    let range = token.text_range();
+    let token = sema.descend_into_macros(token);
+
+    let def = match_ast! {
+        match (token.parent()) {
+            ast::NameRef(name_ref) => classify_name_ref(&sema, &name_ref)?.definition(),
+            ast::Name(name) => classify_name(&sema, &name)?.definition(),
+            _ => return None,
+        }
+    };
+    let res = match def {
+        Definition::ModuleDef(ModuleDef::Trait(it)) => trait_hierarchy(db, it),
+        Definition::ModuleDef(ModuleDef::Adt(it)) => adt_hierarchy(db, it),
+        _ => return None,
+    };
+    Some(RangeInfo::new(range, res))
+}
+
+fn trait_hierarchy(db: &RootDatabase, trait_: Trait) -> TypeHierarchy {
+    // Only crates that can see the trait can extend or implement it.
+    let crates = trait_.module(db).krate().transitive_reverse_dependencies(db);
+
+    let supertraits = trait_.direct_super_traits(db).into_iter().map(|it| it.to_nav(db)).collect();
+
+    let subtraits = crates
+        .iter()
+        .flat_map(|&krate| Trait::all_in_crate(db, krate))
+        .filter(|it| it.direct_super_traits(db).contains(&trait_))
+        .map(|it| it.to_nav(db))
+        .collect();
+
+    // List each type once, even if it implements the trait for several
+    // instantiations like `S<u32>` and `S<i32>`.
+    let mut seen_adts = FxHashSet::default();
+    let implementors = crates
+        .iter()
+        .flat_map(|&krate| ImplDef::for_trait(db, krate, trait_))
+        .filter_map(|impl_def| match impl_def.target_ty(db).as_adt() {
+            Some(adt) if seen_adts.insert(adt) => Some(adt.to_nav(db)),
+            Some(_) => None,
+            None => Some(impl_def.to_nav(db)),
+        })
+        .collect();
+
+    TypeHierarchy {
+        item: trait_.to_nav(db),
+        supertraits,
+        subtraits,
+        implementors,
+        implemented_traits: Vec::new(),
+    }
+}
+
+fn adt_hierarchy(db: &RootDatabase, adt: Adt) -> TypeHierarchy {
+    let ty = adt.ty(db);
+    // An impl of a trait for the type lives either next to the type or next to
+    // the trait, and in both cases in a crate that can see the type.
+    let crates = adt.module(db).krate().transitive_reverse_dependencies(db);
+
+    let implemented_traits = crates
+        .into_iter()
+        .filter_map(|krate| {
+            let traits = ImplDef::all_in_crate(db, krate)
+                .into_iter()
+                .filter(|impl_def| ty.is_equal_for_find_impls(&impl_def.target_ty(db)))
+                .filter_map(|impl_def| impl_def.target_trait_def(db))
+                .map(|it| it.to_nav(db))
+                .collect::<Vec<_>>();
+            if traits.is_empty() {
+                return None;
+            }
+            Some(CrateTraits {
+                crate_root: krate.root_file(db),
+                crate_name: krate.display_name(db).map(|it| it.to_string()),
+                traits,
+            })
+        })
+        .collect();
+
+    TypeHierarchy {
+        item: adt.to_nav(db),
+        supertraits: Vec::new(),
+        subtraits: Vec::new(),
+        implementors: Vec::new(),
+        implemented_traits,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock_analysis::analysis_and_position, NavigationTarget};
+
+    fn check(navs: &[NavigationTarget], expected: &[&str]) {
+        let mut navs = navs.to_vec();
+        assert_eq!(navs.len(), expected.len());
+        navs.sort_by_key(|nav| (nav.file_id(), nav.full_range().start()));
+        navs.into_iter().enumerate().for_each(|(i, nav)| nav.assert_match(expected[i]));
+    }
+
+    #[test]
+    fn type_hierarchy_of_trait() {
+        let (analysis, pos) = analysis_and_position(
+            "
+            //- /lib.rs
+            trait A {}
+            trait B<|>: A {}
+            trait C: B {}
+            struct S;
+            impl A for S {}
+            impl B for S {}
+            ",
+        );
+        let res = analysis.type_hierarchy(pos).unwrap().unwrap().info;
+        res.item.assert_match("B TRAIT_DEF FileId(1) 11..24 17..18");
+        check(&res.supertraits, &["A TRAIT_DEF FileId(1) 0..10 6..7"]);
+        check(&res.subtraits, &["C TRAIT_DEF FileId(1) 25..38 31..32"]);
+        check(&res.implementors, &["S STRUCT_DEF FileId(1) 39..48 46..47"]);
+        assert!(res.implemented_traits.is_empty());
+    }
+
+    #[test]
+    fn type_hierarchy_of_trait_across_crates() {
+        let (analysis, pos) = analysis_and_position(
+            "
+            //- /main.rs
+            trait Sub: lib::Base {}
+            struct S;
+            impl lib::Base for S {}
+            //- /lib/lib.rs
+            pub trait Ba<|>se {}
+            ",
+        );
+        let res = analysis.type_hierarchy(pos).unwrap().unwrap().info;
+        check(&res.subtraits, &["Sub TRAIT_DEF FileId(1) 0..23 6..9"]);
+        check(&res.implementors, &["S STRUCT_DEF FileId(1) 24..33 31..32"]);
+    }
+
+    #[test]
+    fn type_hierarchy_lists_each_implementor_once() {
+        let (analysis, pos) = analysis_and_position(
+            "
+            //- /lib.rs
+            trait T<|>r {}
+            struct S<T>(T);
+            impl Tr for S<u32> {}
+            impl Tr for S<i32> {}
+            ",
+        );
+        let res = analysis.type_hierarchy(pos).unwrap().unwrap().info;
+        check(&res.implementors, &["S STRUCT_DEF FileId(1) 12..27 19..20"]);
+    }
+
+    #[test]
+    fn type_hierarchy_of_type() {
+        let (analysis, pos) = analysis_and_position(
+            "
+            //- /main.rs
+            trait Local {}
+            impl Local for lib::S {}
+            //- /lib/lib.rs
+            pub struct S<|>;
+            pub trait A {}
+            impl A for S {}
+            impl S {}
+            ",
+        );
+        let res = analysis.type_hierarchy(pos).unwrap().unwrap().info;
+        res.item.assert_match("S STRUCT_DEF FileId(2) 0..13 11..12");
+        let mut groups = res.implemented_traits;
+        groups.sort_by_key(|it| it.crate_root);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].crate_name, None);
+        check(&groups[0].traits, &["Local TRAIT_DEF FileId(1) 0..14 6..11"]);
+        assert_eq!(groups[1].crate_name.as_deref(), Some("lib"));
+        check(&groups[1].traits, &["A TRAIT_DEF FileId(2) 14..28 24..25"]);
+    }
+}
@@ -357,6 +357,9 @@ impl RootDatabase {
             hir::db::GenericPredicatesForParamQuery
             hir::db::GenericPredicatesQuery
             hir::db::GenericDefaultsQuery
+            hir::db::LayoutOfQuery
+            hir::db::CoherenceErrorsQuery
+            hir::db::ObjectSafetyQuery
             hir::db::ImplsInCrateQuery
             hir::db::ImplsForTraitQuery
             hir::db::InternTypeCtorQuery
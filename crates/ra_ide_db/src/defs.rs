@@ -9,6 +9,7 @@ use hir::{
     Field, HasVisibility, ImplDef, Local, MacroDef, Module, ModuleDef, Name, PathResolution,
     Semantics, TypeParam, Visibility,
 };
+use ra_db::SourceDatabaseExt;
 use ra_prof::profile;
 use ra_syntax::{
     ast::{self, AstNode},
@@ -41,6 +42,17 @@ impl Definition {
         }
     }
 
+    /// Whether the definition comes from a library crate rather than from the
+    /// workspace. Builtin types are in no crate and so not in a library.
+    pub fn is_in_library(&self, db: &RootDatabase) -> bool {
+        let krate = match self.module(db) {
+            Some(it) => it.krate(),
+            None => return false,
+        };
+        let source_root = db.file_source_root(krate.root_file(db));
+        db.source_root(source_root).is_library
+    }
+
     pub fn visibility(&self, db: &RootDatabase) -> Option<Visibility> {
         let module = self.module(db);
 
@@ -257,20 +269,25 @@ pub fn classify_name_ref(
 
     let path = name_ref.syntax().ancestors().find_map(ast::Path::cast)?;
     let resolved = sema.resolve_path(&path)?;
-    let res = match resolved {
-        PathResolution::Def(def) => Definition::ModuleDef(def),
-        PathResolution::AssocItem(item) => {
-            let def = match item {
-                hir::AssocItem::Function(it) => it.into(),
-                hir::AssocItem::Const(it) => it.into(),
-                hir::AssocItem::TypeAlias(it) => it.into(),
-            };
-            Definition::ModuleDef(def)
+    Some(NameRefClass::Definition(resolved.into()))
+}
+
+impl From<PathResolution> for Definition {
+    fn from(resolution: PathResolution) -> Self {
+        match resolution {
+            PathResolution::Def(def) => Definition::ModuleDef(def),
+            PathResolution::AssocItem(item) => {
+                let def = match item {
+                    hir::AssocItem::Function(it) => it.into(),
+                    hir::AssocItem::Const(it) => it.into(),
+                    hir::AssocItem::TypeAlias(it) => it.into(),
+                };
+                Definition::ModuleDef(def)
+            }
+            PathResolution::Local(local) => Definition::Local(local),
+            PathResolution::TypeParam(par) => Definition::TypeParam(par),
+            PathResolution::Macro(def) => Definition::Macro(def),
+            PathResolution::SelfType(impl_def) => Definition::SelfType(impl_def),
         }
-        PathResolution::Local(local) => Definition::Local(local),
-        PathResolution::TypeParam(par) => Definition::TypeParam(par),
-        PathResolution::Macro(def) => Definition::Macro(def),
-        PathResolution::SelfType(impl_def) => Definition::SelfType(impl_def),
-    };
-    Some(NameRefClass::Definition(res))
+    }
 }
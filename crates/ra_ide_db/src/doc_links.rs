@@ -0,0 +1,389 @@
+//! Intra-doc links: ``[`Foo`]``, `[Foo]` or `[text](crate::foo::Foo)` in doc
+//! comments, which rustdoc resolves like paths in the scope of the documented
+//! item.
+
+use std::ops::Range;
+
+use hir::{ModuleDef, Path, Semantics};
+use ra_syntax::{
+    ast::{self, CommentPlacement},
+    AstNode, Direction, SourceFile, SyntaxKind, SyntaxNode, SyntaxToken, TextRange, TextSize,
+};
+
+use crate::{
+    defs::{classify_name, Definition},
+    RootDatabase,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocLink {
+    /// The range of the whole link, from the opening `[` to the closing `]`
+    /// or `)`.
+    pub full_range: TextRange,
+    /// The text between the brackets.
+    pub label: String,
+    /// The range of the link target, without backticks, disambiguators like
+    /// `struct@` or suffixes like `()` and `!`.
+    pub range: TextRange,
+    /// The link target, like `crate::foo::Foo`.
+    pub path: String,
+}
+
+impl DocLink {
+    /// The range of the last segment of the target, which names the item.
+    pub fn name_range(&self) -> TextRange {
+        let start = self.path.rfind("::").map_or(0, |it| it + 2);
+        TextRange::new(self.range.start() + TextSize::of(&self.path[..start]), self.range.end())
+    }
+
+    /// The link cut after the segment containing `offset`: for `Foo::new` and
+    /// an offset in `Foo`, a link to `Foo`.
+    pub fn prefix_at(&self, offset: TextSize) -> Option<DocLink> {
+        if !self.range.contains_inclusive(offset) {
+            return None;
+        }
+        let offset = usize::from(offset - self.range.start());
+        let end = self.path[offset..].find("::").map_or(self.path.len(), |it| offset + it);
+        let path = &self.path[..end];
+        let range = TextRange::at(self.range.start(), TextSize::of(path));
+        Some(DocLink { range, path: path.to_string(), ..self.clone() })
+    }
+}
+
+/// Finds the intra-doc links in the docs `comment` belongs to, see
+/// [`doc_block`]. Links to URLs and reference-style links are skipped.
+pub fn doc_links(comment: &ast::Comment) -> Vec<DocLink> {
+    block_doc_links(&doc_block(comment))
+}
+
+/// Finds the intra-doc links in a block of doc comments, with ranges in the
+/// source file.
+pub fn block_doc_links(block: &[ast::Comment]) -> Vec<DocLink> {
+    // Join the comments into one markdown text, remembering where each
+    // comment's text starts in the markdown and in the file.
+    let mut markdown = String::new();
+    let mut pieces: Vec<(TextSize, TextSize, TextSize)> = Vec::new();
+    for comment in block {
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        let text = comment_text(comment);
+        let offset = TextSize::of(&comment.text()[..comment.prefix().len()]);
+        pieces.push((
+            TextSize::of(&markdown),
+            comment.syntax().text_range().start() + offset,
+            TextSize::of(text),
+        ));
+        markdown.push_str(text);
+    }
+    let to_source = |offset: TextSize| {
+        pieces
+            .iter()
+            .find(|(start, _, len)| *start <= offset && offset <= *start + *len)
+            .map(|(start, source_start, _)| *source_start + (offset - *start))
+    };
+    let to_source_range =
+        |range: TextRange| Some(TextRange::new(to_source(range.start())?, to_source(range.end())?));
+    markdown_links(&markdown)
+        .into_iter()
+        .filter_map(|link| {
+            let full_range = to_source_range(link.full_range)?;
+            let range = to_source_range(link.range)?;
+            Some(DocLink { full_range, range, ..link })
+        })
+        .collect()
+}
+
+/// The doc comments in `node`, grouped like by [`doc_block`].
+pub fn doc_blocks(node: &SyntaxNode) -> Vec<Vec<ast::Comment>> {
+    let mut res: Vec<Vec<ast::Comment>> = Vec::new();
+    let comments = node
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token().and_then(ast::Comment::cast))
+        .filter(|it| it.kind().doc.is_some());
+    for comment in comments {
+        match res.last_mut() {
+            Some(block) if is_continuation(block.last().unwrap(), &comment) => block.push(comment),
+            _ => res.push(vec![comment]),
+        }
+    }
+    res
+}
+
+/// The doc comments rustdoc joins into one markdown text with `comment`: the
+/// line comments of the same kind on the lines directly before and after it.
+fn doc_block(comment: &ast::Comment) -> Vec<ast::Comment> {
+    if comment.kind().doc.is_none() {
+        return Vec::new();
+    }
+    let mut res = vec![comment.clone()];
+    while let Some(prev) = sibling_comment(&res[0], Direction::Prev) {
+        if !is_continuation(&prev, &res[0]) {
+            break;
+        }
+        res.insert(0, prev);
+    }
+    while let Some(next) = sibling_comment(res.last().unwrap(), Direction::Next) {
+        if !is_continuation(res.last().unwrap(), &next) {
+            break;
+        }
+        res.push(next);
+    }
+    res
+}
+
+/// Whether `next` continues the doc comment `prev` on the following line.
+fn is_continuation(prev: &ast::Comment, next: &ast::Comment) -> bool {
+    if prev.kind() != next.kind() || !prev.kind().shape.is_line() {
+        return false;
+    }
+    match next.syntax().prev_token() {
+        Some(ws) if ws.kind() == SyntaxKind::WHITESPACE => {
+            ws.text().matches('\n').count() == 1 && ws.prev_token().as_ref() == Some(prev.syntax())
+        }
+        _ => false,
+    }
+}
+
+/// The comment before or after `comment`, with only whitespace in between.
+fn sibling_comment(comment: &ast::Comment, direction: Direction) -> Option<ast::Comment> {
+    let step = |token: &SyntaxToken| match direction {
+        Direction::Prev => token.prev_token(),
+        Direction::Next => token.next_token(),
+    };
+    let mut token = step(comment.syntax())?;
+    if token.kind() == SyntaxKind::WHITESPACE {
+        token = step(&token)?;
+    }
+    ast::Comment::cast(token)
+}
+
+/// The text of a doc comment without its `///` or `/**` and `*/`.
+fn comment_text(comment: &ast::Comment) -> &str {
+    let text = &comment.text()[comment.prefix().len()..];
+    if comment.kind().shape.is_block() && text.ends_with("*/") {
+        &text[..text.len() - 2]
+    } else {
+        text
+    }
+}
+
+/// Finds the intra-doc links in documentation text, with ranges relative to
+/// the text. Brackets in code blocks and inline code are not links.
+pub fn markdown_links(text: &str) -> Vec<DocLink> {
+    let code = code_ranges(text);
+    let mut res = Vec::new();
+    let mut pos = 0;
+    while let Some(open) = text[pos..].find('[') {
+        let open = pos + open;
+        let close = match text[open..].find(']') {
+            Some(it) => open + it,
+            None => break,
+        };
+        pos = close + 1;
+
+        let after = &text[close + 1..];
+        let (start, end) = if after.starts_with('(') {
+            // `[text](target)`
+            match after.find(')') {
+                Some(it) => {
+                    pos = close + 1 + it + 1;
+                    (close + 2, close + 1 + it)
+                }
+                None => continue,
+            }
+        } else if after.starts_with('[') {
+            // `[text][ref]`
+            if let Some(it) = after.find(']') {
+                pos = close + 1 + it + 1;
+            }
+            continue;
+        } else if after.starts_with(':') {
+            // `[ref]: target`
+            continue;
+        } else {
+            // `[target]`
+            (open + 1, close)
+        };
+        if code.iter().any(|it| it.contains(&open)) {
+            continue;
+        }
+
+        if let Some((offset, path)) = link_target(&text[start..end]) {
+            res.push(DocLink {
+                full_range: TextRange::new(TextSize::of(&text[..open]), TextSize::of(&text[..pos])),
+                label: text[open + 1..close].to_string(),
+                range: TextRange::at(TextSize::of(&text[..start + offset]), TextSize::of(path)),
+                path: path.to_string(),
+            });
+        }
+    }
+    res
+}
+
+/// Resolves the target of an intra-doc link in `comment` like rustdoc would
+/// in the docs of the documented item, see [`documented_def`].
+pub fn resolve_doc_link(
+    sema: &Semantics<RootDatabase>,
+    comment: &ast::Comment,
+    link: &DocLink,
+) -> Option<Definition> {
+    let def = documented_def(sema, comment)?;
+    resolve_doc_path(sema.db, &def, &link.path)
+}
+
+/// The definition `comment` documents: the item it's attached to, or for
+/// `//!` comments, the module they're in. Docs of items without a definition,
+/// like impls, count as docs of the containing module.
+pub fn documented_def(
+    sema: &Semantics<RootDatabase>,
+    comment: &ast::Comment,
+) -> Option<Definition> {
+    let owner = comment.syntax().parent();
+    if comment.kind().doc == Some(CommentPlacement::Outer) {
+        if let Some(name) = owner.children().find_map(ast::Name::cast) {
+            return Some(classify_name(sema, &name)?.definition());
+        }
+    }
+    let inline_module = ast::ItemList::cast(owner.clone())
+        .and_then(|it| it.syntax().parent())
+        .and_then(ast::Module::cast);
+    let module = match inline_module {
+        Some(it) => sema.to_def(&it)?,
+        None => sema.scope(&owner).module()?,
+    };
+    Some(Definition::ModuleDef(module.into()))
+}
+
+/// Resolves an intra-doc link target in the docs of `def`. Links are resolved
+/// in the module containing `def`, or in `def` itself for a module.
+pub fn resolve_doc_path(db: &RootDatabase, def: &Definition, path: &str) -> Option<Definition> {
+    let module = match def {
+        Definition::ModuleDef(ModuleDef::Module(it)) => *it,
+        _ => def.module(db)?,
+    };
+    let resolved = module.resolve_path(db, &parse_path(path)?)?;
+    Some(resolved.into())
+}
+
+/// Strips backticks, disambiguators and suffixes from a link target, returning
+/// the offset and text of the remaining path, if it looks like one.
+fn link_target(target: &str) -> Option<(usize, &str)> {
+    let mut start = 0;
+    let mut end = target.len();
+    while target[start..end].starts_with('`') {
+        start += 1;
+    }
+    while end > start && target[start..end].ends_with('`') {
+        end -= 1;
+    }
+    if let Some(at) = target[start..end].find('@') {
+        start += at + 1;
+    }
+    if target[start..end].ends_with("()") {
+        end -= 2;
+    } else if target[start..end].ends_with('!') {
+        end -= 1;
+    }
+
+    let path = &target[start..end];
+    let first = path.chars().next()?;
+    let is_path = (first.is_alphabetic() || first == '_')
+        && !path.ends_with(':')
+        && path.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ':');
+    if !is_path {
+        return None;
+    }
+    Some((start, path))
+}
+
+fn parse_path(text: &str) -> Option<Path> {
+    let parse = SourceFile::parse(&format!("use {};", text));
+    if !parse.errors().is_empty() {
+        return None;
+    }
+    let path = parse.tree().syntax().descendants().find_map(ast::Path::cast)?;
+    Path::from_ast(path)
+}
+
+/// The ranges of code blocks and inline code in `markdown`.
+fn code_ranges(markdown: &str) -> Vec<Range<usize>> {
+    let mut res = Vec::new();
+    let mut fence_start = None;
+    let mut line_start = 0;
+    for line in markdown.split('\n') {
+        let line_end = line_start + line.len();
+        if line.trim_start().starts_with("```") {
+            match fence_start.take() {
+                Some(start) => res.push(start..line_end),
+                None => fence_start = Some(line_start),
+            }
+        } else if fence_start.is_none() {
+            let mut pos = 0;
+            while let Some(open) = line[pos..].find('`') {
+                let open = pos + open;
+                let ticks = line[open..].len() - line[open..].trim_start_matches('`').len();
+                let delim = &line[open..open + ticks];
+                match line[open + ticks..].find(delim) {
+                    Some(close) => {
+                        pos = open + ticks + close + ticks;
+                        res.push(line_start + open..line_start + pos);
+                    }
+                    None => pos = open + ticks,
+                }
+            }
+        }
+        line_start = line_end + 1;
+    }
+    if let Some(start) = fence_start {
+        res.push(start..markdown.len());
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::{ast, AstNode, SourceFile};
+
+    use super::doc_links;
+
+    fn check(comment: &str, expected: &[(&str, &str)]) {
+        let text = format!("{}\nfn f() {{}}", comment);
+        let file = SourceFile::parse(&text).tree();
+        let comment = file
+            .syntax()
+            .descendants_with_tokens()
+            .find_map(|it| it.into_token().and_then(ast::Comment::cast))
+            .unwrap();
+        let links = doc_links(&comment);
+        let actual =
+            links.iter().map(|it| (it.path.as_str(), &text[it.name_range()])).collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn finds_doc_links() {
+        check("/// See [`Foo`] and [Bar].", &[("Foo", "Foo"), ("Bar", "Bar")]);
+        check("/// See [the foo](crate::foo::Foo).", &[("crate::foo::Foo", "Foo")]);
+        check(
+            "/// [`struct@Foo`], [`foo()`], [`foo!`]",
+            &[("Foo", "Foo"), ("foo", "foo"), ("foo", "foo")],
+        );
+    }
+
+    #[test]
+    fn skips_non_links() {
+        check("/// [1], [docs](https://docs.rs), [a][b], [`a b`]", &[]);
+        check("// [`Foo`]", &[]);
+    }
+
+    #[test]
+    fn finds_doc_links_in_whole_block() {
+        check(
+            "/// Example:\n///\n/// ```\n/// let x = v[Idx];\n/// ```\n/// See [Foo] and `[Bar]`.",
+            &[("Foo", "Foo")],
+        );
+        check("/// See [the\n/// foo](crate::foo::Foo).", &[("crate::foo::Foo", "Foo")]);
+        check("/// Not [Foo]\n\n/// [Bar]", &[("Foo", "Foo")]);
+    }
+}
@@ -23,19 +23,39 @@ impl<'a> ImportsLocator<'a> {
 
     pub fn find_imports(&mut self, name_to_import: &str) -> Vec<Either<ModuleDef, MacroDef>> {
         let _p = profile("search_for_imports");
+        self.search(name_to_import, true, 40)
+    }
+
+    /// Finds at most `limit` items from the workspace and the libraries whose
+    /// name fuzzily matches `name`, to be offered as completions.
+    pub fn find_similar(&mut self, name: &str, limit: usize) -> Vec<Either<ModuleDef, MacroDef>> {
+        let _p = profile("search_for_similar_imports");
+        self.search(name, false, limit)
+    }
+
+    fn search(
+        &mut self,
+        name: &str,
+        exact: bool,
+        limit: usize,
+    ) -> Vec<Either<ModuleDef, MacroDef>> {
         let db = self.sema.db;
 
         let project_results = {
-            let mut query = Query::new(name_to_import.to_string());
-            query.exact();
-            query.limit(40);
+            let mut query = Query::new(name.to_string());
+            if exact {
+                query.exact();
+            }
+            query.limit(limit);
             symbol_index::world_symbols(db, query)
         };
         let lib_results = {
-            let mut query = Query::new(name_to_import.to_string());
+            let mut query = Query::new(name.to_string());
             query.libs();
-            query.exact();
-            query.limit(40);
+            if exact {
+                query.exact();
+            }
+            query.limit(limit);
             symbol_index::world_symbols(db, query)
         };
 
@@ -8,6 +8,7 @@ pub mod line_index_utils;
 pub mod symbol_index;
 pub mod change;
 pub mod defs;
+pub mod doc_links;
 pub mod search;
 pub mod imports_locator;
 pub mod source_change;
@@ -15,7 +16,7 @@ mod wasm_shims;
 
 use std::sync::Arc;
 
-use hir::db::{AstDatabase, DefDatabase};
+use hir::db::{AstDatabase, DefDatabase, HirDatabase};
 use ra_db::{
     salsa::{self, Database, Durability},
     Canceled, CheckCanceled, CrateId, FileId, FileLoader, FileLoaderDelegate, RelativePath,
@@ -117,9 +118,14 @@ impl RootDatabase {
         db.set_local_roots_with_durability(Default::default(), Durability::HIGH);
         db.set_library_roots_with_durability(Default::default(), Durability::HIGH);
         db.update_lru_capacity(lru_capacity);
+        db.update_trait_solver_config(Default::default());
         db
     }
 
+    pub fn update_trait_solver_config(&mut self, config: hir::TraitSolverConfig) {
+        self.set_trait_solver_config_with_durability(config, Durability::HIGH);
+    }
+
     pub fn update_lru_capacity(&mut self, lru_capacity: Option<usize>) {
         let lru_capacity = lru_capacity.unwrap_or(ra_db::DEFAULT_LRU_CAP);
         self.query_mut(ra_db::ParseQuery).set_lru_capacity(lru_capacity);
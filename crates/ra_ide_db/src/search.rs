@@ -10,12 +10,13 @@ use hir::{DefWithBody, HasSource, Module, ModuleSource, Semantics, Visibility};
 use once_cell::unsync::Lazy;
 use ra_db::{FileId, FileRange, SourceDatabaseExt};
 use ra_prof::profile;
-use ra_syntax::{ast, match_ast, AstNode, TextRange, TextSize};
+use ra_syntax::{algo::find_node_at_offset, ast, match_ast, AstNode, TextRange, TextSize};
 use rustc_hash::FxHashMap;
 use test_utils::tested_by;
 
 use crate::{
     defs::{classify_name_ref, Definition, NameRefClass},
+    doc_links::{doc_links, resolve_doc_link},
     RootDatabase,
 };
 
@@ -213,12 +214,30 @@ impl Definition {
                     continue;
                 }
 
-                let name_ref: ast::NameRef =
-                    if let Some(name_ref) = sema.find_node_at_offset_with_descend(&tree, offset) {
-                        name_ref
-                    } else {
-                        continue;
-                    };
+                let token = match tree.token_at_offset(offset).right_biased() {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if let Some(comment) = ast::Comment::cast(token.clone()) {
+                    if let Some(range) = self.doc_link_usage(&sema, &comment, offset, pat) {
+                        refs.push(Reference {
+                            file_range: FileRange { file_id, range },
+                            kind: ReferenceKind::Other,
+                            access: None,
+                        });
+                    }
+                    continue;
+                }
+
+                let name_ref = match find_node_at_offset::<ast::NameRef>(&tree, offset) {
+                    Some(it) => it,
+                    // The match may be an argument of a macro call: look for the
+                    // name in the expansion, `original_range` maps it back below.
+                    None => match ast::NameRef::cast(sema.descend_into_macros(token).parent()) {
+                        Some(it) => it,
+                        None => continue,
+                    },
+                };
 
                 // FIXME: reuse sb
                 // See https://github.com/rust-lang/rust/pull/68198#issuecomment-574269098
@@ -262,6 +281,25 @@ impl Definition {
         }
         refs
     }
+
+    /// If an intra-doc link names this definition at `offset`, like `Foo` in
+    /// ``[`Foo::new`]``, the range of that name.
+    fn doc_link_usage(
+        &self,
+        sema: &Semantics<RootDatabase>,
+        comment: &ast::Comment,
+        offset: TextSize,
+        name: &str,
+    ) -> Option<TextRange> {
+        let range = TextRange::at(offset, TextSize::of(name));
+        let link = doc_links(comment).into_iter().find_map(|it| it.prefix_at(offset))?;
+        if link.name_range() != range
+            || resolve_doc_link(sema, comment, &link).as_ref() != Some(self)
+        {
+            return None;
+        }
+        Some(range)
+    }
 }
 
 fn reference_access(def: &Definition, name_ref: &ast::NameRef) -> Option<ReferenceAccess> {
@@ -40,7 +40,7 @@ impl From<tt::ExpansionError> for ExpandError {
 
 pub use crate::syntax_bridge::{
     ast_to_token_tree, parse_to_token_tree, syntax_node_to_token_tree, token_tree_to_syntax_node,
-    TokenMap,
+    TokenMap, TokenTextRange,
 };
 
 /// This struct contains AST for a single `macro_rules` definition. What might
@@ -165,6 +165,19 @@ impl MacroRules {
         mbe_expander::expand(self, &tt)
     }
 
+    /// The index of the rule used to expand `tt`: the first one matching it
+    /// without errors, or the one matching the most tokens.
+    pub fn best_matching_rule(&self, tt: &tt::Subtree) -> Option<usize> {
+        mbe_expander::best_matching_rule(&self.rules, tt)
+    }
+
+    /// The index of the rule used to expand `tt` and, for each top-level
+    /// metavariable and repetition of its pattern, how many token trees of `tt`
+    /// come before the ones it matched.
+    pub fn matching_fragments(&self, tt: &tt::Subtree) -> Option<(usize, Vec<usize>)> {
+        mbe_expander::matching_fragments(&self.rules, tt)
+    }
+
     pub fn map_id_down(&self, id: tt::TokenId) -> tt::TokenId {
         self.shift.shift(id)
     }
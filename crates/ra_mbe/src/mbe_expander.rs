@@ -15,8 +15,34 @@ pub(crate) fn expand(rules: &crate::MacroRules, input: &tt::Subtree) -> ExpandRe
 }
 
 fn expand_rules(rules: &[crate::Rule], input: &tt::Subtree) -> ExpandResult<tt::Subtree> {
-    let mut match_: Option<(matcher::Match, &crate::Rule)> = None;
-    for rule in rules {
+    match select_rule(rules, input) {
+        Some((_, expansion)) => expansion,
+        None => ExpandResult(tt::Subtree::default(), Some(ExpandError::NoMatchingRule)),
+    }
+}
+
+pub(crate) fn best_matching_rule(rules: &[crate::Rule], input: &tt::Subtree) -> Option<usize> {
+    select_rule(rules, input).map(|(idx, _)| idx)
+}
+
+pub(crate) fn matching_fragments(
+    rules: &[crate::Rule],
+    input: &tt::Subtree,
+) -> Option<(usize, Vec<usize>)> {
+    let (idx, _) = select_rule(rules, input)?;
+    let starts = matcher::fragment_starts(&rules[idx].lhs, input).ok()?;
+    Some((idx, starts))
+}
+
+/// Picks the rule `input` expands with, returning its index and the expansion:
+/// the first rule which matches and transcribes without errors, or else the
+/// rule which matched the most tokens with the fewest errors.
+fn select_rule(
+    rules: &[crate::Rule],
+    input: &tt::Subtree,
+) -> Option<(usize, ExpandResult<tt::Subtree>)> {
+    let mut match_: Option<(matcher::Match, usize)> = None;
+    for (idx, rule) in rules.iter().enumerate() {
         let new_match = match matcher::match_(&rule.lhs, input) {
             Ok(m) => m,
             Err(_e) => {
@@ -31,7 +57,7 @@ fn expand_rules(rules: &[crate::Rule], input: &tt::Subtree) -> ExpandResult<tt::
             let ExpandResult(res, transcribe_err) =
                 transcriber::transcribe(&rule.rhs, &new_match.bindings);
             if transcribe_err.is_none() {
-                return ExpandResult::ok(res);
+                return Some((idx, ExpandResult::ok(res)));
             }
         }
         // Use the rule if we matched more tokens, or had fewer errors
@@ -39,20 +65,17 @@ fn expand_rules(rules: &[crate::Rule], input: &tt::Subtree) -> ExpandResult<tt::
             if (new_match.unmatched_tts, new_match.err_count)
                 < (prev_match.unmatched_tts, prev_match.err_count)
             {
-                match_ = Some((new_match, rule));
+                match_ = Some((new_match, idx));
             }
         } else {
-            match_ = Some((new_match, rule));
+            match_ = Some((new_match, idx));
         }
     }
-    if let Some((match_, rule)) = match_ {
-        // if we got here, there was no match without errors
-        let ExpandResult(result, transcribe_err) =
-            transcriber::transcribe(&rule.rhs, &match_.bindings);
-        ExpandResult(result, match_.err.or(transcribe_err))
-    } else {
-        ExpandResult(tt::Subtree::default(), Some(ExpandError::NoMatchingRule))
-    }
+    let (match_, idx) = match_?;
+    // if we got here, there was no match without errors
+    let ExpandResult(result, transcribe_err) =
+        transcriber::transcribe(&rules[idx].rhs, &match_.bindings);
+    Some((idx, ExpandResult(result, match_.err.or(transcribe_err))))
 }
 
 /// The actual algorithm for expansion is not too hard, but is pretty tricky.
@@ -142,6 +165,14 @@ mod tests {
         // Add an err test case for ($($i:ident)) => ($())
     }
 
+    #[test]
+    fn test_expand_incomplete_repetition() {
+        let rules = create_rules(&format_macro("($($e:expr),*) => ([$($e),*])"));
+        let ExpandResult(expansion, err) = expand_first(&rules, "foo!(first, second.)");
+        assert!(expansion.to_string().contains("second"));
+        assert!(err.is_some());
+    }
+
     fn assert_err(macro_body: &str, invocation: &str, err: ExpandError) {
         assert_eq!(expand_first(&create_rules(&format_macro(macro_body)), invocation).1, Some(err));
     }
@@ -67,6 +67,8 @@ pub(super) struct Match {
     pub err_count: usize,
     /// How many top-level token trees were left to match.
     pub unmatched_tts: usize,
+    /// Whether a fragment was bound even though it didn't parse completely.
+    pub incomplete_fragment: bool,
 }
 
 impl Match {
@@ -99,81 +101,107 @@ pub(super) fn match_(pattern: &tt::Subtree, src: &tt::Subtree) -> Result<Match,
     Ok(res)
 }
 
+/// For each top-level metavariable and repetition of `pattern`, the number of
+/// token trees of `src` before the ones it matched.
+pub(super) fn fragment_starts(
+    pattern: &tt::Subtree,
+    src: &tt::Subtree,
+) -> Result<Vec<usize>, ExpandError> {
+    let mut res = Match::default();
+    let mut src = TtIter::new(src);
+    let total = src.len();
+    let mut starts = Vec::new();
+    for op in parse_pattern(pattern) {
+        let op = op?;
+        if matches!(op, Op::Var { .. } | Op::Repeat { .. }) {
+            starts.push(total - src.len());
+        }
+        match_op(&mut res, op, &mut src)?;
+    }
+    Ok(starts)
+}
+
 fn match_subtree(
     res: &mut Match,
     pattern: &tt::Subtree,
     src: &mut TtIter,
 ) -> Result<(), ExpandError> {
     for op in parse_pattern(pattern) {
-        match op? {
-            Op::TokenTree(tt::TokenTree::Leaf(lhs)) => {
-                let rhs = match src.expect_leaf() {
-                    Ok(l) => l,
-                    Err(()) => {
-                        res.add_err(err!("expected leaf: `{}`", lhs));
-                        continue;
-                    }
-                };
-                match (lhs, rhs) {
-                    (
-                        tt::Leaf::Punct(tt::Punct { char: lhs, .. }),
-                        tt::Leaf::Punct(tt::Punct { char: rhs, .. }),
-                    ) if lhs == rhs => (),
-                    (
-                        tt::Leaf::Ident(tt::Ident { text: lhs, .. }),
-                        tt::Leaf::Ident(tt::Ident { text: rhs, .. }),
-                    ) if lhs == rhs => (),
-                    (
-                        tt::Leaf::Literal(tt::Literal { text: lhs, .. }),
-                        tt::Leaf::Literal(tt::Literal { text: rhs, .. }),
-                    ) if lhs == rhs => (),
-                    _ => {
-                        res.add_err(ExpandError::UnexpectedToken);
-                    }
+        match_op(res, op?, src)?;
+    }
+    Ok(())
+}
+
+fn match_op(res: &mut Match, op: Op, src: &mut TtIter) -> Result<(), ExpandError> {
+    match op {
+        Op::TokenTree(tt::TokenTree::Leaf(lhs)) => {
+            let rhs = match src.expect_leaf() {
+                Ok(l) => l,
+                Err(()) => {
+                    res.add_err(err!("expected leaf: `{}`", lhs));
+                    return Ok(());
                 }
-            }
-            Op::TokenTree(tt::TokenTree::Subtree(lhs)) => {
-                let rhs = match src.expect_subtree() {
-                    Ok(s) => s,
-                    Err(()) => {
-                        res.add_err(err!("expected subtree"));
-                        continue;
-                    }
-                };
-                if lhs.delimiter_kind() != rhs.delimiter_kind() {
-                    res.add_err(err!("mismatched delimiter"));
-                    continue;
+            };
+            match (lhs, rhs) {
+                (
+                    tt::Leaf::Punct(tt::Punct { char: lhs, .. }),
+                    tt::Leaf::Punct(tt::Punct { char: rhs, .. }),
+                ) if lhs == rhs => (),
+                (
+                    tt::Leaf::Ident(tt::Ident { text: lhs, .. }),
+                    tt::Leaf::Ident(tt::Ident { text: rhs, .. }),
+                ) if lhs == rhs => (),
+                (
+                    tt::Leaf::Literal(tt::Literal { text: lhs, .. }),
+                    tt::Leaf::Literal(tt::Literal { text: rhs, .. }),
+                ) if lhs == rhs => (),
+                _ => {
+                    res.add_err(ExpandError::UnexpectedToken);
                 }
-                let mut src = TtIter::new(rhs);
-                match_subtree(res, lhs, &mut src)?;
-                if src.len() > 0 {
-                    res.add_err(err!("leftover tokens"));
+            }
+        }
+        Op::TokenTree(tt::TokenTree::Subtree(lhs)) => {
+            let rhs = match src.expect_subtree() {
+                Ok(s) => s,
+                Err(()) => {
+                    res.add_err(err!("expected subtree"));
+                    return Ok(());
                 }
+            };
+            if lhs.delimiter_kind() != rhs.delimiter_kind() {
+                res.add_err(err!("mismatched delimiter"));
+                return Ok(());
             }
-            Op::Var { name, kind } => {
-                let kind = match kind {
-                    Some(k) => k,
-                    None => {
-                        res.add_err(ExpandError::UnexpectedToken);
-                        continue;
-                    }
-                };
-                let ExpandResult(matched, match_err) = match_meta_var(kind.as_str(), src);
-                match matched {
-                    Some(fragment) => {
-                        res.bindings.inner.insert(name.clone(), Binding::Fragment(fragment));
-                    }
-                    None if match_err.is_none() => res.bindings.push_optional(name),
-                    _ => {}
+            let mut src = TtIter::new(rhs);
+            match_subtree(res, lhs, &mut src)?;
+            if src.len() > 0 {
+                res.add_err(err!("leftover tokens"));
+            }
+        }
+        Op::Var { name, kind } => {
+            let kind = match kind {
+                Some(k) => k,
+                None => {
+                    res.add_err(ExpandError::UnexpectedToken);
+                    return Ok(());
                 }
-                if let Some(err) = match_err {
-                    res.add_err(err);
+            };
+            let ExpandResult(matched, match_err) = match_meta_var(kind.as_str(), src);
+            match matched {
+                Some(fragment) => {
+                    res.incomplete_fragment |= match_err.is_some();
+                    res.bindings.inner.insert(name.clone(), Binding::Fragment(fragment));
                 }
+                None if match_err.is_none() => res.bindings.push_optional(name),
+                _ => {}
             }
-            Op::Repeat { subtree, kind, separator } => {
-                match_repeat(res, subtree, kind, separator, src)?;
+            if let Some(err) = match_err {
+                res.add_err(err);
             }
         }
+        Op::Repeat { subtree, kind, separator } => {
+            match_repeat(res, subtree, kind, separator, src)?;
+        }
     }
     Ok(())
 }
@@ -404,6 +432,20 @@ pub(super) fn match_repeat(
                 }
             }
         } else {
+            // Keep a repetition with an incomplete fragment, like the `a.` in
+            // `vec![a.]`, so that its tokens survive in the expansion. This is
+            // what completion inside macro calls relies on.
+            if nested.incomplete_fragment {
+                *src = fork;
+                if let Err(err) = res.bindings.push_nested(counter, nested.bindings) {
+                    res.add_err(err);
+                }
+                if let Some(err) = nested.err {
+                    res.add_err(err);
+                }
+                res.incomplete_fragment = true;
+                counter += 1;
+            }
             break;
         }
     }
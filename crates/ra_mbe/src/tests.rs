@@ -305,6 +305,39 @@ fn test_fail_match_pattern_by_first_token() {
     .assert_expand_items("foo! { + Baz }", "struct Baz ;");
 }
 
+#[test]
+fn test_best_matching_rule() {
+    parse_macro(
+        r#"
+        macro_rules! foo {
+            ($ i:ident) => ();
+            ($ i:ident = $ e:expr) => ();
+            ($ i:ident + $ j:ident + $ k:ident) => ();
+        }
+"#,
+    )
+    .assert_best_matching_rule("foo! { foo }", 0)
+    .assert_best_matching_rule("foo! { foo = 92 }", 1)
+    .assert_best_matching_rule("foo! { foo = }", 1)
+    .assert_best_matching_rule("foo! { foo + bar + }", 2);
+}
+
+#[test]
+fn test_matching_fragments() {
+    parse_macro(
+        r#"
+        macro_rules! foo {
+            ($ i:ident) => ();
+            ($ i:ident = $ e:expr) => ();
+            ($ e:expr ; $ ($ es:expr);*) => ();
+        }
+"#,
+    )
+    .assert_matching_fragments("foo! { foo }", 0, &[0])
+    .assert_matching_fragments("foo! { foo = 1 + 2 }", 1, &[0, 2])
+    .assert_matching_fragments("foo! { 1 ; 2 ; 3 }", 2, &[0, 2]);
+}
+
 #[test]
 fn test_fail_match_pattern_by_last_token() {
     parse_macro(
@@ -1583,6 +1616,31 @@ impl MacroFixture {
         self.rules.expand(&invocation_tt).result()
     }
 
+    fn assert_best_matching_rule(&self, invocation: &str, rule: usize) -> &MacroFixture {
+        let source_file = ast::SourceFile::parse(invocation).tree();
+        let macro_invocation =
+            source_file.syntax().descendants().find_map(ast::MacroCall::cast).unwrap();
+        let (invocation_tt, _) =
+            ast_to_token_tree(&macro_invocation.token_tree().unwrap()).unwrap();
+        assert_eq!(self.rules.best_matching_rule(&invocation_tt), Some(rule));
+        self
+    }
+
+    fn assert_matching_fragments(
+        &self,
+        invocation: &str,
+        rule: usize,
+        starts: &[usize],
+    ) -> &MacroFixture {
+        let source_file = ast::SourceFile::parse(invocation).tree();
+        let macro_invocation =
+            source_file.syntax().descendants().find_map(ast::MacroCall::cast).unwrap();
+        let (invocation_tt, _) =
+            ast_to_token_tree(&macro_invocation.token_tree().unwrap()).unwrap();
+        assert_eq!(self.rules.matching_fragments(&invocation_tt), Some((rule, starts.to_vec())));
+        self
+    }
+
     fn assert_expand_err(&self, invocation: &str, err: &ExpandError) {
         assert_eq!(self.try_expand_tt(invocation).as_ref(), Err(err));
     }
@@ -1798,6 +1856,23 @@ fn test_repeat_bad_var() {
     .assert_expand_items("foo!(b0 b1);", "b0 b1");
 }
 
+#[test]
+fn test_best_matching_rule_skips_bad_transcription() {
+    parse_macro(
+        r#"
+        macro_rules! foo {
+            ($( $b:ident )+) => {
+                $( $c )+
+            };
+            ($( $b:ident )+) => {
+                $( $b )+
+            }
+        }
+    "#,
+    )
+    .assert_best_matching_rule("foo!(b0 b1);", 1);
+}
+
 #[test]
 fn test_no_space_after_semi_colon() {
     let expanded = parse_macro(
@@ -52,12 +52,14 @@ pub(super) const ATOM_EXPR_FIRST: TokenSet =
         T![match],
         T![unsafe],
         T![return],
+        T![yield],
         T![break],
         T![continue],
         T![async],
         T![try],
         T![loop],
         T![for],
+        T![_],
         LIFETIME,
     ]);
 
@@ -135,8 +137,19 @@ pub(super) fn atom_expr(p: &mut Parser, r: Restrictions) -> Option<(CompletedMar
             block_expr_unchecked(p)
         }
         T![return] => return_expr(p),
+        T![yield] => yield_expr(p),
         T![continue] => continue_expr(p),
         T![break] => break_expr(p, r),
+        // test_err underscore_expr
+        // fn foo() {
+        //     let x: u32 = _;
+        //     bar(_, 1);
+        // }
+        T![_] => {
+            let m = p.start();
+            p.bump(T![_]);
+            m.complete(p, UNDERSCORE_EXPR)
+        }
         _ => {
             p.err_recover("expected expression", EXPR_RECOVERY_SET);
             return None;
@@ -500,6 +513,21 @@ fn return_expr(p: &mut Parser) -> CompletedMarker {
     m.complete(p, RETURN_EXPR)
 }
 
+// test yield_expr
+// fn foo() {
+//     yield;
+//     yield 92;
+// }
+fn yield_expr(p: &mut Parser) -> CompletedMarker {
+    assert!(p.at(T![yield]));
+    let m = p.start();
+    p.bump(T![yield]);
+    if p.at_ts(EXPR_FIRST) {
+        expr(p);
+    }
+    m.complete(p, YIELD_EXPR)
+}
+
 // test continue_expr
 // fn foo() {
 //     loop {
@@ -348,7 +348,15 @@ impl ProjectWorkspace {
                             opts
                         };
 
-                        let env = Env::default();
+                        // Set like when the toolchain builds the standard
+                        // library, so that we can link to the matching docs.
+                        let mut env = Env::default();
+                        if let (Some(release), Some(channel)) =
+                            (sysroot.release(), sysroot.release_channel())
+                        {
+                            env.set("CFG_RELEASE", release.to_string());
+                            env.set("CFG_RELEASE_CHANNEL", channel.to_string());
+                        }
                         let extern_source = ExternSource::default();
                         let proc_macro = vec![];
                         let crate_name = CrateName::new(&sysroot[krate].name)
@@ -402,6 +410,8 @@ impl ProjectWorkspace {
                                 opts
                             };
                             let mut env = Env::default();
+                            env.set("CARGO_PKG_NAME", cargo[pkg].name.clone());
+                            env.set("CARGO_PKG_VERSION", cargo[pkg].version.clone());
                             let mut extern_source = ExternSource::default();
                             if let Some(out_dir) = &cargo[pkg].out_dir {
                                 // NOTE: cargo and rustc seem to hide non-UTF-8 strings from env! and option_env!()
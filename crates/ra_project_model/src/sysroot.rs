@@ -12,6 +12,7 @@ use ra_arena::{Arena, Idx};
 #[derive(Default, Debug, Clone)]
 pub struct Sysroot {
     crates: Arena<SysrootCrateData>,
+    release: Option<String>,
 }
 
 pub type SysrootCrate = Idx<SysrootCrateData>;
@@ -47,13 +48,30 @@ impl Sysroot {
         self.by_name("proc_macro")
     }
 
+    /// The toolchain's version, like `1.44.0` or `1.45.0-nightly`.
+    pub fn release(&self) -> Option<&str> {
+        self.release.as_deref()
+    }
+
+    /// `stable`, `beta` or `nightly`.
+    pub fn release_channel(&self) -> Option<&str> {
+        let release = self.release()?;
+        let channel = match release.find('-') {
+            None => "stable",
+            Some(idx) if release[idx + 1..].starts_with("beta") => "beta",
+            Some(_) => "nightly",
+        };
+        Some(channel)
+    }
+
     pub fn crates<'a>(&'a self) -> impl Iterator<Item = SysrootCrate> + ExactSizeIterator + 'a {
         self.crates.iter().map(|(id, _data)| id)
     }
 
     pub fn discover(cargo_toml: &Path) -> Result<Sysroot> {
         let src = get_or_install_rust_src(cargo_toml)?;
-        let mut sysroot = Sysroot { crates: Arena::default() };
+        let release = rustc_release(cargo_toml);
+        let mut sysroot = Sysroot { crates: Arena::default(), release };
         for name in SYSROOT_CRATES.trim().lines() {
             let root = src.join(format!("lib{}", name)).join("lib.rs");
             if root.exists() {
@@ -134,6 +152,14 @@ fn get_or_install_rust_src(cargo_toml: &Path) -> Result<PathBuf> {
     Ok(src_path)
 }
 
+/// The version printed by `rustc --version`, like `1.44.0` for
+/// `rustc 1.44.0 (49cae5576 2020-06-01)`.
+fn rustc_release(cargo_toml: &Path) -> Option<String> {
+    let output = run_command_in_cargo_dir(cargo_toml, "rustc", &["--version"]).ok()?;
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    stdout.split_whitespace().nth(1).map(|it| it.to_string())
+}
+
 impl SysrootCrateData {
     pub fn root_dir(&self) -> &Path {
         self.root.parent().unwrap()
@@ -97,6 +97,7 @@ pub(crate) fn validate(root: &SyntaxNode) -> Vec<SyntaxError> {
                 ast::Visibility(it) => validate_visibility(it, &mut errors),
                 ast::RangeExpr(it) => validate_range_expr(it, &mut errors),
                 ast::PathSegment(it) => validate_path_keywords(it, &mut errors),
+                ast::UnderscoreExpr(it) => validate_underscore_expr(it, &mut errors),
                 _ => (),
             }
         }
@@ -224,6 +225,12 @@ fn validate_range_expr(expr: ast::RangeExpr, errors: &mut Vec<SyntaxError>) {
     }
 }
 
+fn validate_underscore_expr(expr: ast::UnderscoreExpr, errors: &mut Vec<SyntaxError>) {
+    // We parse `_` as an expression so that the IDE can treat it as a typed
+    // hole, but it's not valid Rust.
+    errors.push(SyntaxError::new("expected expression, found `_`", expr.syntax().text_range()));
+}
+
 fn validate_path_keywords(segment: ast::PathSegment, errors: &mut Vec<SyntaxError>) {
     use ast::PathSegmentKind;
 
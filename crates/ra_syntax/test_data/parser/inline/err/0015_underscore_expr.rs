@@ -0,0 +1,4 @@
+fn foo() {
+    let x: u32 = _;
+    bar(_, 1);
+}
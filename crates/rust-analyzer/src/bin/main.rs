@@ -77,6 +77,7 @@ fn run_server() -> Result<()> {
     let server_capabilities = serde_json::to_value(rust_analyzer::server_capabilities()).unwrap();
 
     let initialize_params = connection.initialize(server_capabilities)?;
+    let raw_caps = initialize_params.get("capabilities").cloned().unwrap_or_default();
     let initialize_params =
         from_json::<lsp_types::InitializeParams>("InitializeParams", initialize_params)?;
 
@@ -103,6 +104,7 @@ fn run_server() -> Result<()> {
         if let Some(caps) = &initialize_params.capabilities.text_document {
             config.update_caps(caps);
         }
+        config.update_raw_caps(&raw_caps);
         config
     };
 
@@ -11,7 +11,7 @@ use std::{ffi::OsString, path::PathBuf};
 
 use lsp_types::TextDocumentClientCapabilities;
 use ra_flycheck::FlycheckConfig;
-use ra_ide::{CompletionConfig, InlayHintsConfig};
+use ra_ide::{CompletionConfig, InlayHintsConfig, TraitSolverConfig};
 use ra_project_model::CargoConfig;
 use serde::Deserialize;
 
@@ -22,6 +22,7 @@ pub struct Config {
     pub with_sysroot: bool,
     pub publish_diagnostics: bool,
     pub lru_capacity: Option<usize>,
+    pub trait_solver: TraitSolverConfig,
     pub proc_macro_srv: Option<(PathBuf, Vec<OsString>)>,
     pub files: FilesConfig,
     pub notifications: NotificationsConfig,
@@ -70,6 +71,9 @@ pub struct ClientCapsConfig {
     pub line_folding_only: bool,
     pub hierarchical_symbols: bool,
     pub code_action_literals: bool,
+    /// Whether the client sends `workspace/willRenameFiles` requests, which
+    /// we register for dynamically.
+    pub will_rename_files: bool,
 }
 
 impl Default for Config {
@@ -80,6 +84,7 @@ impl Default for Config {
             with_sysroot: true,
             publish_diagnostics: true,
             lru_capacity: None,
+            trait_solver: TraitSolverConfig::default(),
             proc_macro_srv: None,
             files: FilesConfig { watcher: FilesWatcher::Notify, exclude: Vec::new() },
             notifications: NotificationsConfig { cargo_toml_not_found: true },
@@ -97,10 +102,12 @@ impl Default for Config {
                 type_hints: true,
                 parameter_hints: true,
                 chaining_hints: true,
+                binding_mode_hints: false,
                 max_length: None,
             },
             completion: CompletionConfig {
                 enable_postfix_completions: true,
+                enable_autoimport_completions: true,
                 add_call_parenthesis: true,
                 add_call_argument_snippets: true,
                 ..CompletionConfig::default()
@@ -122,6 +129,8 @@ impl Config {
         set(value, "/withSysroot", &mut self.with_sysroot);
         set(value, "/diagnostics/enable", &mut self.publish_diagnostics);
         set(value, "/lruCapacity", &mut self.lru_capacity);
+        set(value, "/traitSolver/fuel", &mut self.trait_solver.fuel);
+        set(value, "/traitSolver/overflowDepth", &mut self.trait_solver.overflow_depth);
         self.files.watcher = match get(value, "/files/watcher") {
             Some("client") => FilesWatcher::Client,
             Some("notify") | _ => FilesWatcher::Notify
@@ -189,8 +198,10 @@ impl Config {
         set(value, "/inlayHints/typeHints", &mut self.inlay_hints.type_hints);
         set(value, "/inlayHints/parameterHints", &mut self.inlay_hints.parameter_hints);
         set(value, "/inlayHints/chainingHints", &mut self.inlay_hints.chaining_hints);
+        set(value, "/inlayHints/bindingModeHints", &mut self.inlay_hints.binding_mode_hints);
         set(value, "/inlayHints/maxLength", &mut self.inlay_hints.max_length);
         set(value, "/completion/postfix/enable", &mut self.completion.enable_postfix_completions);
+        set(value, "/completion/autoimport/enable", &mut self.completion.enable_autoimport_completions);
         set(value, "/completion/addCallParenthesis", &mut self.completion.add_call_parenthesis);
         set(value, "/completion/addCallArgumentSnippets", &mut self.completion.add_call_argument_snippets);
         set(value, "/callInfo/full", &mut self.call_info_full);
@@ -234,4 +245,24 @@ impl Config {
             }
         }
     }
+
+    /// Reads the client capabilities `lsp_types` doesn't know about yet from
+    /// the raw `capabilities` of the `initialize` request.
+    pub fn update_raw_caps(&mut self, caps: &serde_json::Value) {
+        let file_operations = caps.pointer("/workspace/fileOperations");
+        let flag = |name: &str| {
+            file_operations.and_then(|it| it.get(name)).and_then(|it| it.as_bool()) == Some(true)
+        };
+        self.client_caps.will_rename_files = flag("willRename") && flag("dynamicRegistration");
+
+        let resolve_properties =
+            caps.pointer("/textDocument/completion/completionItem/resolveSupport/properties");
+        let resolves = |name: &str| {
+            resolve_properties
+                .and_then(|it| it.as_array())
+                .map_or(false, |it| it.iter().any(|it| it.as_str() == Some(name)))
+        };
+        self.completion.resolve_lazily =
+            resolves("documentation") && resolves("detail") && resolves("additionalTextEdits");
+    }
 }
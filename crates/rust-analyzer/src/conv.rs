@@ -9,10 +9,10 @@ use lsp_types::{
     TextDocumentPositionParams, Url, VersionedTextDocumentIdentifier, WorkspaceEdit,
 };
 use ra_ide::{
-    translate_offset_with_edit, CompletionItem, CompletionItemKind, FileId, FilePosition,
-    FileRange, FileSystemEdit, Fold, FoldKind, Highlight, HighlightModifier, HighlightTag,
-    InlayHint, InlayKind, InsertTextFormat, LineCol, LineIndex, NavigationTarget, RangeInfo,
-    ReferenceAccess, Severity, SourceChange, SourceFileEdit,
+    translate_offset_with_edit, CompletionItem, CompletionItemKind, CompletionScore, FileId,
+    FilePosition, FileRange, FileSystemEdit, Fold, FoldKind, Highlight, HighlightModifier,
+    HighlightTag, InlayHint, InlayKind, InsertTextFormat, LineCol, LineIndex, NavigationTarget,
+    RangeInfo, ReferenceAccess, Severity, SourceChange, SourceFileEdit,
 };
 use ra_syntax::{SyntaxKind, TextRange, TextSize};
 use ra_text_edit::{Indel, TextEdit};
@@ -20,7 +20,10 @@ use ra_vfs::LineEndings;
 
 use crate::{
     req,
-    semantic_tokens::{self, ModifierSet, CONSTANT, CONTROL_FLOW, MUTABLE, UNSAFE},
+    semantic_tokens::{
+        self, ModifierSet, ASSOCIATED, CALLABLE, CONSTANT, CONSUMING, CONTROL_FLOW, LIBRARY,
+        MUTABLE, MUTABLE_REFERENCE, UNSAFE,
+    },
     world::WorldSnapshot,
     Result,
 };
@@ -143,6 +146,11 @@ impl ConvWith<(&LineIndex, LineEndings)> for CompletionItem {
             }
         }
         let text_edit = text_edit.unwrap();
+        if let Some(import_edit) = self.import_edit() {
+            for indel in import_edit.as_indels() {
+                additional_text_edits.push(indel.conv_with((ctx.0, ctx.1)));
+            }
+        }
 
         let mut res = lsp_types::CompletionItem {
             label: self.label().to_string(),
@@ -170,6 +178,15 @@ impl ConvWith<(&LineIndex, LineEndings)> for CompletionItem {
             res.preselect = Some(true)
         }
 
+        // Items of the expected type go first, then local bindings.
+        let relevance = match (self.score(), self.kind()) {
+            (Some(CompletionScore::TypeAndNameMatch), _) => 0,
+            (Some(CompletionScore::TypeMatch), _) => 1,
+            (None, Some(CompletionItemKind::Binding)) => 2,
+            (None, _) => 3,
+        };
+        res.sort_text = Some(format!("{}{}", relevance, self.label()));
+
         if self.deprecated() {
             res.tags = Some(vec![lsp_types::CompletionItemTag::Deprecated])
         }
@@ -340,6 +357,7 @@ impl ConvWith<&LineIndex> for InlayHint {
                 InlayKind::ParameterHint => req::InlayKind::ParameterHint,
                 InlayKind::TypeHint => req::InlayKind::TypeHint,
                 InlayKind::ChainingHint => req::InlayKind::ChainingHint,
+                InlayKind::BindingModeHint => req::InlayKind::BindingModeHint,
             },
         }
     }
@@ -390,6 +408,13 @@ impl Conv for Highlight {
                 HighlightModifier::ControlFlow => CONTROL_FLOW,
                 HighlightModifier::Mutable => MUTABLE,
                 HighlightModifier::Unsafe => UNSAFE,
+                HighlightModifier::Consuming => CONSUMING,
+                HighlightModifier::MutableReference => MUTABLE_REFERENCE,
+                HighlightModifier::Callable => CALLABLE,
+                HighlightModifier::Associated => ASSOCIATED,
+                HighlightModifier::Static => SemanticTokenModifier::STATIC,
+                HighlightModifier::Documentation => SemanticTokenModifier::DOCUMENTATION,
+                HighlightModifier::Library => LIBRARY,
             };
             mods |= modifier;
         }
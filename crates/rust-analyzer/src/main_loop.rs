@@ -164,6 +164,28 @@ pub fn main_loop(ws_roots: Vec<PathBuf>, config: Config, connection: Connection)
             connection.sender.send(request.into()).unwrap();
         }
 
+        if config.client_caps.will_rename_files {
+            let filter = |glob: &str, matches: &str| req::FileOperationFilter {
+                scheme: Some("file".to_string()),
+                pattern: req::FileOperationPattern {
+                    glob: glob.to_string(),
+                    matches: Some(matches.to_string()),
+                },
+            };
+            let registration_options = req::FileOperationRegistrationOptions {
+                filters: vec![filter("**/*.rs", "file"), filter("**", "folder")],
+            };
+            let registration = req::Registration {
+                id: "will-rename-files".to_string(),
+                method: "workspace/willRenameFiles".to_string(),
+                register_options: Some(serde_json::to_value(registration_options).unwrap()),
+            };
+            let params = req::RegistrationParams { registrations: vec![registration] };
+            let request =
+                request_new::<req::RegisterCapability>(loop_state.next_request_id(), params);
+            connection.sender.send(request.into()).unwrap();
+        }
+
         WorldState::new(
             ws_roots,
             workspaces,
@@ -514,23 +536,31 @@ fn on_request(
         .on::<req::AnalyzerStatus>(handlers::handle_analyzer_status)?
         .on::<req::SyntaxTree>(handlers::handle_syntax_tree)?
         .on::<req::ExpandMacro>(handlers::handle_expand_macro)?
+        .on::<req::RevealType>(handlers::handle_reveal_type)?
+        .on::<req::TraceTraitSolve>(handlers::handle_trace_trait_solve)?
+        .on::<req::TypeHierarchy>(handlers::handle_type_hierarchy)?
         .on::<req::OnTypeFormatting>(handlers::handle_on_type_formatting)?
         .on::<req::DocumentSymbolRequest>(handlers::handle_document_symbol)?
         .on::<req::WorkspaceSymbol>(handlers::handle_workspace_symbol)?
         .on::<req::GotoDefinition>(handlers::handle_goto_definition)?
+        .on::<req::GotoDeclaration>(handlers::handle_goto_declaration)?
         .on::<req::GotoImplementation>(handlers::handle_goto_implementation)?
         .on::<req::GotoTypeDefinition>(handlers::handle_goto_type_definition)?
         .on::<req::ParentModule>(handlers::handle_parent_module)?
+        .on::<req::OpenDocs>(handlers::handle_open_docs)?
         .on::<req::Runnables>(handlers::handle_runnables)?
         .on::<req::Completion>(handlers::handle_completion)?
+        .on::<req::ResolveCompletionItem>(handlers::handle_completion_resolve)?
         .on::<req::CodeActionRequest>(handlers::handle_code_action)?
         .on::<req::CodeLensRequest>(handlers::handle_code_lens)?
         .on::<req::CodeLensResolve>(handlers::handle_code_lens_resolve)?
         .on::<req::FoldingRangeRequest>(handlers::handle_folding_range)?
         .on::<req::SignatureHelpRequest>(handlers::handle_signature_help)?
         .on::<req::HoverRequest>(handlers::handle_hover)?
+        .on::<req::HoverWithActions>(handlers::handle_hover_with_actions)?
         .on::<req::PrepareRenameRequest>(handlers::handle_prepare_rename)?
         .on::<req::Rename>(handlers::handle_rename)?
+        .on::<req::WillRenameFiles>(handlers::handle_will_rename_files)?
         .on::<req::References>(handlers::handle_references)?
         .on::<req::Formatting>(handlers::handle_formatting)?
         .on::<req::DocumentHighlightRequest>(handlers::handle_document_highlight)?
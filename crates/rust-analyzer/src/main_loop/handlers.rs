@@ -12,17 +12,19 @@ use lsp_types::{
     CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
     CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
     CodeAction, CodeActionResponse, CodeLens, Command, CompletionItem, Diagnostic,
-    DocumentFormattingParams, DocumentHighlight, DocumentSymbol, FoldingRange, FoldingRangeParams,
-    Hover, HoverContents, Location, MarkupContent, MarkupKind, Position, PrepareRenameResponse,
-    Range, RenameParams, SemanticTokensParams, SemanticTokensRangeParams,
-    SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation, TextDocumentIdentifier,
-    TextEdit, Url, WorkspaceEdit,
+    DiagnosticRelatedInformation, DocumentFormattingParams, DocumentHighlight, DocumentSymbol,
+    FoldingRange, FoldingRangeParams, Hover, HoverContents, Location, MarkupContent, MarkupKind,
+    Position, PrepareRenameResponse, Range, RenameParams, SemanticTokensParams,
+    SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation,
+    TextDocumentIdentifier, TextEdit, Url, WorkspaceEdit,
 };
 use ra_ide::{
-    Assist, FileId, FilePosition, FileRange, Query, RangeInfo, Runnable, RunnableKind, SearchScope,
+    Assist, FileId, FilePosition, FileRange, HoverAction, HoverResult, NavigationTarget, Query,
+    RangeInfo, Runnable, RunnableKind, SearchScope, SourceChange,
 };
 use ra_prof::profile;
 use ra_syntax::{AstNode, SyntaxKind, TextRange, TextSize};
+use relative_path::RelativePathBuf;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::to_value;
@@ -82,6 +84,76 @@ pub fn handle_expand_macro(
     }
 }
 
+pub fn handle_reveal_type(
+    world: WorldSnapshot,
+    params: req::TextDocumentPositionParams,
+) -> Result<Option<req::RevealedType>> {
+    let _p = profile("handle_reveal_type");
+    let position = params.try_conv_with(&world)?;
+    let info = match world.analysis().reveal_type(position)? {
+        None => return Ok(None),
+        Some(info) => info,
+    };
+    let line_index = world.analysis().file_line_index(position.file_id)?;
+    let range = info.range.conv_with(&line_index);
+    Ok(Some(req::RevealedType { range, opaque: info.info.opaque, hidden: info.info.hidden }))
+}
+
+pub fn handle_trace_trait_solve(
+    world: WorldSnapshot,
+    params: req::TextDocumentPositionParams,
+) -> Result<Option<String>> {
+    let _p = profile("handle_trace_trait_solve");
+    let position = params.try_conv_with(&world)?;
+    let res = world.analysis().trace_trait_solve(position)?;
+    Ok(res)
+}
+
+pub fn handle_type_hierarchy(
+    world: WorldSnapshot,
+    params: req::TextDocumentPositionParams,
+) -> Result<Option<req::TypeHierarchyResult>> {
+    let _p = profile("handle_type_hierarchy");
+    let position = params.try_conv_with(&world)?;
+    let info = match world.analysis().type_hierarchy(position)? {
+        None => return Ok(None),
+        Some(info) => info,
+    };
+    let line_index = world.analysis().file_line_index(position.file_id)?;
+    let range = info.range.conv_with(&line_index);
+
+    let to_item = |nav: &NavigationTarget| -> Result<req::TypeHierarchyItem> {
+        Ok(req::TypeHierarchyItem {
+            name: nav.name().to_string(),
+            kind: nav.kind().conv(),
+            location: nav.try_conv_with(&world)?,
+        })
+    };
+    let to_items = |navs: &[NavigationTarget]| -> Result<Vec<req::TypeHierarchyItem>> {
+        navs.iter().map(to_item).collect()
+    };
+
+    let hierarchy = info.info;
+    let implemented_traits = hierarchy
+        .implemented_traits
+        .iter()
+        .map(|it| {
+            Ok(req::CrateTraits {
+                crate_name: it.crate_name.clone(),
+                traits: to_items(&it.traits)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(req::TypeHierarchyResult {
+        range,
+        item: to_item(&hierarchy.item)?,
+        supertraits: to_items(&hierarchy.supertraits)?,
+        subtraits: to_items(&hierarchy.subtraits)?,
+        implementors: to_items(&hierarchy.implementors)?,
+        implemented_traits,
+    }))
+}
+
 pub fn handle_selection_range(
     world: WorldSnapshot,
     params: req::SelectionRangeParams,
@@ -337,6 +409,20 @@ pub fn handle_goto_definition(
     Ok(Some(res))
 }
 
+pub fn handle_goto_declaration(
+    world: WorldSnapshot,
+    params: req::GotoDeclarationParams,
+) -> Result<Option<req::GotoDeclarationResponse>> {
+    let _p = profile("handle_goto_declaration");
+    let position = params.text_document_position_params.try_conv_with(&world)?;
+    let nav_info = match world.analysis().goto_declaration(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let res = (position.file_id, nav_info).try_conv_with(&world)?;
+    Ok(Some(res))
+}
+
 pub fn handle_goto_implementation(
     world: WorldSnapshot,
     params: req::GotoImplementationParams,
@@ -374,6 +460,19 @@ pub fn handle_parent_module(
     world.analysis().parent_module(position)?.iter().try_conv_with_to_vec(&world)
 }
 
+pub fn handle_open_docs(
+    world: WorldSnapshot,
+    params: req::TextDocumentPositionParams,
+) -> Result<Option<Url>> {
+    let _p = profile("handle_open_docs");
+    let position = params.try_conv_with(&world)?;
+    let url = match world.analysis().external_docs(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    Ok(Url::parse(&url).ok())
+}
+
 pub fn handle_runnables(
     world: WorldSnapshot,
     params: req::RunnablesParams,
@@ -427,6 +526,7 @@ pub fn handle_completion(
     params: req::CompletionParams,
 ) -> Result<Option<req::CompletionResponse>> {
     let _p = profile("handle_completion");
+    let text_document_position = params.text_document_position.clone();
     let position = params.text_document_position.try_conv_with(&world)?;
     let completion_triggered_after_single_colon = {
         let mut res = false;
@@ -456,12 +556,72 @@ pub fn handle_completion(
     };
     let line_index = world.analysis().file_line_index(position.file_id)?;
     let line_endings = world.file_line_endings(position.file_id);
-    let items: Vec<CompletionItem> =
-        items.into_iter().map(|item| item.conv_with((&line_index, line_endings))).collect();
+    let resolve_lazily = world.config.completion.resolve_lazily;
+    let items: Vec<CompletionItem> = items
+        .into_iter()
+        .map(|item| {
+            // Clients which can resolve items get only the label and kind here;
+            // docs, detail and the `use` of an item not in scope yet are
+            // computed in `completionItem/resolve`.
+            let data = if resolve_lazily {
+                Some(CompletionResolveData {
+                    position: text_document_position.clone(),
+                    label: item.label().to_string(),
+                })
+            } else {
+                None
+            };
+            let mut item = item.conv_with((&line_index, line_endings));
+            if let Some(data) = data {
+                if item.additional_text_edits.as_ref().map_or(false, |it| it.is_empty()) {
+                    item.additional_text_edits = None;
+                }
+                item.data = Some(to_value(data).unwrap());
+            }
+            item
+        })
+        .collect();
 
     Ok(Some(items.into()))
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletionResolveData {
+    position: req::TextDocumentPositionParams,
+    label: String,
+}
+
+pub fn handle_completion_resolve(
+    world: WorldSnapshot,
+    mut original_item: CompletionItem,
+) -> Result<CompletionItem> {
+    let _p = profile("handle_completion_resolve");
+    let data = match original_item.data.take() {
+        Some(data) => data,
+        None => return Ok(original_item),
+    };
+    let resolve = from_json::<CompletionResolveData>("CompletionResolveData", data)?;
+    let position = resolve.position.try_conv_with(&world)?;
+
+    let item = match world.analysis().resolve_completion(
+        position,
+        &world.config.completion,
+        &resolve.label,
+    )? {
+        None => return Ok(original_item),
+        Some(it) => it,
+    };
+    let line_index = world.analysis().file_line_index(position.file_id)?;
+    let line_endings = world.file_line_endings(position.file_id);
+    let item: CompletionItem = item.conv_with((&line_index, line_endings));
+    original_item.detail = item.detail;
+    original_item.documentation = item.documentation;
+    original_item.additional_text_edits =
+        item.additional_text_edits.filter(|edits| !edits.is_empty());
+    Ok(original_item)
+}
+
 pub fn handle_folding_range(
     world: WorldSnapshot,
     params: FoldingRangeParams,
@@ -511,16 +671,110 @@ pub fn handle_hover(world: WorldSnapshot, params: req::HoverParams) -> Result<Op
         None => return Ok(None),
         Some(info) => info,
     };
-    let line_index = world.analysis.file_line_index(position.file_id)?;
+    Ok(Some(to_lsp_hover(&world, position.file_id, &info)?))
+}
+
+pub fn handle_hover_with_actions(
+    world: WorldSnapshot,
+    params: req::TextDocumentPositionParams,
+) -> Result<Option<req::HoverWithActionsResult>> {
+    let _p = profile("handle_hover_with_actions");
+    let position = params.try_conv_with(&world)?;
+    let info = match world.analysis().hover(position)? {
+        None => return Ok(None),
+        Some(info) => info,
+    };
+    let hover = to_lsp_hover(&world, position.file_id, &info)?;
+    let actions = to_command_link_groups(&world, info.info.actions())?;
+    Ok(Some(req::HoverWithActionsResult { hover, actions }))
+}
+
+fn to_lsp_hover(
+    world: &WorldSnapshot,
+    file_id: FileId,
+    info: &RangeInfo<HoverResult>,
+) -> Result<Hover> {
+    let line_index = world.analysis.file_line_index(file_id)?;
     let range = info.range.conv_with(&line_index);
-    let res = Hover {
+    // Intra-doc links to items in the workspace point at their source.
+    let markup = info.info.to_markup_with_links(|nav| {
+        let location: Location = nav.try_conv_with(world).ok()?;
+        Some(format!("{}#L{}", location.uri, location.range.start.line + 1))
+    });
+    Ok(Hover {
         contents: HoverContents::Markup(MarkupContent {
             kind: MarkupKind::Markdown,
-            value: crate::markdown::format_docs(&info.info.to_markup()),
+            value: crate::markdown::format_docs(&markup),
         }),
         range: Some(range),
-    };
-    Ok(Some(res))
+    })
+}
+
+fn to_command_link_groups(
+    world: &WorldSnapshot,
+    actions: &[HoverAction],
+) -> Result<Vec<req::CommandLinkGroup>> {
+    let mut res = Vec::new();
+    for action in actions {
+        match action {
+            HoverAction::Implementation(position) => {
+                let locations: Vec<Location> =
+                    match world.analysis().goto_implementation(*position)? {
+                        Some(it) => it.info.iter().try_conv_with_to_vec(world)?,
+                        None => Vec::new(),
+                    };
+                if locations.is_empty() {
+                    continue;
+                }
+                let title = if locations.len() == 1 {
+                    "1 implementation".into()
+                } else {
+                    format!("{} implementations", locations.len())
+                };
+                let line_index = world.analysis().file_line_index(position.file_id)?;
+                let uri = world.file_id_to_uri(position.file_id)?;
+                let command = Command {
+                    title,
+                    command: "rust-analyzer.showReferences".into(),
+                    arguments: Some(vec![
+                        to_value(&uri).unwrap(),
+                        to_value(position.offset.conv_with(&line_index)).unwrap(),
+                        to_value(locations).unwrap(),
+                    ]),
+                };
+                res.push(req::CommandLinkGroup {
+                    title: None,
+                    commands: vec![req::CommandLink { command, tooltip: None }],
+                });
+            }
+            HoverAction::Runnable(file_id, runnable) => {
+                let r = to_lsp_runnable(world, *file_id, runnable.clone())?;
+                let (run, debug) = run_and_debug_commands(r, "▶\u{fe0e} Run".to_string());
+                res.push(req::CommandLinkGroup {
+                    title: None,
+                    commands: vec![
+                        req::CommandLink { command: run, tooltip: None },
+                        req::CommandLink { command: debug, tooltip: None },
+                    ],
+                });
+            }
+            HoverAction::GoToType(targets) => {
+                let mut commands = Vec::new();
+                for target in targets {
+                    let location: Location = (&target.nav).try_conv_with(world)?;
+                    let command = Command {
+                        title: target.nav.name().to_string(),
+                        command: "rust-analyzer.gotoLocation".into(),
+                        arguments: Some(vec![to_value(location).unwrap()]),
+                    };
+                    commands
+                        .push(req::CommandLink { command, tooltip: Some(target.mod_path.clone()) });
+                }
+                res.push(req::CommandLinkGroup { title: Some("Go to".into()), commands });
+            }
+        }
+    }
+    Ok(res)
 }
 
 pub fn handle_prepare_rename(
@@ -565,6 +819,64 @@ pub fn handle_rename(world: WorldSnapshot, params: RenameParams) -> Result<Optio
     Ok(Some(source_change_req.workspace_edit))
 }
 
+pub fn handle_will_rename_files(
+    world: WorldSnapshot,
+    params: req::RenameFilesParams,
+) -> Result<Option<WorkspaceEdit>> {
+    let _p = profile("handle_will_rename_files");
+
+    let mut source_file_edits = Vec::new();
+    let mut file_system_edits = Vec::new();
+    for file_rename in params.files {
+        let (file_id, new_path) = match module_file_move(&world, &file_rename) {
+            Some(it) => it,
+            None => continue,
+        };
+        if let Some(change) = world.analysis().will_move_file(file_id, &new_path)? {
+            source_file_edits.extend(change.source_file_edits);
+            // A renamed directory already takes the submodule files along.
+            let is_file = file_rename.old_uri.path().ends_with(".rs");
+            if is_file {
+                file_system_edits.extend(change.file_system_edits);
+            }
+        }
+    }
+    if source_file_edits.is_empty() && file_system_edits.is_empty() {
+        return Ok(None);
+    }
+
+    let change = SourceChange::from_edits("Rename", source_file_edits, file_system_edits);
+    let source_change_req = change.try_conv_with(&world)?;
+    Ok(Some(source_change_req.workspace_edit))
+}
+
+/// For a file or directory rename that renames or moves a module, the file of
+/// the module and its new path relative to its source root.
+fn module_file_move(
+    world: &WorldSnapshot,
+    file_rename: &req::FileRename,
+) -> Option<(FileId, RelativePathBuf)> {
+    let old_path = file_rename.old_uri.to_file_path().ok()?;
+    let new_path = file_rename.new_uri.to_file_path().ok()?;
+    let (module_file, new_module_file) = if old_path.extension() == Some("rs".as_ref()) {
+        // Renaming `mod.rs` itself changes which module it is, not its name.
+        if old_path.file_stem()? == "mod" && new_path.file_stem()? != "mod" {
+            return None;
+        }
+        (old_path, new_path)
+    } else {
+        // Moving a directory moves the module defined by its `mod.rs`.
+        (old_path.join("mod.rs"), new_path.join("mod.rs"))
+    };
+    let uri = Url::from_file_path(&module_file).ok()?;
+    let file_id = world.uri_to_file_id(&uri).ok()?;
+    // The source root is the module file's path minus its relative path.
+    let relative_path = world.analysis().file_relative_path(file_id).ok()?;
+    let root = module_file.ancestors().nth(relative_path.components().count())?;
+    let new_relative_path = RelativePathBuf::from_path(new_module_file.strip_prefix(root).ok()?);
+    Some((file_id, new_relative_path.ok()?))
+}
+
 pub fn handle_references(
     world: WorldSnapshot,
     params: req::ReferenceParams,
@@ -840,33 +1152,11 @@ pub fn handle_code_lens(
             RunnableKind::Bin => "Run",
         }
         .to_string();
-        let mut r = to_lsp_runnable(&world, file_id, runnable)?;
-        let lens = CodeLens {
-            range: r.range,
-            command: Some(Command {
-                title,
-                command: "rust-analyzer.runSingle".into(),
-                arguments: Some(vec![to_value(&r).unwrap()]),
-            }),
-            data: None,
-        };
-        lenses.push(lens);
-
-        if r.args[0] == "run" {
-            r.args[0] = "build".into();
-        } else {
-            r.args.push("--no-run".into());
-        }
-        let debug_lens = CodeLens {
-            range: r.range,
-            command: Some(Command {
-                title: "Debug".into(),
-                command: "rust-analyzer.debugSingle".into(),
-                arguments: Some(vec![to_value(r).unwrap()]),
-            }),
-            data: None,
-        };
-        lenses.push(debug_lens);
+        let r = to_lsp_runnable(&world, file_id, runnable)?;
+        let range = r.range;
+        let (run, debug) = run_and_debug_commands(r, title);
+        lenses.push(CodeLens { range, command: Some(run), data: None });
+        lenses.push(CodeLens { range, command: Some(debug), data: None });
     }
 
     // Handle impls
@@ -990,23 +1280,51 @@ pub fn handle_ssr(world: WorldSnapshot, params: req::SsrParams) -> Result<req::S
 pub fn publish_diagnostics(world: &WorldSnapshot, file_id: FileId) -> Result<DiagnosticTask> {
     let _p = profile("publish_diagnostics");
     let line_index = world.analysis().file_line_index(file_id)?;
-    let diagnostics: Vec<Diagnostic> = world
-        .analysis()
-        .diagnostics(file_id)?
-        .into_iter()
-        .map(|d| Diagnostic {
+    let mut diagnostics = Vec::new();
+    for d in world.analysis().diagnostics(file_id)? {
+        let related_information = if d.related.is_empty() {
+            None
+        } else {
+            let mut related = Vec::with_capacity(d.related.len());
+            for (nav, message) in d.related {
+                let location = (&nav).try_conv_with(world)?;
+                related.push(DiagnosticRelatedInformation { location, message });
+            }
+            Some(related)
+        };
+        diagnostics.push(Diagnostic {
             range: d.range.conv_with(&line_index),
             severity: Some(d.severity.conv()),
             code: None,
             source: Some("rust-analyzer".to_string()),
             message: d.message,
-            related_information: None,
+            related_information,
             tags: None,
-        })
-        .collect();
+        });
+    }
     Ok(DiagnosticTask::SetNative(file_id, diagnostics))
 }
 
+/// Commands running the runnable and building it for a debugger.
+fn run_and_debug_commands(mut r: req::Runnable, run_title: String) -> (Command, Command) {
+    let run = Command {
+        title: run_title,
+        command: "rust-analyzer.runSingle".into(),
+        arguments: Some(vec![to_value(&r).unwrap()]),
+    };
+    if r.args[0] == "run" {
+        r.args[0] = "build".into();
+    } else {
+        r.args.push("--no-run".into());
+    }
+    let debug = Command {
+        title: "Debug".into(),
+        command: "rust-analyzer.debugSingle".into(),
+        arguments: Some(vec![to_value(r).unwrap()]),
+    };
+    (run, debug)
+}
+
 fn to_lsp_runnable(
     world: &WorldSnapshot,
     file_id: FileId,
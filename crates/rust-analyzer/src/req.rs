@@ -1,6 +1,6 @@
 //! Defines `rust-analyzer` specific custom messages.
 
-use lsp_types::{Location, Position, Range, TextDocumentIdentifier};
+use lsp_types::{Command, Location, Position, Range, TextDocumentIdentifier, Url};
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
@@ -73,6 +73,64 @@ pub struct ExpandMacroParams {
     pub position: Option<Position>,
 }
 
+pub enum RevealType {}
+
+impl Request for RevealType {
+    type Params = TextDocumentPositionParams;
+    type Result = Option<RevealedType>;
+    const METHOD: &'static str = "rust-analyzer/revealType";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevealedType {
+    pub range: Range,
+    pub opaque: String,
+    pub hidden: String,
+}
+
+pub enum TraceTraitSolve {}
+
+impl Request for TraceTraitSolve {
+    type Params = TextDocumentPositionParams;
+    type Result = Option<String>;
+    const METHOD: &'static str = "rust-analyzer/traceTraitSolve";
+}
+
+pub enum TypeHierarchy {}
+
+impl Request for TypeHierarchy {
+    type Params = TextDocumentPositionParams;
+    type Result = Option<TypeHierarchyResult>;
+    const METHOD: &'static str = "rust-analyzer/typeHierarchy";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeHierarchyResult {
+    pub range: Range,
+    pub item: TypeHierarchyItem,
+    pub supertraits: Vec<TypeHierarchyItem>,
+    pub subtraits: Vec<TypeHierarchyItem>,
+    pub implementors: Vec<TypeHierarchyItem>,
+    pub implemented_traits: Vec<CrateTraits>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeHierarchyItem {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub location: Location,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CrateTraits {
+    pub crate_name: Option<String>,
+    pub traits: Vec<TypeHierarchyItem>,
+}
+
 pub enum FindMatchingBrace {}
 
 impl Request for FindMatchingBrace {
@@ -96,6 +154,43 @@ impl Request for ParentModule {
     const METHOD: &'static str = "rust-analyzer/parentModule";
 }
 
+pub enum HoverWithActions {}
+
+impl Request for HoverWithActions {
+    type Params = TextDocumentPositionParams;
+    type Result = Option<HoverWithActionsResult>;
+    const METHOD: &'static str = "rust-analyzer/hover";
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HoverWithActionsResult {
+    #[serde(flatten)]
+    pub hover: Hover,
+    pub actions: Vec<CommandLinkGroup>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommandLinkGroup {
+    pub title: Option<String>,
+    pub commands: Vec<CommandLink>,
+}
+
+/// A command rendered as a link, with an optional tooltip.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommandLink {
+    #[serde(flatten)]
+    pub command: Command,
+    pub tooltip: Option<String>,
+}
+
+pub enum OpenDocs {}
+
+impl Request for OpenDocs {
+    type Params = TextDocumentPositionParams;
+    type Result = Option<Url>;
+    const METHOD: &'static str = "rust-analyzer/openDocs";
+}
+
 pub enum JoinLines {}
 
 impl Request for JoinLines {
@@ -154,6 +249,48 @@ pub struct SourceChange {
     pub cursor_position: Option<TextDocumentPositionParams>,
 }
 
+pub enum WillRenameFiles {}
+
+impl Request for WillRenameFiles {
+    type Params = RenameFilesParams;
+    type Result = Option<WorkspaceEdit>;
+    const METHOD: &'static str = "workspace/willRenameFiles";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameFilesParams {
+    pub files: Vec<FileRename>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRename {
+    pub old_uri: Url,
+    pub new_uri: Url,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOperationRegistrationOptions {
+    pub filters: Vec<FileOperationFilter>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOperationFilter {
+    pub scheme: Option<String>,
+    pub pattern: FileOperationPattern,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOperationPattern {
+    pub glob: String,
+    /// Either `"file"` or `"folder"`.
+    pub matches: Option<String>,
+}
+
 pub enum InlayHints {}
 
 impl Request for InlayHints {
@@ -173,6 +310,7 @@ pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    BindingModeHint,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
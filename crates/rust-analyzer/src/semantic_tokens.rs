@@ -67,6 +67,11 @@ define_semantic_token_modifiers![
     (CONTROL_FLOW, "controlFlow"),
     (MUTABLE, "mutable"),
     (UNSAFE, "unsafe"),
+    (CONSUMING, "consuming"),
+    (MUTABLE_REFERENCE, "mutableReference"),
+    (CALLABLE, "callable"),
+    (ASSOCIATED, "associated"),
+    (LIBRARY, "library"),
 ];
 
 #[derive(Default)]
@@ -179,6 +179,7 @@ impl WorldState {
         let flycheck = config.check.as_ref().and_then(|c| create_flycheck(&workspaces, c));
 
         let mut analysis_host = AnalysisHost::new(lru_capacity);
+        analysis_host.update_trait_solver_config(config.trait_solver);
         analysis_host.apply_change(change);
         WorldState {
             config,
@@ -196,6 +197,9 @@ impl WorldState {
 
     pub fn update_configuration(&mut self, config: Config) {
         self.analysis_host.update_lru_capacity(config.lru_capacity);
+        if config.trait_solver != self.config.trait_solver {
+            self.analysis_host.update_trait_solver_config(config.trait_solver);
+        }
         if config.check != self.config.check {
             self.flycheck =
                 config.check.as_ref().and_then(|it| create_flycheck(&self.workspaces, it));
@@ -3,13 +3,14 @@ mod support;
 use std::{collections::HashMap, path::PathBuf, time::Instant};
 
 use lsp_types::{
-    CodeActionContext, DidOpenTextDocumentParams, DocumentFormattingParams, FormattingOptions,
-    GotoDefinitionParams, HoverParams, PartialResultParams, Position, Range, TextDocumentItem,
-    TextDocumentPositionParams, WorkDoneProgressParams,
+    CodeActionContext, CompletionItem, DidOpenTextDocumentParams, DocumentFormattingParams,
+    FormattingOptions, GotoDefinitionParams, HoverParams, PartialResultParams, Position, Range,
+    TextDocumentItem, TextDocumentPositionParams, WorkDoneProgressParams,
 };
 use rust_analyzer::req::{
     CodeActionParams, CodeActionRequest, Completion, CompletionParams, DidOpenTextDocument,
-    Formatting, GotoDefinition, HoverRequest, OnEnter, Runnables, RunnablesParams,
+    Formatting, GotoDefinition, HoverRequest, OnEnter, ResolveCompletionItem, Runnables,
+    RunnablesParams,
 };
 use serde_json::json;
 use tempfile::TempDir;
@@ -56,6 +57,56 @@ use std::collections::Spam;
     eprintln!("completion took {:?}", completion_start.elapsed());
 }
 
+#[test]
+fn resolves_imports_of_completion_items() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = Project::with_fixture(
+        r#"
+//- Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+//- src/lib.rs
+mod bar;
+fn f() {
+    let _ = Spa
+}
+
+//- src/bar.rs
+/// A tin of spam.
+pub struct Spam;
+"#,
+    )
+    .with_config(|config| config.completion.resolve_lazily = true)
+    .server();
+    server.wait_until_workspace_is_loaded();
+    let res = server.send_request::<Completion>(CompletionParams {
+        text_document_position: TextDocumentPositionParams::new(
+            server.doc_id("src/lib.rs"),
+            Position::new(2, 15),
+        ),
+        context: None,
+        partial_result_params: PartialResultParams::default(),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+    });
+    let items: Vec<CompletionItem> = serde_json::from_value(res).unwrap();
+    let item = items.into_iter().find(|it| it.label == "Spam (use bar::Spam)").unwrap();
+    assert!(item.additional_text_edits.is_none());
+    assert!(item.documentation.is_none());
+    assert!(item.data.is_some());
+
+    let res = server.send_request::<ResolveCompletionItem>(item);
+    let item: CompletionItem = serde_json::from_value(res).unwrap();
+    assert!(item.documentation.is_some());
+    let edits = item.additional_text_edits.unwrap();
+    assert_eq!(edits.len(), 1);
+    assert!(edits[0].new_text.contains("use bar::Spam;"));
+}
+
 #[test]
 fn test_runnables_no_project() {
     if skip_slow_tests() {
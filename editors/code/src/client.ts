@@ -1,5 +1,6 @@
 import * as lc from 'vscode-languageclient';
 import * as vscode from 'vscode';
+import * as ra from './rust-analyzer-api';
 
 import { CallHierarchyFeature } from 'vscode-languageclient/lib/callHierarchy.proposed';
 import { SemanticTokensFeature, DocumentSemanticsTokensSignature } from 'vscode-languageclient/lib/semanticTokens.proposed';
@@ -26,6 +27,24 @@ export function createClient(serverPath: string, cwd: string): lc.LanguageClient
         initializationOptions: vscode.workspace.getConfiguration("rust-analyzer"),
         traceOutputChannel,
         middleware: {
+            // Ask for the hover with actions, and render the actions as command links.
+            async provideHover(document: vscode.TextDocument, position: vscode.Position, token: vscode.CancellationToken, _next: lc.ProvideHoverSignature) {
+                const params = res.code2ProtocolConverter.asTextDocumentPositionParams(document, position);
+                return res.sendRequest(ra.hover, params, token).then(
+                    (result) => {
+                        if (result == null) return null;
+                        const hover = res.protocol2CodeConverter.asHover(result);
+                        if (result.actions.length > 0) {
+                            hover.contents.push(renderHoverActions(result.actions));
+                        }
+                        return hover;
+                    },
+                    (error) => {
+                        res.logFailedRequest(lc.HoverRequest.type, error);
+                        return null;
+                    },
+                );
+            },
             // Workaround for https://github.com/microsoft/vscode-languageserver-node/issues/576
             async provideDocumentSemanticTokens(document: vscode.TextDocument, token: vscode.CancellationToken, next: DocumentSemanticsTokensSignature) {
                 const res = await next(document, token);
@@ -52,3 +71,17 @@ export function createClient(serverPath: string, cwd: string): lc.LanguageClient
 
     return res;
 }
+
+function renderCommand(cmd: ra.CommandLink) {
+    const args = encodeURIComponent(JSON.stringify(cmd.arguments));
+    return `[${cmd.title}](command:${cmd.command}?${args} '${cmd.tooltip || cmd.title}')`;
+}
+
+function renderHoverActions(actions: ra.CommandLinkGroup[]): vscode.MarkdownString {
+    const text = actions
+        .map(group => (group.title ? group.title + ' ' : '') + group.commands.map(renderCommand).join(' | '))
+        .join('\n\n___\n\n');
+    const result = new vscode.MarkdownString(text);
+    result.isTrusted = true;
+    return result;
+}
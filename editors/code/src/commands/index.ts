@@ -10,8 +10,11 @@ export * from './matching_brace';
 export * from './join_lines';
 export * from './on_enter';
 export * from './parent_module';
+export * from './open_docs';
 export * from './syntax_tree';
 export * from './expand_macro';
+export * from './reveal_type';
+export * from './trace_trait_solve';
 export * from './runnables';
 export * from './ssr';
 export * from './server_version';
@@ -34,6 +37,20 @@ export function showReferences(ctx: Ctx): Cmd {
     };
 }
 
+export function gotoLocation(ctx: Ctx): Cmd {
+    return async (location: lc.Location) => {
+        const client = ctx.client;
+        if (!client) return;
+
+        const uri = client.protocol2CodeConverter.asUri(location.uri);
+        const range = client.protocol2CodeConverter.asRange(location.range);
+        const doc = await vscode.workspace.openTextDocument(uri);
+        const e = await vscode.window.showTextDocument(doc);
+        e.selection = new vscode.Selection(range.start, range.start);
+        e.revealRange(range, vscode.TextEditorRevealType.InCenter);
+    };
+}
+
 export function applySourceChange(ctx: Ctx): Cmd {
     return async (change: ra.SourceChange) => {
         await sourceChange.applySourceChange(ctx, change);
@@ -0,0 +1,29 @@
+import * as vscode from 'vscode';
+import * as ra from '../rust-analyzer-api';
+
+import { Ctx, Cmd } from '../ctx';
+
+// Shows the concrete types hidden behind the `impl Trait`s in the type of the
+// expression under the cursor.
+export function revealType(ctx: Ctx): Cmd {
+    return async () => {
+        const editor = ctx.activeRustEditor;
+        const client = ctx.client;
+        if (!editor || !client) return;
+
+        const revealed = await client.sendRequest(ra.revealType, {
+            textDocument: { uri: editor.document.uri.toString() },
+            position: client.code2ProtocolConverter.asPosition(
+                editor.selection.active,
+            ),
+        });
+        if (revealed == null) {
+            void vscode.window.showInformationMessage('No `impl Trait` to reveal here');
+            return;
+        }
+
+        void vscode.window.showInformationMessage(
+            `${revealed.opaque} is ${revealed.hidden}`
+        );
+    };
+}
@@ -97,6 +97,7 @@ export class Config {
             typeHints: this.get<boolean>("inlayHints.typeHints"),
             parameterHints: this.get<boolean>("inlayHints.parameterHints"),
             chainingHints: this.get<boolean>("inlayHints.chainingHints"),
+            bindingModeHints: this.get<boolean>("inlayHints.bindingModeHints"),
             maxLength: this.get<null | number>("inlayHints.maxLength"),
         };
     }
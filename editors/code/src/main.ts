@@ -75,8 +75,11 @@ export async function activate(context: vscode.ExtensionContext) {
     ctx.registerCommand('matchingBrace', commands.matchingBrace);
     ctx.registerCommand('joinLines', commands.joinLines);
     ctx.registerCommand('parentModule', commands.parentModule);
+    ctx.registerCommand('openDocs', commands.openDocs);
     ctx.registerCommand('syntaxTree', commands.syntaxTree);
     ctx.registerCommand('expandMacro', commands.expandMacro);
+    ctx.registerCommand('revealType', commands.revealType);
+    ctx.registerCommand('traceTraitSolve', commands.traceTraitSolve);
     ctx.registerCommand('run', commands.run);
 
     defaultOnEnter.dispose();
@@ -89,6 +92,7 @@ export async function activate(context: vscode.ExtensionContext) {
     ctx.registerCommand('runSingle', commands.runSingle);
     ctx.registerCommand('debugSingle', commands.debugSingle);
     ctx.registerCommand('showReferences', commands.showReferences);
+    ctx.registerCommand('gotoLocation', commands.gotoLocation);
     ctx.registerCommand('applySourceChange', commands.applySourceChange);
     ctx.registerCommand('selectAndApplySourceChange', commands.selectAndApplySourceChange);
 
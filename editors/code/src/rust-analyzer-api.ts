@@ -40,6 +40,16 @@ export interface ExpandedMacro {
 export const expandMacro = request<ExpandMacroParams, Option<ExpandedMacro>>("expandMacro");
 
 
+export interface RevealedType {
+    range: lc.Range;
+    opaque: string;
+    hidden: string;
+}
+export const revealType = request<lc.TextDocumentPositionParams, Option<RevealedType>>("revealType");
+
+export const traceTraitSolve = request<lc.TextDocumentPositionParams, Option<string>>("traceTraitSolve");
+
+
 export interface FindMatchingBraceParams {
     textDocument: lc.TextDocumentIdentifier;
     offsets: Vec<lc.Position>;
@@ -61,6 +71,24 @@ export const decorationsRequest = request<lc.TextDocumentIdentifier, Vec<Decorat
 
 export const parentModule = request<lc.TextDocumentPositionParams, Vec<lc.Location>>("parentModule");
 
+export const openDocs = request<lc.TextDocumentPositionParams, Option<string>>("openDocs");
+
+
+export interface CommandLink extends lc.Command {
+    /**
+     * A tooltip for the command, when represented in the UI.
+     */
+    tooltip?: string;
+}
+export interface CommandLinkGroup {
+    title?: string;
+    commands: CommandLink[];
+}
+export interface HoverWithActions extends lc.Hover {
+    actions: CommandLinkGroup[];
+}
+export const hover = request<lc.TextDocumentPositionParams, Option<HoverWithActions>>("hover");
+
 
 export interface JoinLinesParams {
     textDocument: lc.TextDocumentIdentifier;
@@ -86,13 +114,14 @@ export interface Runnable {
 }
 export const runnables = request<RunnablesParams, Vec<Runnable>>("runnables");
 
-export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint;
+export type InlayHint = InlayHint.TypeHint | InlayHint.ParamHint | InlayHint.ChainingHint | InlayHint.BindingModeHint;
 
 export namespace InlayHint {
     export const enum Kind {
         TypeHint = "TypeHint",
         ParamHint = "ParameterHint",
         ChainingHint = "ChainingHint",
+        BindingModeHint = "BindingModeHint",
     }
     interface Common {
         range: lc.Range;
@@ -101,6 +130,7 @@ export namespace InlayHint {
     export type TypeHint = Common & { kind: Kind.TypeHint };
     export type ParamHint = Common & { kind: Kind.ParamHint };
     export type ChainingHint = Common & { kind: Kind.ChainingHint };
+    export type BindingModeHint = Common & { kind: Kind.BindingModeHint };
 }
 export interface InlayHintsParams {
     textDocument: lc.TextDocumentIdentifier;
@@ -68,7 +68,7 @@ pub(crate) const KINDS_SRC: KindsSrc = KindsSrc {
         "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else",
         "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "macro",
         "match", "mod", "move", "mut", "pub", "ref", "return", "self", "static", "struct", "super",
-        "trait", "true", "try", "type", "unsafe", "use", "where", "while",
+        "trait", "true", "try", "type", "unsafe", "use", "where", "while", "yield",
     ],
     contextual_keywords: &["auto", "default", "existential", "union", "raw"],
     literals: &[
@@ -155,6 +155,7 @@ pub(crate) const KINDS_SRC: KindsSrc = KindsSrc {
         "LABEL",
         "BLOCK_EXPR",
         "RETURN_EXPR",
+        "YIELD_EXPR",
         "MATCH_EXPR",
         "MATCH_ARM_LIST",
         "MATCH_ARM",
@@ -164,6 +165,7 @@ pub(crate) const KINDS_SRC: KindsSrc = KindsSrc {
         "RECORD_FIELD",
         "EFFECT_EXPR",
         "BOX_EXPR",
+        "UNDERSCORE_EXPR",
         // postfix
         "CALL_EXPR",
         "INDEX_EXPR",
@@ -454,6 +456,7 @@ pub(crate) const AST_SRC: AstSrc = AstSrc {
             T!['{'], statements: [Stmt], Expr, T!['}'],
         }
         struct ReturnExpr: AttrsOwner { Expr }
+        struct YieldExpr: AttrsOwner { Expr }
         struct CallExpr: ArgListOwner { Expr }
         struct MethodCallExpr: AttrsOwner, ArgListOwner {
             Expr, T![.], NameRef, TypeArgList,
@@ -466,6 +469,7 @@ pub(crate) const AST_SRC: AstSrc = AstSrc {
         struct RefExpr: AttrsOwner { T![&], T![raw], T![mut], Expr }
         struct PrefixExpr: AttrsOwner { /*PrefixOp,*/ Expr }
         struct BoxExpr: AttrsOwner { T![box], Expr }
+        struct UnderscoreExpr: AttrsOwner { T![_] }
         struct RangeExpr: AttrsOwner { /*RangeOp*/ }
         struct BinExpr: AttrsOwner { /*BinOp*/ }
         struct Literal { /*LiteralToken*/ }
@@ -709,6 +713,7 @@ pub(crate) const AST_SRC: AstSrc = AstSrc {
             Label,
             BlockExpr,
             ReturnExpr,
+            YieldExpr,
             MatchExpr,
             RecordLit,
             CallExpr,
@@ -726,6 +731,7 @@ pub(crate) const AST_SRC: AstSrc = AstSrc {
             Literal,
             MacroCall,
             BoxExpr,
+            UnderscoreExpr,
         }
 
         enum Pat {